@@ -5,29 +5,30 @@ use std::{
 };
 
 use anyhow::anyhow;
-use crosscut_compiler::{Compiler, CompilerOutput};
+use crosscut_compiler::{
+    code::syntax::Module,
+    sources::{module_file_name, search_path, SourceFile, Sources},
+    standard_library, Compiler, CompilerOutput,
+};
 use crosscut_game_engine::host::GameEngineHost;
 use crosscut_protocol::Versioned;
-use crosscut_watch::DebouncedChanges;
+use crosscut_watch::Watcher;
 use tokio::{fs, sync::mpsc, task};
 
 pub async fn build_game_once(
     game_dir: &Path,
-) -> Result<CompilerOutput, BuildGameOnceError> {
+) -> Result<GameBuild, BuildGameOnceError> {
     let mut compiler = Compiler::default();
-    let output = build_game_once_with_compiler(game_dir, &mut compiler).await?;
-    Ok(output)
+    let build = build_game_once_with_compiler(game_dir, &mut compiler).await?;
+    Ok(build)
 }
 
-pub fn build_and_watch_game(
-    game_dir: PathBuf,
-    changes: DebouncedChanges,
-) -> EventsRx {
+pub fn build_and_watch_game(game_dir: PathBuf, watcher: Watcher) -> EventsRx {
     let (events_tx, events_rx) = mpsc::channel(1);
 
     task::spawn(async move {
         if let Err(err) =
-            build_and_watch_game_inner(&game_dir, changes, events_tx).await
+            build_and_watch_game_inner(&game_dir, watcher, events_tx).await
         {
             tracing::error!("Error building and watching game: {err}");
 
@@ -43,12 +44,32 @@ pub type EventsRx = mpsc::Receiver<Event>;
 
 pub enum Event {
     ChangeDetected,
-    BuildFinished(Versioned<CompilerOutput>),
+    BuildFinished {
+        code: Box<Versioned<CompilerOutput>>,
+        diagnostics: String,
+    },
+}
+
+/// # The result of building a game
+pub struct GameBuild {
+    /// # The output of the compiler
+    pub code: CompilerOutput,
+
+    /// # The diagnostics produced by the compiler, rendered for the terminal
+    ///
+    /// This is empty, if the compiler didn't produce any diagnostics.
+    pub diagnostics: String,
+
+    /// # The paths of all source files that are involved in the build
+    ///
+    /// This includes the paths where source files for imported modules were
+    /// looked for, but not found.
+    pub files: Vec<PathBuf>,
 }
 
 async fn build_and_watch_game_inner(
     game_dir: &Path,
-    mut changes: DebouncedChanges,
+    mut watcher: Watcher,
     events: mpsc::Sender<Event>,
 ) -> anyhow::Result<()> {
     let mut compiler = Compiler::default();
@@ -62,10 +83,10 @@ async fn build_and_watch_game_inner(
             return Ok(());
         }
 
-        let code = match build_game_once_with_compiler(game_dir, &mut compiler)
+        let build = match build_game_once_with_compiler(game_dir, &mut compiler)
             .await
         {
-            Ok(code) => code,
+            Ok(build) => build,
             Err(err) => match err.source.kind() {
                 io::ErrorKind::NotFound => {
                     // Depending on the editor, this can happen while the file
@@ -90,18 +111,25 @@ async fn build_and_watch_game_inner(
 
         ignored_error = None;
 
+        watcher.watch_files(&build.files)?;
+
         timestamp.update();
 
-        let code = Versioned {
+        let code = Box::new(Versioned {
             timestamp: timestamp.0,
-            inner: code,
-        };
-        if events.send(Event::BuildFinished(code)).await.is_err() {
+            inner: build.code,
+        });
+        let diagnostics = build.diagnostics;
+        if events
+            .send(Event::BuildFinished { code, diagnostics })
+            .await
+            .is_err()
+        {
             // Receiver dropped. We must be in the process of shutting down.
             return Ok(());
         }
 
-        if changes.wait_for_change().await {
+        if watcher.changes.wait_for_change().await {
             continue;
         } else {
             break;
@@ -114,14 +142,85 @@ async fn build_and_watch_game_inner(
 async fn build_game_once_with_compiler(
     game_dir: &Path,
     compiler: &mut Compiler,
-) -> Result<CompilerOutput, BuildGameOnceError> {
-    let path = game_dir.join("main.capi");
-    let source = fs::read_to_string(&path)
-        .await
-        .map_err(|source| BuildGameOnceError { source, path })?;
-    let output = compiler.compile(&source, &GameEngineHost);
+) -> Result<GameBuild, BuildGameOnceError> {
+    let (sources, files) = load_sources(game_dir).await?;
+
+    let code = compiler.compile_sources(&sources, &GameEngineHost);
+    let diagnostics = code.diagnostics.display(&sources).to_string();
+
+    Ok(GameBuild {
+        code,
+        diagnostics,
+        files,
+    })
+}
+
+/// # Load the source files of a game
+///
+/// Starts with the main module, then loads any modules it imports, directly or
+/// indirectly. Modules are looked for in the game directory first, then in its
+/// parent directory, where modules that are shared between games can live.
+///
+/// Returns the loaded source files, as well as the paths of all files that are
+/// involved in the build.
+pub async fn load_sources(
+    game_dir: &Path,
+) -> Result<(Sources, Vec<PathBuf>), BuildGameOnceError> {
+    let path = game_dir.join(module_file_name(Module::MAIN));
+    let code = read_source(&path).await?;
+
+    let main = SourceFile::main(path.clone(), code);
+
+    let mut files = vec![path];
+    let mut sources = Sources::new(main);
+
+    // The standard library is included in the compiler. If we're running from
+    // within the Crosscut repository, load it from there instead. That way,
+    // changes to it are picked up like changes to any other code.
+    let standard_library_files = sources
+        .iter()
+        .filter(|(_, file)| standard_library::contains(&file.module))
+        .map(|(module, file)| (module, file.path.clone()))
+        .collect::<Vec<_>>();
+    for (module, path) in standard_library_files {
+        let Ok(code) = fs::read_to_string(&path).await else {
+            continue;
+        };
+
+        if let Some(file) = sources.get_mut(&module) {
+            file.code = code;
+        }
+        files.push(path);
+    }
+
+    // Module files are small, so reading them synchronously doesn't hold up
+    // anything else for long.
+    let imported_files =
+        sources.load_imports(&search_path(game_dir), |path| {
+            match std::fs::read_to_string(path) {
+                Ok(code) => Ok(Some(code)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(source) => Err(BuildGameOnceError {
+                    source,
+                    path: path.to_path_buf(),
+                }),
+            }
+        })?;
+    files.extend(imported_files);
+
+    files.sort();
+    files.dedup();
 
-    Ok(output)
+    Ok((sources, files))
+}
+
+async fn read_source(path: &Path) -> Result<String, BuildGameOnceError> {
+    fs::read_to_string(path)
+        .await
+        .map_err(|source| BuildGameOnceError {
+            source,
+            path: path.to_path_buf(),
+        })
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use tokio::fs;
+
+use crate::build_game::{build_game_once, GameBuild};
+
+pub async fn run(games_path: PathBuf) -> anyhow::Result<()> {
+    let mut games_with_errors = Vec::new();
+
+    for game_dir in find_games(&games_path).await? {
+        let name = game_name(&game_dir);
+
+        println!("🔍 Checking `{name}`...");
+
+        let GameBuild {
+            code, diagnostics, ..
+        } = build_game_once(&game_dir).await?;
+
+        if !diagnostics.is_empty() {
+            println!("\n{diagnostics}");
+        }
+
+        // Any error means that the affected code triggers a build error, if it
+        // is ever executed.
+        let num_errors = code.diagnostics.errors().count();
+        if num_errors > 0 {
+            println!("❌ Found {num_errors} error(s) in `{name}`.");
+            games_with_errors.push(name);
+        } else {
+            println!("✅ No errors in `{name}`.");
+        }
+    }
+
+    if !games_with_errors.is_empty() {
+        return Err(anyhow!(
+            "Check failed for the following games: {}",
+            games_with_errors.join(", "),
+        ));
+    }
+
+    Ok(())
+}
+
+/// # Find the directories of all games in the provided directory
+///
+/// The directories are sorted, to make any output that is based on them
+/// independent of the order the file system returns them in.
+pub async fn find_games(games_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut game_dirs = Vec::new();
+
+    let mut entries = fs::read_dir(games_path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            // Modules that are shared between games can live here too.
+            continue;
+        }
+
+        let path = entry.path();
+
+        if fs::try_exists(path.join("main.capi")).await? {
+            game_dirs.push(path);
+        }
+    }
+
+    game_dirs.sort();
+
+    Ok(game_dirs)
+}
+
+pub fn game_name(game_dir: &Path) -> String {
+    game_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| game_dir.display().to_string())
+}
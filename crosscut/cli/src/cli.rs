@@ -3,7 +3,7 @@ use std::{fmt::Write, net::SocketAddr, path::PathBuf};
 use anyhow::anyhow;
 use clap::Parser;
 
-use crate::{export::export, files, headless, server};
+use crate::{check, export::export, files, fmt, headless, server};
 
 pub async fn run() -> anyhow::Result<()> {
     tracing_subscriber::fmt().init();
@@ -11,12 +11,22 @@ pub async fn run() -> anyhow::Result<()> {
     let args = Args::parse();
 
     match args.command {
+        Command::Check => {
+            check::run(args.games).await?;
+        }
+        Command::Fmt { check } => {
+            fmt::run(args.games, check).await?;
+        }
         Command::Export { path } => {
             check_files()?;
             export(args.games, path).await?;
         }
-        Command::Headless => {
-            headless::run(args.games).await?;
+        Command::Headless {
+            record,
+            replay,
+            seed,
+        } => {
+            headless::run(args.games, record, replay, seed).await?;
         }
         Command::Serve { address } => {
             check_files()?;
@@ -31,7 +41,10 @@ pub async fn run() -> anyhow::Result<()> {
                             ⏳ Change detected. Building game...\n"
                         );
                     }
-                    server::Event::BuildFinished => {
+                    server::Event::BuildFinished { diagnostics } => {
+                        if !diagnostics.is_empty() {
+                            println!("{diagnostics}");
+                        }
                         println!("✅ Finished building game.");
                     }
                     server::Event::ServerReady => {
@@ -62,11 +75,32 @@ struct Args {
 
 #[derive(clap::Subcommand)]
 enum Command {
+    /// Compile all games and report any problems, without running them
+    Check,
     Export {
         #[arg(short, long)]
         path: PathBuf,
     },
-    Headless,
+    /// Format the code of all games
+    Fmt {
+        /// Don't write any files, but fail if any are not formatted
+        #[arg(long)]
+        check: bool,
+    },
+    /// Run the game without displaying it, measuring its performance
+    Headless {
+        /// Record the session to a file, for later replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+
+        /// Replay a recorded session, verifying the game behaves the same way
+        #[arg(long, conflicts_with = "record")]
+        replay: Option<PathBuf>,
+
+        /// Seed the random number generator, instead of picking a random seed
+        #[arg(long, conflicts_with = "replay")]
+        seed: Option<u64>,
+    },
     Serve {
         /// Address to serve at
         #[arg(short, long, default_value = "127.0.0.1:34480")]
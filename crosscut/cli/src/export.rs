@@ -66,9 +66,9 @@ async fn export_game_code(
     game_dir: &Path,
     target_path: &Path,
 ) -> anyhow::Result<()> {
-    let compiler_output = build_game_once(game_dir).await?;
+    let build = build_game_once(game_dir).await?;
     let command = Command::UpdateCode {
-        instructions: compiler_output.instructions,
+        instructions: build.code.instructions,
     }
     .serialize();
 
@@ -0,0 +1,79 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::anyhow;
+use crosscut_compiler::{
+    format::{format_source, FormatError},
+    sources::{SourceFile, Sources},
+    standard_library,
+};
+use tokio::fs;
+
+use crate::{build_game::load_sources, check::find_games};
+
+pub async fn run(games_path: PathBuf, check: bool) -> anyhow::Result<()> {
+    let mut files = BTreeMap::new();
+
+    // Modules can be shared between games. Collect the source files of all
+    // games first, so each is only formatted once.
+    for game_dir in find_games(&games_path).await? {
+        let (sources, _) = load_sources(&game_dir).await?;
+        files.extend(
+            sources
+                .iter()
+                // The standard library is formatted as part of the compiler.
+                .filter(|(_, file)| !standard_library::contains(&file.module))
+                .map(|(_, file)| (file.path.clone(), file.clone())),
+        );
+    }
+
+    let mut failed = Vec::new();
+
+    for file in files.into_values() {
+        let path = file.path.display().to_string();
+
+        let formatted = match format_source(&file.code) {
+            Ok(formatted) => formatted,
+            Err(FormatError::SyntaxErrors(diagnostics)) => {
+                let sources = Sources::new(SourceFile {
+                    module: file.module.clone(),
+                    path: file.path.clone(),
+                    code: file.code.clone(),
+                });
+
+                println!(
+                    "❌ Can't format `{path}`, due to syntax errors:\n\n{}",
+                    diagnostics.display(&sources),
+                );
+                failed.push(path);
+                continue;
+            }
+            Err(err) => {
+                println!("❌ Can't format `{path}`: {err}");
+                failed.push(path);
+                continue;
+            }
+        };
+
+        if formatted == file.code {
+            println!("✅ `{path}` is formatted.");
+            continue;
+        }
+
+        if check {
+            println!("❌ `{path}` is not formatted.");
+            failed.push(path);
+        } else {
+            fs::write(&file.path, formatted).await?;
+            println!("📝 Formatted `{path}`.");
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(anyhow!(
+            "Formatting failed for the following files: {}",
+            failed.join(", "),
+        ));
+    }
+
+    Ok(())
+}
@@ -1,22 +1,67 @@
-use std::{path::PathBuf, time::Instant};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
+use crosscut_compiler::CompilerOutput;
 use crosscut_game_engine::{
-    command::Command, display::NUM_PIXEL_BYTES, game_engine::GameEngine,
+    command::Command,
+    display::NUM_PIXEL_BYTES,
+    game_engine::GameEngine,
+    recording::{RecordedEvent, Replay},
 };
 use rand::random;
 
-use crate::build_game::build_game_once;
-
-pub async fn run(games_path: PathBuf) -> anyhow::Result<()> {
-    let code = build_game_once(&games_path.join("snake")).await?;
+use crate::build_game::{build_game_once, GameBuild};
+
+pub async fn run(
+    games_path: PathBuf,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    seed: Option<u64>,
+) -> anyhow::Result<()> {
+    let GameBuild {
+        code, diagnostics, ..
+    } = build_game_once(&games_path.join("snake")).await?;
+    if !diagnostics.is_empty() {
+        eprintln!("{diagnostics}");
+    }
 
-    let mut pixels = [0; NUM_PIXEL_BYTES];
     let mut game_engine = GameEngine::new();
 
     game_engine.on_command(Command::UpdateCode {
-        instructions: code.instructions,
+        instructions: code.instructions.clone(),
     });
 
+    if let Some(path) = replay {
+        return replay_recording(&path, &mut game_engine);
+    }
+
+    // Print the seed, so a run can be reproduced, even if it wasn't recorded.
+    let seed = seed.unwrap_or_else(random);
+    eprintln!("Seed: {seed}");
+    game_engine.on_command(Command::SetSeed { seed });
+
+    let mut recording = match record {
+        Some(path) => {
+            game_engine.start_recording();
+            Some(BufWriter::new(File::create(path)?))
+        }
+        None => None,
+    };
+
+    run_game(&code, &mut game_engine, recording.as_mut())
+}
+
+fn run_game(
+    code: &CompilerOutput,
+    game_engine: &mut GameEngine,
+    mut recording: Option<&mut BufWriter<File>>,
+) -> anyhow::Result<()> {
+    let mut pixels = [0; NUM_PIXEL_BYTES];
+
     let start_of_game = Instant::now();
     let mut start_of_loop;
     let mut start_of_frame = Instant::now();
@@ -27,19 +72,36 @@ pub async fn run(games_path: PathBuf) -> anyhow::Result<()> {
     while !game_engine.runtime.state().has_finished() {
         start_of_loop = Instant::now();
 
-        while game_engine.push_random(random()) {}
-
-        if !game_engine.run_until_end_of_frame(
+        let finished_frame = game_engine.run_until_end_of_frame(
             start_of_game.elapsed().as_secs_f64(),
             &mut pixels,
-        ) {
-            // Game engine decided that it's not time to run another frame yet.
+        );
+
+        if let Some(recording) = &mut recording {
+            // Write the events as we go, so the recording is complete, even if
+            // the process gets killed.
+            for event in game_engine.take_recorded_events() {
+                writeln!(recording, "{}", ron::to_string(&event)?)?;
+            }
+            recording.flush()?;
+        }
+
+        if !finished_frame {
+            // Game engine decided that it's not time to run another frame yet,
+            // or it yielded before finishing the current one.
             continue;
         }
 
         if let Some(effect) = game_engine.runtime.effect().inspect() {
             eprintln!("Unhandled effect: {effect:#?}");
             eprintln!("Current stack:\n{:#?}", game_engine.runtime.stack());
+
+            let call_chain = code.call_chain(
+                game_engine.runtime.evaluator().active_instructions(),
+            );
+            eprintln!("Call chain, outermost first:");
+            print_call_chain(call_chain);
+
             break;
         }
 
@@ -76,6 +138,51 @@ pub async fn run(games_path: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn replay_recording(
+    path: &Path,
+    game_engine: &mut GameEngine,
+) -> anyhow::Result<()> {
+    let mut replay = Replay::new();
+
+    for line in BufReader::new(File::open(path)?).lines() {
+        let event: RecordedEvent = ron::from_str(&line?)?;
+        replay.replay_event(event, game_engine)?;
+    }
+
+    eprintln!(
+        "✅ Replayed {} frames, matching the recording.",
+        replay.num_frames()
+    );
+
+    Ok(())
+}
+
+fn print_call_chain(call_chain: Vec<String>) {
+    // After a stack overflow, the call chain tends to consist of the same
+    // recursive function, over and over. Collapse those repetitions, to keep
+    // the output readable.
+    let mut entries: Vec<(String, usize)> = Vec::new();
+
+    for function in call_chain {
+        match entries.last_mut() {
+            Some((previous, count)) if *previous == function => {
+                *count += 1;
+            }
+            _ => {
+                entries.push((function, 1));
+            }
+        }
+    }
+
+    for (function, count) in entries {
+        if count > 1 {
+            eprintln!("- {function} (x{count})");
+        } else {
+            eprintln!("- {function}");
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Measurements {
     total_ms: u128,
@@ -1,7 +1,9 @@
 mod build_game;
+mod check;
 mod cli;
 mod export;
 mod files;
+mod fmt;
 mod headless;
 mod server;
 
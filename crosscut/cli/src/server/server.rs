@@ -1,6 +1,7 @@
-use std::{future, net::SocketAddr, path::PathBuf};
+use std::{future, io, net::SocketAddr, path::PathBuf};
 
 use axum::{
+    body::Bytes,
     extract::{ws::WebSocket, Path, State, WebSocketUpgrade},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
@@ -10,6 +11,7 @@ use axum::{
 use crosscut_compiler::CompilerOutput;
 use crosscut_protocol::{ron_options, Versioned};
 use tokio::{
+    fs,
     net::TcpListener,
     sync::{oneshot, watch},
     task,
@@ -26,12 +28,19 @@ type CodeRx = watch::Receiver<Code>;
 type ReadyTx = oneshot::Sender<()>;
 pub type ReadyRx = oneshot::Receiver<()>;
 
-pub fn start(address: SocketAddr, code: Code) -> (ReadyRx, CodeTx) {
+pub fn start(
+    address: SocketAddr,
+    code: Code,
+    snapshot: PathBuf,
+    recording: PathBuf,
+) -> (ReadyRx, CodeTx) {
     let (code_tx, code_rx) = watch::channel(code);
     let (ready_tx, ready_rx) = oneshot::channel();
 
     task::spawn(async move {
-        if let Err(err) = start_inner(address, ready_tx, code_rx).await {
+        if let Err(err) =
+            start_inner(address, ready_tx, code_rx, snapshot, recording).await
+        {
             error!("Error serving game code: {err:?}");
 
             // The rest of the system will start shutting down, as messages to
@@ -46,15 +55,23 @@ async fn start_inner(
     address: SocketAddr,
     ready: ReadyTx,
     code: CodeRx,
+    snapshot: PathBuf,
+    recording: PathBuf,
 ) -> anyhow::Result<()> {
     let router = Router::new()
         .route("/is-alive", get(serve_is_alive))
         .route("/wait-while-alive", get(serve_wait_while_alive))
         .route("/code", get(serve_code))
         .route("/code/{timestamp}", get(serve_code))
+        .route("/snapshot", get(serve_snapshot).put(store_snapshot))
+        .route("/recording", get(serve_recording).put(store_recording))
         .route("/", get(serve_index))
         .route("/{*path}", get(serve_static))
-        .with_state(ServerState { code });
+        .with_state(ServerState {
+            code,
+            snapshot,
+            recording,
+        });
 
     let listener = TcpListener::bind(address).await?;
 
@@ -71,6 +88,17 @@ async fn start_inner(
 #[derive(Clone, Debug)]
 pub struct ServerState {
     code: CodeRx,
+
+    /// # The file that the debugger's snapshot is stored in
+    ///
+    /// This is a file, so the snapshot survives a restart of the server.
+    snapshot: PathBuf,
+
+    /// # The file that the debugger's most recent recording is stored in
+    ///
+    /// The debugger stores the recording here, once it is finished. From here,
+    /// it can be downloaded and replayed using `crosscut headless --replay`.
+    recording: PathBuf,
 }
 
 async fn serve_is_alive() -> StatusCode {
@@ -115,6 +143,57 @@ async fn serve_code(
     }
 }
 
+async fn serve_snapshot(State(state): State<ServerState>) -> Response {
+    serve_stored_file(&state.snapshot, "snapshot").await
+}
+
+async fn store_snapshot(
+    State(state): State<ServerState>,
+    snapshot: Bytes,
+) -> StatusCode {
+    store_file(&state.snapshot, snapshot, "snapshot").await
+}
+
+async fn serve_recording(State(state): State<ServerState>) -> Response {
+    serve_stored_file(&state.recording, "recording").await
+}
+
+async fn store_recording(
+    State(state): State<ServerState>,
+    recording: Bytes,
+) -> StatusCode {
+    store_file(&state.recording, recording, "recording").await
+}
+
+async fn serve_stored_file(path: &std::path::Path, name: &str) -> Response {
+    match fs::read(path).await {
+        Ok(file) => file.into_response(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Err(err) => {
+            error!("Error reading {name}: {err:?}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn store_file(
+    path: &std::path::Path,
+    file: Bytes,
+    name: &str,
+) -> StatusCode {
+    // The server doesn't care what's in the file. Only the debugger (or
+    // whoever downloads it) needs to understand it.
+    match fs::write(path, file).await {
+        Ok(()) => StatusCode::OK,
+        Err(err) => {
+            error!("Error writing {name}: {err:?}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
 async fn serve_index() -> impl IntoResponse {
     make_file_response(PathBuf::from("index-debugger.html")).await
 }
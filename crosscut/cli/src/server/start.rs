@@ -11,7 +11,7 @@ use super::server::{self, CodeTx};
 
 pub enum Event {
     ChangeDetected,
-    BuildFinished,
+    BuildFinished { diagnostics: String },
     ServerReady,
 }
 
@@ -41,10 +41,11 @@ async fn start_inner(
     address: SocketAddr,
     events: EventsTx,
 ) -> anyhow::Result<()> {
-    let watcher =
-        Watcher::new(&games_path).context("Creating watcher for game")?;
-    let mut build_events =
-        build_and_watch_game(games_path.join("snake"), watcher.changes);
+    let watcher = Watcher::for_files().context("Creating watcher for game")?;
+    let game_path = games_path.join("snake");
+    let snapshot_path = game_path.join("snapshot.ron");
+    let recording_path = game_path.join("recording.ron");
+    let mut build_events = build_and_watch_game(game_path, watcher);
 
     let mut server_task = ServerTask::Uninitialized { address };
 
@@ -53,12 +54,17 @@ async fn start_inner(
             build_game::Event::ChangeDetected => {
                 events.send(Event::ChangeDetected).await?;
             }
-            build_game::Event::BuildFinished(code) => {
-                events.send(Event::BuildFinished).await?;
+            build_game::Event::BuildFinished { code, diagnostics } => {
+                events.send(Event::BuildFinished { diagnostics }).await?;
 
                 match server_task {
                     ServerTask::Uninitialized { address } => {
-                        let (ready_rx, code_tx) = server::start(address, code);
+                        let (ready_rx, code_tx) = server::start(
+                            address,
+                            *code,
+                            snapshot_path.clone(),
+                            recording_path.clone(),
+                        );
 
                         ready_rx.await?;
                         events.send(Event::ServerReady).await?;
@@ -66,7 +72,7 @@ async fn start_inner(
                         server_task = ServerTask::Initialized { code_tx };
                     }
                     ServerTask::Initialized { code_tx } => {
-                        if code_tx.send(code).is_err() {
+                        if code_tx.send(*code).is_err() {
                             return Ok(());
                         }
                         server_task = ServerTask::Initialized { code_tx };
@@ -17,6 +17,7 @@ mod tests {
             },
             Dependencies, FunctionCalls, IndexMap, Tokens,
         },
+        diagnostics::Diagnostics,
         host::NoHost,
     };
 
@@ -394,7 +395,11 @@ mod tests {
         let syntax_tree = SyntaxTree::parse(tokens);
 
         permutate_syntax_tree(syntax_tree).map(|syntax_tree| {
-            let function_calls = FunctionCalls::resolve(&syntax_tree, &NoHost);
+            let function_calls = FunctionCalls::resolve(
+                &syntax_tree,
+                &NoHost,
+                &mut Diagnostics::default(),
+            );
             let dependencies =
                 Dependencies::resolve(&syntax_tree, &function_calls);
 
@@ -413,7 +418,10 @@ mod tests {
 
         for named_functions in permutations {
             permutate_rest_of_named_functions(
-                SyntaxTree::default(),
+                SyntaxTree {
+                    modules: original.modules.clone(),
+                    ..SyntaxTree::default()
+                },
                 named_functions.into_iter(),
                 &mut syntax_trees,
             );
@@ -433,6 +441,7 @@ mod tests {
                     let named_function = NamedFunction {
                         comment: named_function.comment.clone(),
                         name: named_function.name.clone(),
+                        module: named_function.module,
                         inner: function,
                     };
                     let mut syntax_tree = syntax_tree.clone();
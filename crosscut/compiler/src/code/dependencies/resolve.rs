@@ -29,7 +29,8 @@ pub fn resolve_function_dependencies(
         for branch in function.branches() {
             for expression in branch.expressions() {
                 let dependee = match expression.fragment {
-                    Expression::Identifier { .. } => function_calls
+                    Expression::Identifier { .. }
+                    | Expression::FunctionReference { .. } => function_calls
                         .is_call_to_user_defined_function(&expression.location)
                         .cloned(),
                     _ => expression
@@ -82,7 +83,8 @@ pub fn resolve_branch_dependencies(
             let branch_has_no_unresolved_dependencies =
                 branch.expressions().all(|expression| {
                     let dependee = match expression.fragment {
-                        Expression::Identifier { .. } => {
+                        Expression::Identifier { .. }
+                        | Expression::FunctionReference { .. } => {
                             let Some(dependee) = function_calls
                                 .is_call_to_user_defined_function(
                                     &expression.location,
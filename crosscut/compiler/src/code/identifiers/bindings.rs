@@ -1,8 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::code::syntax::{
-    Binding, Branch, Expression, Function, FunctionLocation, Located,
-    MemberLocation, ParameterLocation, SyntaxTree,
+use crate::{
+    code::syntax::{
+        Binding, Branch, Expression, Function, FunctionLocation, Located,
+        MemberLocation, ParameterLocation, SyntaxTree,
+    },
+    diagnostics::{Diagnostic, Diagnostics},
 };
 
 /// # Tracks bindings
@@ -17,11 +20,18 @@ pub struct Bindings {
 
 impl Bindings {
     /// # Resolve all bindings
-    pub fn resolve(syntax_tree: &SyntaxTree) -> Self {
+    ///
+    /// Reports a warning for every binding that is never used, unless its name
+    /// starts with an underscore.
+    pub fn resolve(
+        syntax_tree: &SyntaxTree,
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
         let mut bindings = BTreeMap::new();
         let mut environments = BTreeMap::new();
 
         resolve_bindings(syntax_tree, &mut bindings, &mut environments);
+        report_unused_bindings(syntax_tree, &bindings, diagnostics);
 
         Self {
             bindings,
@@ -111,6 +121,39 @@ fn resolve_bindings(
     }
 }
 
+fn report_unused_bindings(
+    syntax_tree: &SyntaxTree,
+    bindings: &BindingsMap,
+    diagnostics: &mut Diagnostics,
+) {
+    let used = bindings.values().collect::<BTreeSet<_>>();
+
+    for function in syntax_tree.all_functions() {
+        for branch in function.branches() {
+            for binding in branch.bindings() {
+                if used.contains(&binding.location)
+                    || binding.name.starts_with('_')
+                {
+                    continue;
+                }
+
+                let span =
+                    syntax_tree.spans.of_parameter(&binding.location).copied();
+                diagnostics.push(
+                    Diagnostic::warning(
+                        format!("Unused binding `{}`", binding.name),
+                        span,
+                    )
+                    .with_note(
+                        "If this is intentional, prefix the name with an \
+                        underscore.",
+                    ),
+                );
+            }
+        }
+    }
+}
+
 fn resolve_bindings_in_function(
     function: Located<&Function>,
     scopes: &mut Scopes,
@@ -156,8 +199,12 @@ fn resolve_bindings_in_branch(
     for expression in branch.expressions() {
         match expression.fragment {
             Expression::Identifier { name } => {
+                // An identifier could access a field of a binding, like
+                // `position.x`. It's still the binding that we're looking for.
+                let name = name.split('.').next().unwrap_or(name);
+
                 let binding = scopes.iter().rev().find_map(|scope| {
-                    scope.values().find(|binding| binding.name == *name)
+                    scope.values().find(|binding| binding.name == name)
                 });
 
                 if let Some(binding) = binding {
@@ -214,9 +261,12 @@ type Scopes = Vec<BTreeMap<ParameterLocation, Located<Binding>>>;
 mod tests {
     use itertools::Itertools;
 
-    use crate::code::{
-        syntax::{Expression, FunctionLocation, SyntaxTree},
-        Tokens,
+    use crate::{
+        code::{
+            syntax::{Expression, FunctionLocation, SyntaxTree},
+            Tokens,
+        },
+        diagnostics::Diagnostics,
     };
 
     use super::Bindings;
@@ -433,10 +483,36 @@ mod tests {
             .any(|b| b == binding));
     }
 
+    #[test]
+    fn report_unused_binding() {
+        // Bindings that are never used should be reported, unless their name
+        // marks them as intentionally unused.
+
+        let tokens = Tokens::tokenize(
+            r"
+                f: fn
+                    br used, unused, _ignored ->
+                        used
+                    end
+                end
+            ",
+        );
+        let syntax_tree = SyntaxTree::parse(tokens);
+
+        let mut diagnostics = Diagnostics::default();
+        Bindings::resolve(&syntax_tree, &mut diagnostics);
+
+        let [warning] = diagnostics.warnings().collect::<Vec<_>>()[..] else {
+            panic!("Expected exactly one warning: {diagnostics:#?}");
+        };
+        assert_eq!(warning.message, "Unused binding `unused`");
+    }
+
     fn resolve_bindings(input: &str) -> (SyntaxTree, Bindings) {
         let tokens = Tokens::tokenize(input);
         let syntax_tree = SyntaxTree::parse(tokens);
-        let bindings = Bindings::resolve(&syntax_tree);
+        let bindings =
+            Bindings::resolve(&syntax_tree, &mut Diagnostics::default());
 
         (syntax_tree, bindings)
     }
@@ -1,9 +1,17 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    code::syntax::{Expression, FunctionLocation, MemberLocation, SyntaxTree},
+    code::{
+        syntax::{
+            Enum, Expression, FunctionLocation, MemberLocation, Module, Struct,
+            SyntaxTree, Variant,
+        },
+        Index,
+    },
+    diagnostics::{Diagnostic, Diagnostics},
     host::{Host, HostFunction},
     intrinsics::IntrinsicFunction,
+    standard_library,
 };
 
 /// # Tracks function calls
@@ -12,28 +20,67 @@ pub struct FunctionCalls {
     to_host_functions: BTreeMap<MemberLocation, HostFunction>,
     to_intrinsic_functions: BTreeMap<MemberLocation, IntrinsicFunction>,
     to_user_defined_functions: BTreeMap<MemberLocation, FunctionLocation>,
+    to_constructors: BTreeMap<MemberLocation, Constructor>,
 }
 
 impl FunctionCalls {
     /// # Resolve all function calls
-    pub fn resolve(syntax_tree: &SyntaxTree, host: &impl Host) -> Self {
+    ///
+    /// A function can call user-defined functions that are defined in its own
+    /// module, in any of the modules that its module imports, or in the
+    /// standard library. The name of a struct refers to its constructor, as
+    /// does the qualified name of an enum variant. Constructors can be called
+    /// from any module.
+    ///
+    /// The standard library is only considered, if an identifier doesn't refer
+    /// to any other function. That way, a game can define functions with the
+    /// same names as those of the standard library, which then shadow them.
+    ///
+    /// A reference to a function, like `&f`, is resolved the same way as a call
+    /// to that function would be.
+    ///
+    /// Reports an error for every import of a module that doesn't exist, and
+    /// for every identifier that could refer to more than one function.
+    pub fn resolve(
+        syntax_tree: &SyntaxTree,
+        host: &impl Host,
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
         let mut to_host_functions = BTreeMap::new();
         let mut to_intrinsic_functions = BTreeMap::new();
         let mut to_user_defined_functions = BTreeMap::new();
+        let mut to_constructors = BTreeMap::new();
+
+        let visible_modules = resolve_imports(syntax_tree, diagnostics);
 
         for function in syntax_tree.all_functions() {
+            let Some(module) = syntax_tree
+                .find_top_level_parent_function(&function.location)
+                .map(|named_function| named_function.module)
+            else {
+                continue;
+            };
+            let Some(visible_modules) = visible_modules.get(&module) else {
+                continue;
+            };
+
             for branch in function.branches() {
                 for expression in branch.expressions() {
-                    if let Expression::Identifier { name } = expression.fragment
+                    if let Expression::Identifier { name }
+                    | Expression::FunctionReference { name } =
+                        expression.fragment
                     {
-                        // If multiple functions of different types have the
-                        // same name, the following code will resolve a single
-                        // identifier as multiple types of function call.
+                        // If multiple functions have the same name, the
+                        // following code will resolve a single identifier as
+                        // multiple types of function call.
                         //
                         // This is by design. Later compiler passes can sort it
-                        // out in whatever way they wish.
+                        // out in whatever way they wish. We still let the
+                        // developer know, as it's never what they want.
+                        let mut candidates = Vec::new();
 
                         if let Some(function) = host.function_by_name(name) {
+                            candidates.push("host function".to_string());
                             to_host_functions
                                 .insert(expression.location.clone(), function);
                         }
@@ -41,18 +88,80 @@ impl FunctionCalls {
                         if let Some(function) =
                             IntrinsicFunction::from_name(name)
                         {
+                            candidates.push("intrinsic function".to_string());
                             to_intrinsic_functions
                                 .insert(expression.location.clone(), function);
                         }
 
-                        if let Some(function) =
-                            syntax_tree.function_by_name(name)
+                        for module in &visible_modules.imported {
+                            resolve_user_defined_function(
+                                name,
+                                module,
+                                &expression.location,
+                                syntax_tree,
+                                &mut candidates,
+                                &mut to_user_defined_functions,
+                            );
+                        }
+
+                        if let Some(struct_) = syntax_tree.struct_by_name(name)
                         {
-                            to_user_defined_functions.insert(
-                                expression.location,
-                                function.location(),
+                            candidates.push("constructor".to_string());
+                            to_constructors.insert(
+                                expression.location.clone(),
+                                Constructor::Struct {
+                                    index: struct_.location,
+                                },
                             );
                         }
+
+                        if let Some((enum_, variant)) =
+                            syntax_tree.variant_by_name(name)
+                        {
+                            candidates.push("constructor".to_string());
+                            to_constructors.insert(
+                                expression.location.clone(),
+                                Constructor::Variant {
+                                    enum_: enum_.location,
+                                    variant,
+                                },
+                            );
+                        }
+
+                        if candidates.is_empty() {
+                            for module in &visible_modules.library {
+                                resolve_user_defined_function(
+                                    name,
+                                    module,
+                                    &expression.location,
+                                    syntax_tree,
+                                    &mut candidates,
+                                    &mut to_user_defined_functions,
+                                );
+                            }
+                        }
+
+                        if candidates.len() > 1 {
+                            let span = syntax_tree
+                                .spans
+                                .of_member(&expression.location)
+                                .copied();
+
+                            let mut diagnostic = Diagnostic::error(
+                                format!(
+                                    "Identifier `{name}` refers to multiple \
+                                    functions"
+                                ),
+                                span,
+                            );
+                            for candidate in candidates {
+                                diagnostic = diagnostic.with_note(format!(
+                                    "Could be the {candidate} `{name}`."
+                                ));
+                            }
+
+                            diagnostics.push(diagnostic);
+                        }
                     }
                 }
             }
@@ -62,6 +171,7 @@ impl FunctionCalls {
             to_host_functions,
             to_intrinsic_functions,
             to_user_defined_functions,
+            to_constructors,
         }
     }
 
@@ -88,12 +198,127 @@ impl FunctionCalls {
     ) -> Option<&FunctionLocation> {
         self.to_user_defined_functions.get(location)
     }
+
+    /// # Determine, if an expression is a call to a constructor
+    pub fn is_call_to_constructor(
+        &self,
+        location: &MemberLocation,
+    ) -> Option<&Constructor> {
+        self.to_constructors.get(location)
+    }
+}
+
+/// # A constructor, which creates a value of a struct or enum
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub enum Constructor {
+    /// # The constructor of a struct
+    Struct {
+        /// # The index of the struct
+        index: Index<Struct>,
+    },
+
+    /// # The constructor of an enum variant
+    Variant {
+        /// # The index of the enum
+        enum_: Index<Enum>,
+
+        /// # The index of the variant within the enum
+        variant: Index<Variant>,
+    },
+}
+
+fn resolve_user_defined_function(
+    name: &str,
+    module: &Index<Module>,
+    location: &MemberLocation,
+    syntax_tree: &SyntaxTree,
+    candidates: &mut Vec<String>,
+    to_user_defined_functions: &mut BTreeMap<MemberLocation, FunctionLocation>,
+) {
+    let Some(function) = syntax_tree.function_by_name_in_module(name, module)
+    else {
+        return;
+    };
+
+    let module_name = syntax_tree
+        .modules
+        .get(module)
+        .map(|module| module.name.as_str())
+        .unwrap_or_default();
+
+    candidates
+        .push(format!("user-defined function from module `{module_name}`"));
+    to_user_defined_functions
+        .entry(location.clone())
+        .or_insert(function.location());
+}
+
+/// # The modules that are visible from a module
+struct VisibleModules {
+    /// # The module itself, and all modules that it imports
+    imported: Vec<Index<Module>>,
+
+    /// # The modules of the standard library that it didn't import
+    library: Vec<Index<Module>>,
+}
+
+/// # Determine which modules are visible from each module
+///
+/// A module can see itself, all modules it imports directly, and the standard
+/// library. Imports are not transitive.
+fn resolve_imports(
+    syntax_tree: &SyntaxTree,
+    diagnostics: &mut Diagnostics,
+) -> BTreeMap<Index<Module>, VisibleModules> {
+    let mut visible_modules = BTreeMap::new();
+
+    for (&index, module) in syntax_tree.modules.iter() {
+        let mut visible = vec![index];
+        let mut library = Vec::new();
+
+        for (i, import) in module.imports.iter().enumerate() {
+            let Some(imported) = syntax_tree.module_by_name(import) else {
+                let span = syntax_tree.spans.of_import(&index, i).copied();
+                diagnostics.push(Diagnostic::error(
+                    format!("Module `{import}` not found"),
+                    span,
+                ));
+                continue;
+            };
+
+            if !visible.contains(&imported) {
+                visible.push(imported);
+            }
+        }
+
+        // The standard library doesn't need to be imported.
+        for (&index, module) in syntax_tree.modules.iter() {
+            if standard_library::contains(&module.name)
+                && !visible.contains(&index)
+            {
+                library.push(index);
+            }
+        }
+
+        visible_modules.insert(
+            index,
+            VisibleModules {
+                imported: visible,
+                library,
+            },
+        );
+    }
+
+    visible_modules
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         code::{syntax::SyntaxTree, Tokens},
+        diagnostics::Diagnostics,
         host::{Host, HostFunction},
     };
 
@@ -204,10 +429,44 @@ mod tests {
             .is_some());
     }
 
+    #[test]
+    fn report_identifier_that_refers_to_multiple_functions() {
+        // If an identifier could refer to functions of more than one type, that
+        // should be reported.
+
+        let tokens = Tokens::tokenize(
+            r"
+                f: fn
+                    br ->
+                        host_fn
+                    end
+                end
+
+                host_fn: fn
+                    br ->
+                    end
+                end
+            ",
+        );
+        let syntax_tree = SyntaxTree::parse(tokens);
+
+        let mut diagnostics = Diagnostics::default();
+        FunctionCalls::resolve(&syntax_tree, &TestHost, &mut diagnostics);
+
+        let [error] = diagnostics.errors().collect::<Vec<_>>()[..] else {
+            panic!("Expected exactly one error: {diagnostics:#?}");
+        };
+        assert_eq!(error.notes.len(), 2);
+    }
+
     fn resolve_function_calls(input: &str) -> (SyntaxTree, FunctionCalls) {
         let tokens = Tokens::tokenize(input);
         let syntax_tree = SyntaxTree::parse(tokens);
-        let function_calls = FunctionCalls::resolve(&syntax_tree, &TestHost);
+        let function_calls = FunctionCalls::resolve(
+            &syntax_tree,
+            &TestHost,
+            &mut Diagnostics::default(),
+        );
 
         (syntax_tree, function_calls)
     }
@@ -1,10 +1,14 @@
 use std::collections::BTreeMap;
 
 use crate::{
-    code::syntax::{
-        Expression, FunctionLocation, MemberLocation, ParameterLocation,
-        SyntaxTree,
+    code::{
+        syntax::{
+            Expression, FunctionLocation, MemberLocation, ParameterLocation,
+            SyntaxTree,
+        },
+        Constructor,
     },
+    diagnostics::{Diagnostic, Diagnostics},
     host::HostFunction,
     intrinsics::IntrinsicFunction,
 };
@@ -18,18 +22,27 @@ pub struct Identifiers {
 }
 
 impl Identifiers {
-    /// # Resolve all bindings
+    /// # Resolve all identifiers
+    ///
+    /// This includes the identifiers in function references, like `&f`.
+    ///
+    /// Identifiers that can't be resolved to a single target are reported as
+    /// errors, and left unresolved.
     pub fn resolve(
         syntax_tree: &SyntaxTree,
         bindings: &Bindings,
         function_calls: &FunctionCalls,
+        diagnostics: &mut Diagnostics,
     ) -> Self {
         let mut targets = BTreeMap::new();
 
         for function in syntax_tree.all_functions() {
             for branch in function.branches() {
                 for expression in branch.expressions() {
-                    if let Expression::Identifier { .. } = expression.fragment {
+                    if let Expression::Identifier { name }
+                    | Expression::FunctionReference { name } =
+                        expression.fragment
+                    {
                         let binding = bindings.is_binding(&expression.location);
                         let host_function = function_calls
                             .is_call_to_host_function(&expression.location);
@@ -41,45 +54,135 @@ impl Identifiers {
                             .is_call_to_user_defined_function(
                                 &expression.location,
                             );
+                        let constructor = function_calls
+                            .is_call_to_constructor(&expression.location);
 
                         let target = match (
                             binding,
                             host_function,
                             intrinsic_function,
                             user_defined_function,
+                            constructor,
                         ) {
-                            (Some(binding), None, None, None) => {
+                            (Some(binding), None, None, None, None) => {
                                 IdentifierTarget::Binding(binding.clone())
                             }
-                            (None, Some(host_function), None, None) => {
+                            (None, Some(host_function), None, None, None) => {
                                 IdentifierTarget::HostFunction(
                                     host_function.clone(),
                                 )
                             }
-                            (None, None, Some(intrinsic_function), None) => {
-                                IdentifierTarget::IntrinsicFunction(
-                                    *intrinsic_function,
-                                )
+                            (
+                                None,
+                                None,
+                                Some(intrinsic_function),
+                                None,
+                                None,
+                            ) => IdentifierTarget::IntrinsicFunction(
+                                *intrinsic_function,
+                            ),
+                            (
+                                None,
+                                None,
+                                None,
+                                Some(user_defined_function),
+                                None,
+                            ) => IdentifierTarget::UserDefinedFunction(
+                                user_defined_function.clone(),
+                            ),
+                            (None, None, None, None, Some(constructor)) => {
+                                IdentifierTarget::Constructor(*constructor)
                             }
-                            (None, None, None, Some(user_defined_function)) => {
-                                IdentifierTarget::UserDefinedFunction(
-                                    user_defined_function.clone(),
-                                )
+                            (None, None, None, None, None) => {
+                                let span = syntax_tree
+                                    .spans
+                                    .of_member(&expression.location)
+                                    .copied();
+                                let mut diagnostic = Diagnostic::error(
+                                    format!("Unresolved identifier `{name}`"),
+                                    span,
+                                );
+
+                                // The identifier might refer to a function in
+                                // a module that hasn't been imported.
+                                for function in syntax_tree
+                                    .named_functions()
+                                    .filter(|function| function.name == *name)
+                                {
+                                    let Some(module) = syntax_tree
+                                        .modules
+                                        .get(&function.module)
+                                    else {
+                                        continue;
+                                    };
+
+                                    diagnostic = diagnostic.with_note(format!(
+                                        "There is a function `{name}` in \
+                                        module `{}`, which isn't imported \
+                                        here.",
+                                        module.name,
+                                    ));
+                                }
+
+                                diagnostics.push(diagnostic);
+                                continue;
                             }
-                            _ => {
-                                panic!(
-                                    "Identifier resolved to multiple targets:\n\
-                                    \n\
-                                    Binding: {binding:?}\n\
-                                    Host function: {host_function:?}\n\
-                                    Intrinsic function: {intrinsic_function:?} \
-                                    \n\
-                                    User-defined function: \
-                                    {user_defined_function:?}\n"
+                            (Some(_), _, _, _, _) => {
+                                let span = syntax_tree
+                                    .spans
+                                    .of_member(&expression.location)
+                                    .copied();
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        format!(
+                                            "Identifier `{name}` refers to \
+                                            both a binding and a function"
+                                        ),
+                                        span,
+                                    )
+                                    .with_note(
+                                        "Bindings can't have the same name as \
+                                        a function.",
+                                    ),
                                 );
+                                continue;
+                            }
+                            _ => {
+                                // The identifier refers to multiple functions.
+                                // This has already been reported, when
+                                // resolving function calls.
+                                continue;
                             }
                         };
 
+                        if let (
+                            Expression::FunctionReference { .. },
+                            IdentifierTarget::IntrinsicFunction(_)
+                            | IdentifierTarget::Constructor(_),
+                        ) = (expression.fragment, &target)
+                        {
+                            // Intrinsic functions and constructors are compiled
+                            // in place, wherever they are called. There is no
+                            // function to refer to.
+                            let span = syntax_tree
+                                .spans
+                                .of_member(&expression.location)
+                                .copied();
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    format!(
+                                        "Can't refer to `{name}` as a value"
+                                    ),
+                                    span,
+                                )
+                                .with_note(
+                                    "Only user-defined functions and host \
+                                    functions can be referred to.",
+                                ),
+                            );
+                            continue;
+                        }
+
                         targets.insert(expression.location, target);
                     }
                 }
@@ -111,4 +214,7 @@ pub enum IdentifierTarget {
 
     /// # The identifier resolves to a user-defined function
     UserDefinedFunction(FunctionLocation),
+
+    /// # The identifier resolves to the constructor of a struct or enum
+    Constructor(Constructor),
 }
@@ -4,6 +4,6 @@ mod identifiers;
 
 pub use self::{
     bindings::{Bindings, Environment},
-    function_calls::FunctionCalls,
+    function_calls::{Constructor, FunctionCalls},
     identifiers::{IdentifierTarget, Identifiers},
 };
@@ -11,6 +11,7 @@ mod changes;
 mod functions;
 mod hash;
 mod index;
+mod span;
 
 pub use self::{
     changes::{Changes, FunctionInUpdate, FunctionUpdate},
@@ -18,11 +19,13 @@ pub use self::{
     functions::Functions,
     hash::Hash,
     identifiers::{
-        Bindings, Environment, FunctionCalls, IdentifierTarget, Identifiers,
+        Bindings, Constructor, Environment, FunctionCalls, IdentifierTarget,
+        Identifiers,
     },
     index::{Index, IndexMap},
     recursion::Recursion,
+    span::{Position, Span},
     tail_expressions::TailExpressions,
-    tokens::{Token, Tokens},
+    tokens::{Keyword, Punctuator, Token, Tokens},
     types::{Signature, Type, TypeAnnotations, Types},
 };
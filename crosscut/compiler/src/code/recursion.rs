@@ -1,15 +1,18 @@
 use std::collections::BTreeSet;
 
+use crate::diagnostics::{Diagnostic, Diagnostics};
+
 use super::{
     syntax::{Expression, FunctionLocation, MemberLocation, SyntaxTree},
-    Dependencies, FunctionCalls,
+    Dependencies, FunctionCalls, TailExpressions,
 };
 
 /// # Tracks recursive expressions
 ///
-/// There are two types of expressions that can be recursive:
+/// There are three types of expressions that can be recursive:
 ///
 /// - Calls to user-defined functions
+/// - References to user-defined functions
 /// - Local functions
 ///
 /// A call to a user-defined function can be recursive in the following ways:
@@ -21,6 +24,9 @@ use super::{
 ///   they are defined. The call from that other function could be indirect,
 ///   through any number of mutually recursive functions.
 ///
+/// References to user-defined functions are recursive in the same ways, except
+/// that they are never reported as non-tail calls. They don't call anything.
+///
 /// Local functions can be recursive in the following ways:
 ///
 /// - They contain a recursive call.
@@ -40,10 +46,16 @@ pub struct Recursion {
 
 impl Recursion {
     /// # Find all recursive expressions
+    ///
+    /// Reports a warning for every recursive call that is not a tail call.
+    /// Those can't be eliminated, meaning deep recursion might exhaust the
+    /// stack.
     pub fn find(
         syntax_tree: &SyntaxTree,
         function_calls: &FunctionCalls,
         dependencies: &Dependencies,
+        tail_expressions: &TailExpressions,
+        diagnostics: &mut Diagnostics,
     ) -> Self {
         let mut recursive_expressions = BTreeSet::new();
 
@@ -52,7 +64,50 @@ impl Recursion {
                 for branch in function.branches() {
                     for expression in branch.expressions() {
                         match expression.fragment {
-                            Expression::Identifier { name: _ } => {
+                            Expression::Identifier { name } => {
+                                let Some(location) = function_calls
+                                    .is_call_to_user_defined_function(
+                                        &expression.location,
+                                    )
+                                else {
+                                    continue;
+                                };
+
+                                if !cluster.contains_function(location) {
+                                    continue;
+                                }
+
+                                if !tail_expressions
+                                    .is_tail_expression(&expression.location)
+                                {
+                                    let span = syntax_tree
+                                        .spans
+                                        .of_member(&expression.location)
+                                        .copied();
+                                    diagnostics.push(
+                                        Diagnostic::warning(
+                                            format!(
+                                                "Recursive call to `{name}` \
+                                                is not a tail call"
+                                            ),
+                                            span,
+                                        )
+                                        .with_note(
+                                            "Every call adds to the stack, \
+                                            until the recursion ends. Deep \
+                                            recursion might exhaust it.",
+                                        ),
+                                    );
+                                }
+
+                                recursive_expressions
+                                    .insert(expression.location);
+                            }
+                            Expression::FunctionReference { .. } => {
+                                // Referring to a function doesn't call it. But
+                                // if the function is in the same cluster, it
+                                // might not have been compiled yet, when the
+                                // reference is.
                                 let Some(location) = function_calls
                                     .is_call_to_user_defined_function(
                                         &expression.location,
@@ -99,7 +154,11 @@ mod tests {
     use itertools::Itertools;
 
     use crate::{
-        code::{syntax::SyntaxTree, Dependencies, FunctionCalls, Tokens},
+        code::{
+            syntax::SyntaxTree, Dependencies, FunctionCalls, TailExpressions,
+            Tokens,
+        },
+        diagnostics::Diagnostics,
         host::NoHost,
     };
 
@@ -397,14 +456,55 @@ mod tests {
         assert!(recursion.is_recursive_expression(&function));
     }
 
+    #[test]
+    fn report_recursive_call_that_is_not_a_tail_call() {
+        // Recursive calls that are not tail calls are valid, but can't be
+        // eliminated. This should be reported.
+
+        let (_, _, diagnostics) = find_recursion_with_diagnostics(
+            r"
+                f: fn
+                    br 0 ->
+                    end
+                    br n ->
+                        n 1 sub_s32 f
+                        n 1 sub_s32 f
+                    end
+                end
+            ",
+        );
+
+        let [warning] = diagnostics.warnings().collect::<Vec<_>>()[..] else {
+            panic!("Expected exactly one warning: {diagnostics:#?}");
+        };
+        assert_eq!(warning.message, "Recursive call to `f` is not a tail call");
+    }
+
     fn find_recursion(input: &str) -> (SyntaxTree, Recursion) {
+        let (syntax_tree, recursion, _) =
+            find_recursion_with_diagnostics(input);
+        (syntax_tree, recursion)
+    }
+
+    fn find_recursion_with_diagnostics(
+        input: &str,
+    ) -> (SyntaxTree, Recursion, Diagnostics) {
         let tokens = Tokens::tokenize(input);
         let syntax_tree = SyntaxTree::parse(tokens);
-        let function_calls = FunctionCalls::resolve(&syntax_tree, &NoHost);
+        let mut diagnostics = Diagnostics::default();
+
+        let function_calls =
+            FunctionCalls::resolve(&syntax_tree, &NoHost, &mut diagnostics);
+        let tail_expressions = TailExpressions::find(&syntax_tree);
         let dependencies = Dependencies::resolve(&syntax_tree, &function_calls);
-        let recursion =
-            Recursion::find(&syntax_tree, &function_calls, &dependencies);
+        let recursion = Recursion::find(
+            &syntax_tree,
+            &function_calls,
+            &dependencies,
+            &tail_expressions,
+            &mut diagnostics,
+        );
 
-        (syntax_tree, recursion)
+        (syntax_tree, recursion, diagnostics)
     }
 }
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use super::{syntax::Module, Index};
+
+/// # A position within the source code
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub struct Position {
+    /// # The offset from the start of the source code, in bytes
+    pub byte: usize,
+
+    /// # The line, starting at `1`
+    pub line: usize,
+
+    /// # The column within the line, in characters, starting at `1`
+    pub column: usize,
+}
+
+impl Position {
+    /// # Advance the position past the provided character
+    pub fn advance(&mut self, ch: char) {
+        self.byte += ch.len_utf8();
+
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            byte: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// # A span of source code
+///
+/// Spans are half-open: `start` is the position of the first character within
+/// the span, `end` the position right after the last one.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub struct Span {
+    /// # The module whose source code the span is located in
+    pub module: Index<Module>,
+
+    /// # The start of the span
+    pub start: Position,
+
+    /// # The end of the span
+    pub end: Position,
+}
+
+impl Span {
+    /// # Create a span that covers both this one and the provided one
+    ///
+    /// Expects both spans to be located in the same module.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            module: self.module,
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Self {
+            module: Module::main_index(),
+            start: Position::default(),
+            end: Position::default(),
+        }
+    }
+}
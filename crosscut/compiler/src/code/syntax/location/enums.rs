@@ -0,0 +1,7 @@
+use crate::code::{syntax::Enum, Index};
+
+use super::located::HasLocation;
+
+impl HasLocation for Enum {
+    type Location = Index<Enum>;
+}
@@ -2,11 +2,13 @@ mod located;
 
 mod binding;
 mod branch;
+mod enums;
 mod expression;
 mod function;
 mod member;
 mod named_function;
 mod parameter;
+mod structs;
 
 pub use self::{
     branch::BranchLocation, function::FunctionLocation, located::Located,
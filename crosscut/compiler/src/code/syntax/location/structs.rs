@@ -0,0 +1,7 @@
+use crate::code::{syntax::Struct, Index};
+
+use super::located::HasLocation;
+
+impl HasLocation for Struct {
+    type Location = Index<Struct>;
+}
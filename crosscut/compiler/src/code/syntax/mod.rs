@@ -26,11 +26,16 @@ pub use self::{
         ParameterLocation,
     },
     repr::{
+        enums::{Enum, Variant},
+        error::SyntaxError,
         expression::Expression,
         function::{
             Binding, Branch, Comment, Function, Member, NamedFunction,
             Parameter,
         },
+        module::Module,
+        spans::Spans,
+        structs::{Field, Struct},
         syntax_tree::SyntaxTree,
         types::SyntaxType,
     },
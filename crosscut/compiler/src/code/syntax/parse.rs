@@ -1,82 +1,266 @@
 use std::result;
 
 use crate::code::{
-    tokens::{Keyword::*, NoMoreTokens, Punctuator::*, Token, Tokens},
-    Index, IndexMap, Signature,
+    tokens::{
+        Keyword::*, NoMoreTokens, Punctuator, Punctuator::*, Token, Tokens,
+    },
+    Index, IndexMap, Signature, Span,
 };
 
 use super::{
-    repr::types::SyntaxType, Binding, Branch, BranchLocation, Comment,
-    Expression, Function, FunctionLocation, Member, MemberLocation,
-    NamedFunction, Parameter,
+    repr::types::SyntaxType, Binding, Branch, BranchLocation, Comment, Enum,
+    Expression, Field, Function, FunctionLocation, Member, MemberLocation,
+    Module, NamedFunction, Parameter, ParameterLocation, Spans, Struct,
+    SyntaxError, Variant,
 };
 
 /// # Parse the provided tokens
 ///
-/// ## Implementation Note
+/// The parser does not give up, when it encounters a token it doesn't expect.
+/// Instead, it records the offending tokens as a [`SyntaxError`], skips ahead
+/// to a point where it can resume, and continues from there. It finds those
+/// points by keeping track of the `fn`, `br`, and `end` keywords, which delimit
+/// functions and branches.
 ///
-/// This compiler pass currently panics when it encounters an unexpected token.
-/// It would be better, if it encoded the error into its output instead. This is
-/// non-trivial though, compared to other compiler passes that do that.
+/// Syntax errors are recorded as close to where they occur as possible. Within
+/// a branch body, they end up as a [`Member`]; within a list of branch
+/// parameters, as a [`Parameter`]. Any syntax error that affects an import or
+/// a named function as a whole is returned separately, alongside the imports.
 ///
-/// For example, if an identifier can not be resolved, this only affects that
-/// identifier, and it is quite easy to encode that by having a [`Expression`]
-/// variant for unresolved identifiers.
-///
-/// In the case of parsing, however, an unexpected token would likely result in
-/// the parser not knowing what to do with the following tokens, even it can be
-/// taught to recover eventually. Those tokens would also have to be encoded
-/// into the code representation. Otherwise, viewing that representation (which
-/// would include the error) makes no sense to a user.
-///
-/// I'm inclined to leave this be for now. Panicking certainly works well enough
-/// in the current setup. Once we have a code database, it will no longer do.
-/// But then we also need a completely different parser architecture.
-///
-/// It's probably not worth solving this non-trivial problem for the current
-/// architecture, for little gain, only to re-solve it again for the new
-/// architecture, once that is necessary.
-pub fn parse(mut tokens: Tokens) -> IndexMap<NamedFunction> {
-    let mut named_functions = IndexMap::default();
+/// The tokens are expected to make up the source code of a single module. Any
+/// named functions, structs, and enums that are parsed are added to the
+/// provided ones.
+pub fn parse(
+    mut tokens: Tokens,
+    module: Index<Module>,
+    named_functions: &mut IndexMap<NamedFunction>,
+    structs: &mut IndexMap<Struct>,
+    enums: &mut IndexMap<Enum>,
+    spans: &mut Spans,
+) -> (Vec<String>, Vec<SyntaxError>) {
+    let mut imports = Vec::new();
+    let mut errors = Vec::new();
+
+    // A module can have a single import statement, which lists all imported
+    // modules. It has to come before any named functions.
+    if let Ok(Token::Keyword(Import)) = tokens.peek() {
+        let start = tokens.position();
+
+        match parse_import(&mut tokens) {
+            Ok(names) => {
+                for (name, span) in names {
+                    spans.imports.insert((module, imports.len()), span);
+                    imports.push(name);
+                }
+            }
+            Err(err) => {
+                let unexpected = match err {
+                    Error::NoMoreTokens(NoMoreTokens) => None,
+                    Error::UnexpectedToken { actual } => Some(actual),
+                };
+
+                errors.push(recover(&mut tokens, start, unexpected));
+                spans
+                    .errors
+                    .push(tokens.span_since(start).unwrap_or_default());
+            }
+        }
+    }
 
     loop {
-        let index = named_functions.next_index();
+        let indices = (
+            named_functions.next_index(),
+            structs.next_index(),
+            enums.next_index(),
+        );
+        let start = tokens.position();
 
-        let function = match parse_named_function(&mut tokens, index) {
-            Ok(function) => function,
+        // If parsing fails, we're going to have recorded the spans of whatever
+        // we parsed before that. We don't want to keep those around, so we
+        // record them separately, and only keep them on success.
+        let mut item_spans = Spans::default();
+
+        let result =
+            parse_named_item(&mut tokens, indices, module, &mut item_spans);
+        let item = match result {
+            Ok(item) => item,
             Err(Error::NoMoreTokens(NoMoreTokens)) => {
+                let is_only_comments = tokens
+                    .taken_since(start)
+                    .iter()
+                    .all(|token| matches!(token, Token::CommentLine { .. }));
+
+                if !is_only_comments {
+                    // The input ended in the middle of a named function.
+                    errors.push(recover(&mut tokens, start, None));
+                    spans
+                        .errors
+                        .push(tokens.span_since(start).unwrap_or_default());
+                }
+
                 break;
             }
-            Err(err) => {
-                panic!("Parser error: {err:?}");
+            Err(Error::UnexpectedToken { actual }) => {
+                let mut error = recover(&mut tokens, start, Some(actual));
+
+                if error.tokens.is_empty() {
+                    // `recover` leaves any `end` that doesn't close a function
+                    // or branch it has seen, for the enclosing function or
+                    // branch to take care of. But at the top level, there is
+                    // none of those. If we don't take the `end` here, nobody
+                    // will.
+                    error.tokens.extend(tokens.take());
+                }
+
+                errors.push(error);
+                spans
+                    .errors
+                    .push(tokens.span_since(start).unwrap_or_default());
+
+                continue;
             }
         };
 
-        let actual_index = named_functions.push(function);
-        assert_eq!(
-            index, actual_index,
-            "Function has a different index than was initially assumed.",
-        );
+        append_spans(spans, item_spans);
+
+        match item {
+            NamedItem::Function(function) => {
+                let actual_index = named_functions.push(function);
+                assert_eq!(
+                    indices.0, actual_index,
+                    "Function has a different index than was initially \
+                    assumed.",
+                );
+            }
+            NamedItem::Struct(struct_) => {
+                let actual_index = structs.push(struct_);
+                assert_eq!(
+                    indices.1, actual_index,
+                    "Struct has a different index than was initially assumed.",
+                );
+            }
+            NamedItem::Enum(enum_) => {
+                let actual_index = enums.push(enum_);
+                assert_eq!(
+                    indices.2, actual_index,
+                    "Enum has a different index than was initially assumed.",
+                );
+            }
+        }
     }
 
-    named_functions
+    (imports, errors)
 }
 
-fn parse_named_function(
+enum NamedItem {
+    Function(NamedFunction),
+    Struct(Struct),
+    Enum(Enum),
+}
+
+fn parse_import(tokens: &mut Tokens) -> Result<Vec<(String, Span)>> {
+    match tokens.take()? {
+        Token::Keyword(Import) => {}
+        token => {
+            return Err(Error::UnexpectedToken { actual: token });
+        }
+    }
+
+    let mut names = Vec::new();
+
+    loop {
+        let start = tokens.position();
+
+        let name = match tokens.take()? {
+            Token::Identifier { name } => name,
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        };
+        let span = tokens.span_since(start).unwrap_or_default();
+
+        names.push((name, span));
+
+        match tokens.take()? {
+            Token::Punctuator(Delimiter) => {
+                continue;
+            }
+            Token::Punctuator(Terminator) => {
+                break;
+            }
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// # Parse a named function, a struct, or an enum
+///
+/// All of them start out the same, with a name followed by an introducer. They
+/// only become distinguishable after that.
+fn parse_named_item(
     tokens: &mut Tokens,
-    index: Index<NamedFunction>,
-) -> Result<NamedFunction> {
+    (function_index, struct_index, enum_index): (
+        Index<NamedFunction>,
+        Index<Struct>,
+        Index<Enum>,
+    ),
+    module: Index<Module>,
+    spans: &mut Spans,
+) -> Result<NamedItem> {
     let comment = parse_comment(tokens)?;
-    let name = parse_function_name(tokens)?;
 
-    let location = FunctionLocation::Named { index };
-    let function = parse_function(tokens, location)?;
+    let start = tokens.position();
+    let name = parse_item_name(tokens)?;
+
+    if let Token::Keyword(Struct) = tokens.peek()? {
+        let fields = parse_struct_fields(tokens, struct_index, spans)?;
+
+        if let Some(span) = tokens.span_since(start) {
+            spans.structs.insert(struct_index, span);
+        }
+
+        return Ok(NamedItem::Struct(Struct {
+            comment,
+            name,
+            module,
+            fields,
+        }));
+    }
+
+    if let Token::Keyword(Enum) = tokens.peek()? {
+        let variants = parse_enum_variants(tokens, enum_index, spans)?;
+
+        if let Some(span) = tokens.span_since(start) {
+            spans.enums.insert(enum_index, span);
+        }
+
+        return Ok(NamedItem::Enum(Enum {
+            comment,
+            name,
+            module,
+            variants,
+        }));
+    }
+
+    let location = FunctionLocation::Named {
+        index: function_index,
+    };
+    let function = parse_function(tokens, location, spans)?;
 
-    Ok(NamedFunction {
+    if let Some(span) = tokens.span_since(start) {
+        spans.named_functions.insert(function_index, span);
+    }
+
+    Ok(NamedItem::Function(NamedFunction {
         comment,
         name,
+        module,
         inner: function,
-    })
+    }))
 }
 
 fn parse_comment(tokens: &mut Tokens) -> Result<Option<Comment>> {
@@ -110,7 +294,7 @@ fn parse_comment(tokens: &mut Tokens) -> Result<Option<Comment>> {
     Ok(comment)
 }
 
-fn parse_function_name(tokens: &mut Tokens) -> Result<String> {
+fn parse_item_name(tokens: &mut Tokens) -> Result<String> {
     let name = match tokens.take()? {
         Token::Identifier { name } => name,
         token => {
@@ -128,9 +312,99 @@ fn parse_function_name(tokens: &mut Tokens) -> Result<String> {
     Ok(name)
 }
 
+fn parse_struct_fields(
+    tokens: &mut Tokens,
+    struct_index: Index<Struct>,
+    spans: &mut Spans,
+) -> Result<IndexMap<Field>> {
+    let mut fields = IndexMap::default();
+
+    match tokens.take()? {
+        Token::Keyword(Struct) => {}
+        token => {
+            return Err(Error::UnexpectedToken { actual: token });
+        }
+    }
+
+    loop {
+        let start = tokens.position();
+
+        let name = match tokens.take()? {
+            Token::Identifier { name } => name,
+            Token::Keyword(End) => {
+                break;
+            }
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        };
+
+        match tokens.take()? {
+            Token::Punctuator(Introducer) => {}
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        }
+
+        let type_ = parse_type(tokens)?;
+
+        if let Some(span) = tokens.span_since(start) {
+            spans
+                .fields
+                .insert((struct_index, fields.next_index()), span);
+        }
+
+        fields.push(Field { name, type_ });
+    }
+
+    Ok(fields)
+}
+
+fn parse_enum_variants(
+    tokens: &mut Tokens,
+    enum_index: Index<Enum>,
+    spans: &mut Spans,
+) -> Result<IndexMap<Variant>> {
+    let mut variants = IndexMap::default();
+
+    match tokens.take()? {
+        Token::Keyword(Enum) => {}
+        token => {
+            return Err(Error::UnexpectedToken { actual: token });
+        }
+    }
+
+    loop {
+        let start = tokens.position();
+
+        let name = match tokens.take()? {
+            Token::Identifier { name } => name,
+            Token::Keyword(End) => {
+                break;
+            }
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        };
+
+        let payload = parse_type_annotation(tokens)?;
+
+        if let Some(span) = tokens.span_since(start) {
+            spans
+                .variants
+                .insert((enum_index, variants.next_index()), span);
+        }
+
+        variants.push(Variant { name, payload });
+    }
+
+    Ok(variants)
+}
+
 fn parse_function(
     tokens: &mut Tokens,
     location: FunctionLocation,
+    spans: &mut Spans,
 ) -> Result<Function> {
     let mut branches = IndexMap::default();
 
@@ -147,7 +421,7 @@ fn parse_function(
             index: branches.next_index(),
         };
 
-        let Some(branch) = parse_branch(tokens, location)? else {
+        let Some(branch) = parse_branch(tokens, location, spans)? else {
             break;
         };
 
@@ -167,8 +441,10 @@ fn parse_function(
 fn parse_branch(
     tokens: &mut Tokens,
     location: BranchLocation,
+    spans: &mut Spans,
 ) -> Result<Option<Branch>> {
     let comment = parse_comment(tokens)?;
+    let start = tokens.position();
 
     match tokens.peek()? {
         Token::Keyword(Br) => {
@@ -185,8 +461,12 @@ fn parse_branch(
         }
     }
 
-    let parameters = parse_branch_parameters(tokens)?;
-    let body = parse_branch_body(tokens, location)?;
+    let parameters = parse_branch_parameters(tokens, &location, spans)?;
+    let body = parse_branch_body(tokens, location.clone(), spans)?;
+
+    if let Some(span) = tokens.span_since(start) {
+        spans.branches.insert(location, span);
+    }
 
     Ok(Some(Branch {
         comment,
@@ -195,32 +475,80 @@ fn parse_branch(
     }))
 }
 
-fn parse_branch_parameters(tokens: &mut Tokens) -> Result<IndexMap<Parameter>> {
+fn parse_branch_parameters(
+    tokens: &mut Tokens,
+    location: &BranchLocation,
+    spans: &mut Spans,
+) -> Result<IndexMap<Parameter>> {
     let mut parameters = IndexMap::default();
 
+    let mut record_span = |index, span: Option<Span>| {
+        if let Some(span) = span {
+            let location = ParameterLocation {
+                parent: Box::new(location.clone()),
+                index,
+            };
+            spans.parameters.insert(location, span);
+        }
+    };
+
     loop {
         if let Token::Punctuator(Transformer) = tokens.peek()? {
             tokens.take()?;
             break;
         }
 
-        let parameter = parse_parameter(tokens)?;
-        parameters.push(parameter);
+        let start = tokens.position();
 
-        match tokens.take()? {
-            Token::Punctuator(Delimiter) => {
+        match parse_parameter_and_delimiter(tokens) {
+            Ok((parameter, span, Delimiter)) => {
                 // If we have a delimiter, then we're good here. Next loop
                 // iteration, we'll either parse the next parameter, or if it
                 // was the last one, find the start of the branch body.
+                let index = parameters.push(parameter);
+                record_span(index, span);
                 continue;
             }
-            Token::Punctuator(Transformer) => {
-                // The last parameter doesn't need a delimiter, so this is fine
-                // too.
+            Ok((parameter, span, _)) => {
+                // The last parameter doesn't need a delimiter, so finding the
+                // start of the branch body is fine too.
+                let index = parameters.push(parameter);
+                record_span(index, span);
                 break;
             }
-            token => {
-                return Err(Error::UnexpectedToken { actual: token });
+            Err(Error::UnexpectedToken { actual }) => {
+                if !matches!(actual, Token::Keyword(_)) {
+                    // Whatever is wrong with this parameter, there's a good
+                    // chance that the next one is fine. Let's skip ahead to it.
+                    loop {
+                        match tokens.peek()? {
+                            Token::Punctuator(Delimiter) => {
+                                tokens.take()?;
+                                break;
+                            }
+                            Token::Punctuator(Transformer)
+                            | Token::Keyword(_) => {
+                                break;
+                            }
+                            _ => {
+                                tokens.take()?;
+                            }
+                        }
+                    }
+                }
+
+                let error = recover(tokens, start, Some(actual));
+                let index = parameters.push(Parameter::Error(error));
+                record_span(index, tokens.span_since(start));
+
+                if let Token::Keyword(End) = tokens.peek()? {
+                    // The branch ends without ever getting to a body. Leave
+                    // the `end` for the code parsing the body to find.
+                    break;
+                }
+            }
+            Err(err) => {
+                return Err(err);
             }
         }
     }
@@ -228,9 +556,33 @@ fn parse_branch_parameters(tokens: &mut Tokens) -> Result<IndexMap<Parameter>> {
     Ok(parameters)
 }
 
+/// # Parse a parameter, and the punctuator that follows it
+///
+/// Only returns successfully, if that punctuator is either a delimiter or the
+/// transformer that starts the branch body. The returned span only covers the
+/// parameter, not the punctuator.
+fn parse_parameter_and_delimiter(
+    tokens: &mut Tokens,
+) -> Result<(Parameter, Option<Span>, Punctuator)> {
+    let start = tokens.position();
+    let parameter = parse_parameter(tokens)?;
+    let span = tokens.span_since(start);
+
+    match tokens.take()? {
+        Token::Punctuator(punctuator @ (Delimiter | Transformer)) => {
+            Ok((parameter, span, punctuator))
+        }
+        token => Err(Error::UnexpectedToken { actual: token }),
+    }
+}
+
 fn parse_parameter(tokens: &mut Tokens) -> Result<Parameter> {
     let parameter = match tokens.take()? {
         Token::Identifier { name } => {
+            if let Token::Punctuator(Terminator) = tokens.peek()? {
+                return parse_variant_parameter(tokens, name);
+            }
+
             let type_ = parse_type_annotation(tokens)?;
 
             Parameter::Binding {
@@ -238,9 +590,7 @@ fn parse_parameter(tokens: &mut Tokens) -> Result<Parameter> {
                 type_,
             }
         }
-        Token::IntegerLiteral { value } => Parameter::Literal {
-            value: value.into(),
-        },
+        Token::IntegerLiteral { value } => Parameter::Literal { value },
         token => {
             return Err(Error::UnexpectedToken { actual: token });
         }
@@ -249,9 +599,48 @@ fn parse_parameter(tokens: &mut Tokens) -> Result<Parameter> {
     Ok(parameter)
 }
 
+/// # Parse the rest of a variant parameter, like `Shape.Circle radius`
+///
+/// Expects the name of the enum to already have been taken.
+fn parse_variant_parameter(
+    tokens: &mut Tokens,
+    enum_: String,
+) -> Result<Parameter> {
+    match tokens.take()? {
+        Token::Punctuator(Terminator) => {}
+        token => {
+            return Err(Error::UnexpectedToken { actual: token });
+        }
+    }
+
+    let variant = match tokens.take()? {
+        Token::Identifier { name } => name,
+        token => {
+            return Err(Error::UnexpectedToken { actual: token });
+        }
+    };
+
+    let binding = if let Token::Identifier { .. } = tokens.peek()? {
+        let Token::Identifier { name } = tokens.take()? else {
+            unreachable!("Just checked that the next token is an identifier.");
+        };
+
+        Some(Binding { name })
+    } else {
+        None
+    };
+
+    Ok(Parameter::Variant {
+        enum_,
+        variant,
+        binding,
+    })
+}
+
 fn parse_branch_body(
     tokens: &mut Tokens,
     location: BranchLocation,
+    spans: &mut Spans,
 ) -> Result<IndexMap<Member>> {
     let mut body = IndexMap::default();
 
@@ -265,7 +654,29 @@ fn parse_branch_body(
             parent: Box::new(location.clone()),
             index: body.next_index(),
         };
-        let member = parse_member(tokens, location)?;
+        let start = tokens.position();
+
+        // See comment in `parse`, on why we record spans separately here.
+        let mut member_spans = Spans::default();
+
+        let member =
+            match parse_member(tokens, location.clone(), &mut member_spans) {
+                Ok(member) => {
+                    append_spans(spans, member_spans);
+                    member
+                }
+                Err(Error::UnexpectedToken { actual }) => {
+                    Member::Error(recover(tokens, start, Some(actual)))
+                }
+                Err(err) => {
+                    return Err(err);
+                }
+            };
+
+        if let Some(span) = tokens.span_since(start) {
+            spans.members.insert(location, span);
+        }
+
         body.push(member);
     }
 
@@ -275,11 +686,13 @@ fn parse_branch_body(
 fn parse_member(
     tokens: &mut Tokens,
     location: MemberLocation,
+    spans: &mut Spans,
 ) -> Result<Member> {
     let member = if let Some(comment) = parse_comment(tokens)? {
         Member::Comment(comment)
     } else {
-        let (expression, signature) = parse_expression(tokens, location)?;
+        let (expression, signature) =
+            parse_expression(tokens, location, spans)?;
 
         Member::Expression {
             expression,
@@ -293,17 +706,29 @@ fn parse_member(
 fn parse_expression(
     tokens: &mut Tokens,
     location: MemberLocation,
+    spans: &mut Spans,
 ) -> Result<(Expression, Option<Signature<SyntaxType>>)> {
     let expression = if let Token::Keyword(Fn) = tokens.peek()? {
         let location = FunctionLocation::Local { location };
-        parse_function(tokens, location)
+        parse_function(tokens, location, spans)
             .map(|function| Expression::LocalFunction { function })?
     } else {
         match tokens.take()? {
-            Token::Identifier { name } => Expression::Identifier { name },
-            Token::IntegerLiteral { value } => Expression::LiteralNumber {
-                value: value.into(),
+            Token::Identifier { name } => {
+                let name = parse_field_access(tokens, name)?;
+                Expression::Identifier { name }
+            }
+            Token::Punctuator(Reference) => match tokens.take()? {
+                Token::Identifier { name } => {
+                    Expression::FunctionReference { name }
+                }
+                token => {
+                    return Err(Error::UnexpectedToken { actual: token });
+                }
             },
+            Token::IntegerLiteral { value } => {
+                Expression::LiteralNumber { value }
+            }
             token => {
                 return Err(Error::UnexpectedToken { actual: token });
             }
@@ -315,6 +740,28 @@ fn parse_expression(
     Ok((expression, signature))
 }
 
+/// # Parse any field accesses that follow an identifier
+///
+/// A field access, like `position.x`, becomes part of the identifier. It is up
+/// to later compiler passes to make sense of that.
+fn parse_field_access(tokens: &mut Tokens, mut name: String) -> Result<String> {
+    while let Token::Punctuator(Terminator) = tokens.peek()? {
+        tokens.take()?;
+
+        match tokens.take()? {
+            Token::Identifier { name: field } => {
+                name.push('.');
+                name.push_str(&field);
+            }
+            token => {
+                return Err(Error::UnexpectedToken { actual: token });
+            }
+        }
+    }
+
+    Ok(name)
+}
+
 fn parse_type_annotation(tokens: &mut Tokens) -> Result<Option<SyntaxType>> {
     let Token::Punctuator(Introducer) = tokens.peek()? else {
         return Ok(None);
@@ -335,14 +782,19 @@ fn parse_signature_annotation(
     tokens.take()?;
 
     let terminator = Token::Punctuator(Terminator);
-    let signature = parse_signature(tokens, terminator)?;
+    let signature = parse_signature(tokens, &[terminator])?;
+    tokens.take()?;
 
     Ok(Some(signature))
 }
 
+/// # Parse a signature
+///
+/// Stops right before any of the provided terminators, leaving it for the
+/// caller to take.
 fn parse_signature(
     tokens: &mut Tokens,
-    terminator: Token,
+    terminators: &[Token],
 ) -> Result<Signature<SyntaxType>> {
     let mut inputs = Vec::new();
 
@@ -371,26 +823,25 @@ fn parse_signature(
     let mut outputs = Vec::new();
 
     loop {
-        // We already handle the terminator at the end of the loop. We have to
-        // also handle it here, since it's allowed to not have any outputs,
-        // which would make the terminator the first token we encounter in this
-        // loop.
-        if *tokens.peek()? == terminator {
-            tokens.take()?;
+        // It's allowed to not have any outputs, which would make the
+        // terminator the first token we encounter in this loop.
+        if terminators.contains(tokens.peek()?) {
             break;
         }
 
         let type_ = parse_type(tokens)?;
         outputs.push(type_);
 
-        match tokens.take()? {
+        match tokens.peek()? {
             Token::Punctuator(Delimiter) => {
+                tokens.take()?;
                 continue;
             }
-            token if token == terminator => {
+            token if terminators.contains(token) => {
                 break;
             }
-            token => {
+            _ => {
+                let token = tokens.take()?;
                 return Err(Error::UnexpectedToken { actual: token });
             }
         }
@@ -403,9 +854,36 @@ fn parse_type(tokens: &mut Tokens) -> Result<SyntaxType> {
     let type_ = match tokens.take()? {
         Token::Identifier { name } => SyntaxType::Identifier { name },
         Token::Keyword(Fn) => {
-            let terminator = Token::Keyword(End);
-            let signature = parse_signature(tokens, terminator)?;
-            SyntaxType::Function { signature }
+            let terminators = [Token::Keyword(End), Token::Keyword(With)];
+            let signature = parse_signature(tokens, &terminators)?;
+
+            let mut environment = Vec::new();
+
+            if let Token::Keyword(With) = tokens.take()? {
+                loop {
+                    let type_ = parse_type(tokens)?;
+                    environment.push(type_);
+
+                    match tokens.take()? {
+                        Token::Punctuator(Delimiter) => {
+                            continue;
+                        }
+                        Token::Keyword(End) => {
+                            break;
+                        }
+                        token => {
+                            return Err(Error::UnexpectedToken {
+                                actual: token,
+                            });
+                        }
+                    }
+                }
+            }
+
+            SyntaxType::Function {
+                signature,
+                environment,
+            }
         }
         token => {
             return Err(Error::UnexpectedToken { actual: token });
@@ -415,6 +893,82 @@ fn parse_type(tokens: &mut Tokens) -> Result<SyntaxType> {
     Ok(type_)
 }
 
+/// # Recover from a syntax error
+///
+/// Skips ahead until every `fn`, `br`, `struct`, and `enum` that was taken
+/// since `start` has been closed by a matching `end`. Then records all tokens
+/// taken since `start` as a syntax error.
+///
+/// An `end` taken since `start` that doesn't close anything, must belong to an
+/// enclosing function or branch. Recovery stops right before such an `end`, to
+/// leave it for that enclosing function or branch.
+fn recover(
+    tokens: &mut Tokens,
+    start: usize,
+    unexpected: Option<Token>,
+) -> SyntaxError {
+    let mut depth = 0;
+    let mut unmatched_end = None;
+
+    for (i, token) in tokens.taken_since(start).iter().enumerate() {
+        match token {
+            Token::Keyword(Br | Enum | Fn | Struct) => {
+                depth += 1;
+            }
+            Token::Keyword(End) => {
+                if depth == 0 {
+                    unmatched_end = Some(start + i);
+                    break;
+                }
+
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(position) = unmatched_end {
+        tokens.rewind(position);
+    } else {
+        while depth > 0 {
+            let Ok(token) = tokens.take() else {
+                // We're not going to find the `end`s we're looking for. Not
+                // much we can do about that here, but whoever called us is
+                // going to notice, next time they look for a token.
+                break;
+            };
+
+            match token {
+                Token::Keyword(Br | Enum | Fn | Struct) => {
+                    depth += 1;
+                }
+                Token::Keyword(End) => {
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    SyntaxError {
+        unexpected,
+        tokens: tokens.taken_since(start).to_vec(),
+    }
+}
+
+fn append_spans(spans: &mut Spans, mut other: Spans) {
+    spans.named_functions.append(&mut other.named_functions);
+    spans.structs.append(&mut other.structs);
+    spans.enums.append(&mut other.enums);
+    spans.fields.append(&mut other.fields);
+    spans.variants.append(&mut other.variants);
+    spans.branches.append(&mut other.branches);
+    spans.parameters.append(&mut other.parameters);
+    spans.members.append(&mut other.members);
+    spans.imports.append(&mut other.imports);
+    spans.errors.append(&mut other.errors);
+}
+
 type Result<T> = result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
@@ -425,3 +979,193 @@ enum Error {
     #[error("Unexpected token: {actual:?}")]
     UnexpectedToken { actual: Token },
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::code::{
+        syntax::{Expression, Member, Parameter, SyntaxTree},
+        tokens::{Keyword, Punctuator, Token, Tokens},
+        Span,
+    };
+
+    #[test]
+    fn recover_from_error_in_branch_body() {
+        // An unexpected token in a branch body should be recorded as a syntax
+        // error, without affecting the rest of the branch.
+
+        let syntax_tree = parse(
+            r"
+                f: fn
+                    br ->
+                        a
+                        ->
+                        b
+                    end
+                end
+            ",
+        );
+
+        let function = syntax_tree
+            .function_by_name("f")
+            .unwrap()
+            .into_located_function();
+        let branch = function.find_single_branch().unwrap();
+        let members = branch.body.values().collect::<Vec<_>>();
+
+        let [a, Member::Error(error), b] = members.as_slice() else {
+            panic!("Unexpected members: {members:#?}");
+        };
+        assert_eq!(
+            a.as_expression(),
+            Some(&Expression::Identifier { name: "a".into() }),
+        );
+        assert_eq!(
+            error.unexpected,
+            Some(Token::Punctuator(Punctuator::Transformer)),
+        );
+        assert_eq!(
+            b.as_expression(),
+            Some(&Expression::Identifier { name: "b".into() }),
+        );
+    }
+
+    #[test]
+    fn recover_from_error_in_branch_parameters() {
+        // An unexpected token in the parameter list of a branch should be
+        // recorded as a syntax error, without affecting the rest of the branch.
+
+        let syntax_tree = parse(
+            r"
+                f: fn
+                    br a b, c ->
+                        c
+                    end
+                end
+            ",
+        );
+
+        let function = syntax_tree
+            .function_by_name("f")
+            .unwrap()
+            .into_located_function();
+        let branch = function.find_single_branch().unwrap();
+        let parameters = branch.parameters.values().collect::<Vec<_>>();
+
+        let [Parameter::Error(error), Parameter::Binding { binding, .. }] =
+            parameters.as_slice()
+        else {
+            panic!("Unexpected parameters: {parameters:#?}");
+        };
+        assert_eq!(
+            error.tokens,
+            [
+                Token::Identifier { name: "a".into() },
+                Token::Identifier { name: "b".into() },
+                Token::Punctuator(Punctuator::Delimiter),
+            ],
+        );
+        assert_eq!(binding.name, "c");
+        assert_eq!(branch.body.len(), 1);
+    }
+
+    #[test]
+    fn recover_from_error_in_named_function() {
+        // If a named function as a whole can't be parsed, the error should
+        // include all of its tokens, up to its final `end`. Following functions
+        // should not be affected.
+
+        let syntax_tree = parse(
+            r"
+                f fn
+                    br ->
+                    end
+                end
+
+                g: fn
+                    br ->
+                    end
+                end
+            ",
+        );
+
+        assert!(syntax_tree.function_by_name("f").is_none());
+        assert!(syntax_tree.function_by_name("g").is_some());
+
+        let [error] = syntax_tree.errors.as_slice() else {
+            panic!("Unexpected errors: {:#?}", syntax_tree.errors);
+        };
+        assert_eq!(error.unexpected, Some(Token::Keyword(Keyword::Fn)));
+        assert_eq!(error.tokens.len(), 6);
+    }
+
+    #[test]
+    fn recover_from_unexpected_end_of_input() {
+        // If the input ends in the middle of a named function, that should be
+        // recorded as a syntax error.
+
+        let syntax_tree = parse(
+            r"
+                f: fn
+                    br ->
+                    end
+                end
+
+                g: fn
+                    br ->
+            ",
+        );
+
+        assert!(syntax_tree.function_by_name("f").is_some());
+        assert!(syntax_tree.function_by_name("g").is_none());
+
+        let [error] = syntax_tree.errors.as_slice() else {
+            panic!("Unexpected errors: {:#?}", syntax_tree.errors);
+        };
+        assert_eq!(error.unexpected, None);
+    }
+
+    #[test]
+    fn track_spans_of_syntax_elements() {
+        // The spans of named functions, branches, and members should point to
+        // the source code they were parsed from.
+
+        let input = "f: fn\n    br ->\n        a\n        bc\n    end\nend\n";
+        let syntax_tree = parse(input);
+
+        let function = syntax_tree
+            .function_by_name("f")
+            .unwrap()
+            .into_located_function();
+        let branch = function.find_single_branch().unwrap();
+        let (a, bc) = branch
+            .body()
+            .map(|member| member.location)
+            .collect_tuple()
+            .unwrap();
+
+        let spans = &syntax_tree.spans;
+        let span_of_function = spans.of_function(&function.location).unwrap();
+        let span_of_branch = spans.of_branch(&branch.location).unwrap();
+        let span_of_a = spans.of_member(&a).unwrap();
+        let span_of_bc = spans.of_member(&bc).unwrap();
+
+        let text = |span: &Span| &input[span.start.byte..span.end.byte];
+        assert_eq!(text(span_of_function), &input[..input.len() - 1]);
+        assert_eq!(
+            text(span_of_branch),
+            "br ->\n        a\n        bc\n    end"
+        );
+        assert_eq!(text(span_of_a), "a");
+        assert_eq!(text(span_of_bc), "bc");
+
+        assert_eq!(span_of_bc.start.to_string(), "4:9");
+        assert_eq!(span_of_bc.end.to_string(), "4:11");
+    }
+
+    fn parse(input: &str) -> SyntaxTree {
+        let tokens = Tokens::tokenize(input);
+        SyntaxTree::parse(tokens)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::code::{Index, IndexMap};
+
+use super::{function::Comment, module::Module, types::SyntaxType};
+
+/// # An enum
+///
+/// Enums are defined in the top-level context, like named functions and
+/// structs. A value of an enum is exactly one of the enum's variants.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Enum {
+    /// # The comment about the enum, if any
+    pub comment: Option<Comment>,
+
+    /// # The name of the enum
+    pub name: String,
+
+    /// # The module that the enum is defined in
+    pub module: Index<Module>,
+
+    /// # The variants of the enum
+    pub variants: IndexMap<Variant>,
+}
+
+/// # A variant of an enum
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Variant {
+    /// # The name of the variant
+    ///
+    /// The variant is referred to by the name of its enum, followed by its own
+    /// name, like `Direction.Up`. This refers to the variant's constructor,
+    /// which takes the payload, if the variant has one.
+    pub name: String,
+
+    /// # The type of the value that the variant carries, if any
+    pub payload: Option<SyntaxType>,
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use crate::code::Token;
+
+/// # A syntax error
+///
+/// The parser produces a syntax error, if it encounters tokens that it can't
+/// make sense of. Instead of giving up, it records those tokens here, then
+/// continues with whatever comes after them.
+///
+/// This means that code containing syntax errors can still be compiled and
+/// displayed, with the erroneous parts clearly marked.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
+pub struct SyntaxError {
+    /// # The token that the parser did not expect
+    ///
+    /// This is `None`, if the input ended while the parser still expected more
+    /// tokens.
+    pub unexpected: Option<Token>,
+
+    /// # The tokens that the parser skipped over, while recovering
+    ///
+    /// This includes all tokens that are part of the syntactic element in which
+    /// the error occurred, so the code can be displayed as it was written.
+    pub tokens: Vec<Token>,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, token) in self.tokens.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{token}")?;
+        }
+
+        Ok(())
+    }
+}
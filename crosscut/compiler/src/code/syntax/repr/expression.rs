@@ -1,5 +1,3 @@
-use crosscut_runtime::Value;
-
 use super::function::Function;
 
 /// # An expression within a function
@@ -23,10 +21,19 @@ pub enum Expression {
         name: String,
     },
 
+    /// # A reference to a named function or a host function
+    ///
+    /// Evaluates to the function as a value, which can be passed around and
+    /// later evaluated using `eval`.
+    FunctionReference {
+        /// # The name of the function
+        name: String,
+    },
+
     /// # A number literal
     LiteralNumber {
         /// The number defined by this literal
-        value: Value,
+        value: i64,
     },
 
     /// # A local function
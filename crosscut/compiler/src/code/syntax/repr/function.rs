@@ -1,8 +1,9 @@
-use crosscut_runtime::Value;
+use crate::code::{Index, IndexMap, Signature};
 
-use crate::code::{IndexMap, Signature};
-
-use super::{expression::Expression, types::SyntaxType};
+use super::{
+    error::SyntaxError, expression::Expression, module::Module,
+    types::SyntaxType,
+};
 
 /// # A function that has a name
 ///
@@ -16,6 +17,9 @@ pub struct NamedFunction {
     /// # The name of the function
     pub name: String,
 
+    /// # The module that the function is defined in
+    pub module: Index<Module>,
+
     /// # The function
     pub inner: Function,
 }
@@ -74,6 +78,13 @@ pub struct Branch {
 /// # A parameter
 ///
 /// Parameters match against the arguments of a function.
+///
+/// ## Implementation Note
+///
+/// There is no parameter that destructures a struct yet. A struct argument can
+/// only be bound to a name as a whole, and its fields accessed through that
+/// name. Supporting this would require a single parameter to introduce
+/// multiple bindings, which the rest of the compiler doesn't expect.
 #[derive(
     Clone,
     Debug,
@@ -105,20 +116,46 @@ pub enum Parameter {
     /// on the arguments of the function call.
     Literal {
         /// # The value that an argument is matched against
-        value: Value,
+        value: i64,
+    },
+
+    /// # The parameter is an enum variant
+    ///
+    /// Variant parameters only match against values of that variant. If the
+    /// variant has a payload, it can be bound to a name, which is available
+    /// locally in the branch.
+    Variant {
+        /// # The name of the enum
+        enum_: String,
+
+        /// # The name of the variant
+        variant: String,
+
+        /// # The binding of the variant's payload, if any
+        binding: Option<Binding>,
     },
+
+    /// # The parameter could not be parsed
+    ///
+    /// Everything from the start of this parameter, up to the start of the
+    /// branch body, is recorded in the syntax error.
+    Error(SyntaxError),
 }
 
 impl Parameter {
     /// # Convert this parameter into a binding
     ///
-    /// Returns `None`, if the parameter is not a binding.
+    /// Returns `None`, if the parameter doesn't bind a name. This includes
+    /// variants that don't bind their payload.
     pub fn as_binding(&self) -> Option<(&Binding, Option<&SyntaxType>)> {
-        let Self::Binding { binding, type_ } = self else {
-            return None;
-        };
-
-        Some((binding, type_.as_ref()))
+        match self {
+            Self::Binding { binding, type_ } => Some((binding, type_.as_ref())),
+            Self::Variant {
+                binding: Some(binding),
+                ..
+            } => Some((binding, None)),
+            _ => None,
+        }
     }
 }
 
@@ -165,6 +202,9 @@ pub enum Member {
         /// # The optional type annotation that applies to the expression
         signature: Option<Signature<SyntaxType>>,
     },
+
+    /// # The member could not be parsed
+    Error(SyntaxError),
 }
 
 impl Member {
@@ -1,4 +1,9 @@
+pub mod enums;
+pub mod error;
 pub mod expression;
 pub mod function;
+pub mod module;
+pub mod spans;
+pub mod structs;
 pub mod syntax_tree;
 pub mod types;
@@ -0,0 +1,29 @@
+use crate::code::Index;
+
+/// # A module
+///
+/// Every source file is a module. A module can call the functions defined
+/// within it, as well as those defined within the modules it imports.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Module {
+    /// # The name of the module
+    ///
+    /// This is the name of the source file, without the extension.
+    pub name: String,
+
+    /// # The names of the modules that this module imports
+    pub imports: Vec<String>,
+}
+
+impl Module {
+    /// # The name of the main module
+    pub const MAIN: &str = "main";
+
+    /// # The index of the main module
+    ///
+    /// The main module is where compilation starts, and where the `main`
+    /// function is expected. It always comes first.
+    pub fn main_index() -> Index<Module> {
+        Index::from(0)
+    }
+}
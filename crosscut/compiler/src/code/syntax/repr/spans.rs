@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use crate::code::{
+    syntax::{
+        BranchLocation, FunctionLocation, MemberLocation, ParameterLocation,
+    },
+    Index, Span,
+};
+
+use super::{
+    enums::{Enum, Variant},
+    function::NamedFunction,
+    module::Module,
+    structs::{Field, Struct},
+};
+
+/// # The spans of syntax elements within the source code
+///
+/// ## Implementation Note
+///
+/// Spans are tracked separately from the syntax elements they refer to, since
+/// those are hashed to detect changes in the code. Any edit near the top of the
+/// source code would move all the spans that come after it, making unchanged
+/// functions look changed.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct Spans {
+    pub(crate) named_functions: BTreeMap<Index<NamedFunction>, Span>,
+    pub(crate) structs: BTreeMap<Index<Struct>, Span>,
+    pub(crate) enums: BTreeMap<Index<Enum>, Span>,
+    pub(crate) fields: BTreeMap<(Index<Struct>, Index<Field>), Span>,
+    pub(crate) variants: BTreeMap<(Index<Enum>, Index<Variant>), Span>,
+    pub(crate) branches: BTreeMap<BranchLocation, Span>,
+    pub(crate) parameters: BTreeMap<ParameterLocation, Span>,
+    pub(crate) members: BTreeMap<MemberLocation, Span>,
+    pub(crate) imports: BTreeMap<(Index<Module>, usize), Span>,
+    pub(crate) errors: Vec<Span>,
+}
+
+impl Spans {
+    /// # Access the span of the named function at the provided index
+    pub fn of_named_function(
+        &self,
+        index: &Index<NamedFunction>,
+    ) -> Option<&Span> {
+        self.named_functions.get(index)
+    }
+
+    /// # Access the span of the struct at the provided index
+    pub fn of_struct(&self, index: &Index<Struct>) -> Option<&Span> {
+        self.structs.get(index)
+    }
+
+    /// # Access the span of the enum at the provided index
+    pub fn of_enum(&self, index: &Index<Enum>) -> Option<&Span> {
+        self.enums.get(index)
+    }
+
+    /// # Access the span of a field of the struct at the provided index
+    pub fn of_field(
+        &self,
+        struct_: &Index<Struct>,
+        field: &Index<Field>,
+    ) -> Option<&Span> {
+        self.fields.get(&(*struct_, *field))
+    }
+
+    /// # Access the span of a variant of the enum at the provided index
+    pub fn of_variant(
+        &self,
+        enum_: &Index<Enum>,
+        variant: &Index<Variant>,
+    ) -> Option<&Span> {
+        self.variants.get(&(*enum_, *variant))
+    }
+
+    /// # Access the span of the function at the provided location
+    ///
+    /// For local functions, this is the span of the expression that defines
+    /// them.
+    pub fn of_function(&self, location: &FunctionLocation) -> Option<&Span> {
+        match location {
+            FunctionLocation::Named { index } => self.of_named_function(index),
+            FunctionLocation::Local { location } => self.of_member(location),
+        }
+    }
+
+    /// # Access the span of the branch at the provided location
+    pub fn of_branch(&self, location: &BranchLocation) -> Option<&Span> {
+        self.branches.get(location)
+    }
+
+    /// # Access the span of the parameter at the provided location
+    pub fn of_parameter(&self, location: &ParameterLocation) -> Option<&Span> {
+        self.parameters.get(location)
+    }
+
+    /// # Access the span of the member at the provided location
+    pub fn of_member(&self, location: &MemberLocation) -> Option<&Span> {
+        self.members.get(location)
+    }
+
+    /// # Access the span of an import
+    ///
+    /// Expects the index of the import within the list of imports of the
+    /// provided module.
+    pub fn of_import(
+        &self,
+        module: &Index<Module>,
+        index: usize,
+    ) -> Option<&Span> {
+        self.imports.get(&(*module, index))
+    }
+
+    /// # Access the span of a top-level syntax error
+    ///
+    /// Expects the index of the syntax error within [`SyntaxTree`]'s list of
+    /// errors.
+    ///
+    /// [`SyntaxTree`]: super::syntax_tree::SyntaxTree
+    pub fn of_error(&self, index: usize) -> Option<&Span> {
+        self.errors.get(index)
+    }
+}
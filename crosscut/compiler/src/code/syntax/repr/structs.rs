@@ -0,0 +1,35 @@
+use crate::code::{Index, IndexMap};
+
+use super::{function::Comment, module::Module, types::SyntaxType};
+
+/// # A struct
+///
+/// Structs are defined in the top-level context, like named functions. A value
+/// of a struct is made up of the values of its fields.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Struct {
+    /// # The comment about the struct, if any
+    pub comment: Option<Comment>,
+
+    /// # The name of the struct
+    ///
+    /// The name also refers to the struct's constructor, which takes the
+    /// values of all fields, in the order they are defined in.
+    pub name: String,
+
+    /// # The module that the struct is defined in
+    pub module: Index<Module>,
+
+    /// # The fields of the struct
+    pub fields: IndexMap<Field>,
+}
+
+/// # A field of a struct
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Field {
+    /// # The name of the field
+    pub name: String,
+
+    /// # The type of the field
+    pub type_: SyntaxType,
+}
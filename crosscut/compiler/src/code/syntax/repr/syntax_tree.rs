@@ -1,27 +1,103 @@
 use std::iter;
 
-use crate::code::{
-    syntax::{
-        parse::parse, BranchLocation, FunctionLocation, Located,
-        ParameterLocation,
+use crate::{
+    code::{
+        syntax::{
+            parse::parse, BranchLocation, FunctionLocation, Located,
+            ParameterLocation,
+        },
+        Index, IndexMap, Tokens,
     },
-    IndexMap, Tokens,
+    sources::Sources,
 };
 
-use super::function::{Binding, Branch, Function, NamedFunction, Parameter};
+use super::{
+    enums::{Enum, Variant},
+    error::SyntaxError,
+    function::{Binding, Branch, Function, NamedFunction, Parameter},
+    module::Module,
+    spans::Spans,
+    structs::Struct,
+};
 
 /// # The syntax tree
 ///
 /// See [parent module](super).
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct SyntaxTree {
+    /// # The modules that make up the code
+    ///
+    /// The first one is always the main module.
+    pub modules: IndexMap<Module>,
+
     pub named_functions: IndexMap<NamedFunction>,
+
+    /// # The structs that are defined in the code
+    pub structs: IndexMap<Struct>,
+
+    /// # The enums that are defined in the code
+    pub enums: IndexMap<Enum>,
+
+    /// # Syntax errors that could not be attributed to a named function
+    ///
+    /// Most syntax errors are recorded right where they occur, within the
+    /// function that contains them. But if the parser can't even make sense of
+    /// an import or a named function as a whole (because its name is missing,
+    /// for example), the error ends up here.
+    pub errors: Vec<SyntaxError>,
+
+    /// # The spans of the syntax elements within the source code
+    pub spans: Spans,
 }
 
 impl SyntaxTree {
+    /// # Parse the tokens of a main module that doesn't import anything
     pub fn parse(tokens: Tokens) -> Self {
-        let named_functions = parse(tokens);
-        Self { named_functions }
+        let mut syntax_tree = Self::default();
+        syntax_tree.parse_module(Module::MAIN.to_string(), tokens);
+        syntax_tree
+    }
+
+    /// # Parse all provided source files, each into its own module
+    pub fn parse_sources(sources: &Sources) -> Self {
+        let mut syntax_tree = Self::default();
+
+        for (index, file) in sources.iter() {
+            let tokens = Tokens::tokenize_module(&file.code, index);
+            let actual_index =
+                syntax_tree.parse_module(file.module.clone(), tokens);
+
+            assert_eq!(
+                index, actual_index,
+                "Module has a different index than its source file.",
+            );
+        }
+
+        syntax_tree
+    }
+
+    fn parse_module(&mut self, name: String, tokens: Tokens) -> Index<Module> {
+        let index = self.modules.next_index();
+
+        let (imports, mut errors) = parse(
+            tokens,
+            index,
+            &mut self.named_functions,
+            &mut self.structs,
+            &mut self.enums,
+            &mut self.spans,
+        );
+
+        self.errors.append(&mut errors);
+        self.modules.push(Module { name, imports })
+    }
+
+    /// # Find the module with the provided name
+    pub fn module_by_name(&self, name: &str) -> Option<Index<Module>> {
+        self.modules
+            .iter()
+            .find(|(_, module)| module.name == name)
+            .map(|(&index, _)| index)
     }
 
     /// # Find the function at the provided location
@@ -70,6 +146,17 @@ impl SyntaxTree {
         })
     }
 
+    /// # Find the parameter at the provided location
+    ///
+    /// Returns `None`, if no parameter can be found at this location.
+    pub fn parameter_by_location<'r>(
+        &'r self,
+        location: &ParameterLocation,
+    ) -> Option<&'r Parameter> {
+        let branch = self.branch_by_location(&location.parent)?;
+        branch.fragment.parameters.get(&location.index)
+    }
+
     /// # Find the binding at the provided location
     ///
     /// Returns `None`, if no binding can be found at this location.
@@ -77,12 +164,8 @@ impl SyntaxTree {
         &'r self,
         location: &ParameterLocation,
     ) -> Option<Located<&'r Binding>> {
-        let branch = self.branch_by_location(&location.parent)?;
-        let parameter = branch.parameters.get(&location.index)?;
-
-        let Parameter::Binding { binding, .. } = parameter else {
-            return None;
-        };
+        let parameter = self.parameter_by_location(location)?;
+        let (binding, _) = parameter.as_binding()?;
 
         Some(Located {
             fragment: binding,
@@ -101,6 +184,59 @@ impl SyntaxTree {
             .find(|function| function.name == name)
     }
 
+    /// # Find the function with the provided name in the provided module
+    ///
+    /// Returns `None`, if the module has no function with this name.
+    pub fn function_by_name_in_module(
+        &self,
+        name: &str,
+        module: &Index<Module>,
+    ) -> Option<Located<&NamedFunction>> {
+        self.named_functions().find(|function| {
+            function.name == name && function.module == *module
+        })
+    }
+
+    /// # Find the struct with the provided name
+    ///
+    /// Unlike functions, structs are visible from all modules, without having
+    /// to be imported.
+    ///
+    /// Returns `None`, if no struct with this name can be found.
+    pub fn struct_by_name(&self, name: &str) -> Option<Located<&Struct>> {
+        self.structs().find(|struct_| struct_.name == name)
+    }
+
+    /// # Find the enum with the provided name
+    ///
+    /// Like structs, enums are visible from all modules.
+    ///
+    /// Returns `None`, if no enum with this name can be found.
+    pub fn enum_by_name(&self, name: &str) -> Option<Located<&Enum>> {
+        self.enums().find(|enum_| enum_.name == name)
+    }
+
+    /// # Find the enum variant with the provided name
+    ///
+    /// Expects the name of the variant to be qualified with the name of its
+    /// enum, like `Direction.Up`.
+    ///
+    /// Returns `None`, if no such variant can be found.
+    pub fn variant_by_name(
+        &self,
+        name: &str,
+    ) -> Option<(Located<&Enum>, Index<Variant>)> {
+        let (enum_, variant) = name.split_once('.')?;
+
+        let enum_ = self.enum_by_name(enum_)?;
+        let (&index, _) = enum_
+            .variants
+            .iter()
+            .find(|(_, candidate)| candidate.name == variant)?;
+
+        Some((enum_, index))
+    }
+
     /// # Find the top-level parent of a given function
     ///
     /// If the function at the provided location has no parent, the function
@@ -139,6 +275,22 @@ impl SyntaxTree {
             })
     }
 
+    /// # Iterate over the structs
+    pub fn structs(&self) -> impl Iterator<Item = Located<&Struct>> {
+        self.structs.iter().map(|(&index, struct_)| Located {
+            fragment: struct_,
+            location: index,
+        })
+    }
+
+    /// # Iterate over the enums
+    pub fn enums(&self) -> impl Iterator<Item = Located<&Enum>> {
+        self.enums.iter().map(|(&index, enum_)| Located {
+            fragment: enum_,
+            location: index,
+        })
+    }
+
     /// # Iterate over all functions, both named and anonymous
     pub fn all_functions(&self) -> impl Iterator<Item = Located<&Function>> {
         self.named_functions().flat_map(|named_function| {
@@ -25,6 +25,12 @@ pub enum SyntaxType {
     Function {
         /// # The signature of the function
         signature: Signature<Self>,
+
+        /// # The types of the values that the function captured
+        ///
+        /// This is empty, if the type doesn't list any captured values after
+        /// `with`.
+        environment: Vec<Self>,
     },
 
     /// # An identifier that refers to a type
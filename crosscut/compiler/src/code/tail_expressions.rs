@@ -1,6 +1,6 @@
 use std::collections::BTreeSet;
 
-use super::syntax::{Expression, MemberLocation, SyntaxTree};
+use super::syntax::{Expression, Member, MemberLocation, SyntaxTree};
 
 /// # Tracks tail expressions
 ///
@@ -24,7 +24,18 @@ impl TailExpressions {
 
         for function in syntax_tree.all_functions() {
             for branch in function.branches() {
-                if let Some(expression) = branch.expressions().last() {
+                // A syntax error at the end of the branch might have been
+                // meant as an expression. So we can't skip it, like we do with
+                // comments, when looking for the last expression.
+                let last_expression = branch
+                    .body()
+                    .rev()
+                    .find(|member| {
+                        !matches!(member.fragment, Member::Comment(_))
+                    })
+                    .and_then(|member| member.into_expression());
+
+                if let Some((expression, _)) = last_expression {
                     if let Expression::Identifier { .. } = expression.fragment {
                         tail_expressions.insert(expression.location);
                     }
@@ -1,7 +1,19 @@
+use std::fmt;
+
 /// # A token
 ///
 /// See [parent module](super).
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
 pub enum Token {
     /// # A line of a comment
     ///
@@ -20,7 +32,7 @@ pub enum Token {
     /// # An integer literal
     IntegerLiteral {
         /// # The value of the integer literal
-        value: i32,
+        value: i64,
     },
 
     /// # A keyword
@@ -30,11 +42,33 @@ pub enum Token {
     Punctuator(Punctuator),
 }
 
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CommentLine { line } => write!(f, "#{line}"),
+            Self::Identifier { name } => write!(f, "{name}"),
+            Self::IntegerLiteral { value } => write!(f, "{value}"),
+            Self::Keyword(keyword) => write!(f, "{keyword}"),
+            Self::Punctuator(punctuator) => write!(f, "{punctuator}"),
+        }
+    }
+}
+
 /// # Keywords
 ///
 /// A keyword is a specific word with special meaning in the language, that is
 /// delimited by whitespace.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
 pub enum Keyword {
     /// # The `br` keyword
     Br,
@@ -42,15 +76,53 @@ pub enum Keyword {
     /// # The `end` keyword
     End,
 
+    /// # The `enum` keyword
+    Enum,
+
     /// # The `fn` keyword
     Fn,
+
+    /// # The `import` keyword
+    Import,
+
+    /// # The `struct` keyword
+    Struct,
+
+    /// # The `with` keyword
+    With,
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keyword = match self {
+            Self::Br => "br",
+            Self::End => "end",
+            Self::Enum => "enum",
+            Self::Fn => "fn",
+            Self::Import => "import",
+            Self::Struct => "struct",
+            Self::With => "with",
+        };
+
+        write!(f, "{keyword}")
+    }
 }
 
 /// # Punctuators
 ///
 /// A punctuator is a token with syntactic and semantic meaning to the compiler,
 /// that in itself is never an expression.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
 pub enum Punctuator {
     /// # A delimiter in a list, rendered as `,`
     Delimiter,
@@ -61,6 +133,12 @@ pub enum Punctuator {
     /// previous one, that it relates to.
     Introducer,
 
+    /// # A reference to a function, rendered as `&`
+    ///
+    /// Shows up in the form of `&f`, which refers to the function `f` as a
+    /// value, instead of calling it.
+    Reference,
+
     /// # An indication that a thing is transformed to another, rendered as `->`
     ///
     /// Shows up in the form of `a -> b`, where `a` could be the parameter list
@@ -71,3 +149,17 @@ pub enum Punctuator {
     /// # A token that ends an expression, where necessary, rendered as `.`
     Terminator,
 }
+
+impl fmt::Display for Punctuator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let punctuator = match self {
+            Self::Delimiter => ",",
+            Self::Introducer => ":",
+            Self::Reference => "&",
+            Self::Transformer => "->",
+            Self::Terminator => ".",
+        };
+
+        write!(f, "{punctuator}")
+    }
+}
@@ -1,19 +1,21 @@
 use std::mem;
 
-use crate::code::tokens::Token;
+use crate::code::{syntax::Module, tokens::Token, Index, Position, Span};
 
 use super::{Keyword::*, Punctuator::*};
 
-pub fn tokenize(input: &str) -> Vec<Token> {
+pub fn tokenize(input: &str, module: Index<Module>) -> Vec<(Token, Span)> {
     let eager_tokens = vec![
         (r",", Token::Punctuator(Delimiter)),
         (r":", Token::Punctuator(Introducer)),
+        (r"&", Token::Punctuator(Reference)),
         (r"->", Token::Punctuator(Transformer)),
         (r".", Token::Punctuator(Terminator)),
     ];
 
     let mut state = State::Initial;
-    let mut buffer = Buffer::default();
+    let mut buffer = Buffer::new(module);
+    let mut position = Position::default();
 
     let mut tokens = Vec::new();
 
@@ -22,36 +24,43 @@ pub fn tokenize(input: &str) -> Vec<Token> {
             State::Initial => match ch {
                 '#' => {
                     buffer.take_literal_or_keyword_or_identifier(&mut tokens);
-                    state = State::Comment;
+                    state = State::Comment { start: position };
                 }
                 ch if ch.is_whitespace() => {
                     buffer.take_literal_or_keyword_or_identifier(&mut tokens);
                 }
                 ch => {
-                    buffer.push(ch);
+                    buffer.push(ch, position);
 
                     for (s, token) in &eager_tokens {
-                        if buffer.take_from_end(s) {
+                        if let Some(span) = buffer.take_from_end(s) {
                             buffer.take_literal_or_keyword_or_identifier(
                                 &mut tokens,
                             );
-                            tokens.push(token.clone());
+                            tokens.push((token.clone(), span));
                         }
                     }
                 }
             },
-            State::Comment => match ch {
+            State::Comment { start } => match ch {
                 '\n' => {
-                    tokens.push(Token::CommentLine {
-                        line: buffer.take(),
-                    });
+                    let (line, _) = buffer.take();
+                    let span = Span {
+                        module,
+                        start,
+                        end: position,
+                    };
+
+                    tokens.push((Token::CommentLine { line }, span));
                     state = State::Initial;
                 }
                 ch => {
-                    buffer.push(ch);
+                    buffer.push(ch, position);
                 }
             },
         }
+
+        position.advance(ch);
     }
 
     tokens
@@ -59,24 +68,44 @@ pub fn tokenize(input: &str) -> Vec<Token> {
 
 enum State {
     Initial,
-    Comment,
+    Comment { start: Position },
 }
 
-#[derive(Default)]
 struct Buffer {
     inner: String,
+    span: Option<Span>,
+    module: Index<Module>,
 }
 
 impl Buffer {
-    pub fn push(&mut self, ch: char) {
+    pub fn new(module: Index<Module>) -> Self {
+        Self {
+            inner: String::new(),
+            span: None,
+            module,
+        }
+    }
+
+    pub fn push(&mut self, ch: char, position: Position) {
         self.inner.push(ch);
+
+        let mut end = position;
+        end.advance(ch);
+
+        let start = self.span.map(|span| span.start).unwrap_or(position);
+        self.span = Some(Span {
+            module: self.module,
+            start,
+            end,
+        });
     }
 
-    pub fn take(&mut self) -> String {
-        mem::take(&mut self.inner)
+    pub fn take(&mut self) -> (String, Span) {
+        let span = self.span.take().unwrap_or_default();
+        (mem::take(&mut self.inner), span)
     }
 
-    pub fn take_if_not_empty(&mut self) -> Option<String> {
+    pub fn take_if_not_empty(&mut self) -> Option<(String, Span)> {
         if self.inner.is_empty() {
             None
         } else {
@@ -86,29 +115,78 @@ impl Buffer {
 
     pub fn take_literal_or_keyword_or_identifier(
         &mut self,
-        tokens: &mut Vec<Token>,
+        tokens: &mut Vec<(Token, Span)>,
     ) {
-        tokens.extend(self.take_if_not_empty().map(|token| {
-            if let Ok(value) = token.parse() {
+        tokens.extend(self.take_if_not_empty().map(|(token, span)| {
+            let token = if let Some(value) = parse_integer_literal(&token) {
                 Token::IntegerLiteral { value }
             } else if token == "br" {
                 Token::Keyword(Br)
             } else if token == "end" {
                 Token::Keyword(End)
+            } else if token == "enum" {
+                Token::Keyword(Enum)
             } else if token == "fn" {
                 Token::Keyword(Fn)
+            } else if token == "import" {
+                Token::Keyword(Import)
+            } else if token == "struct" {
+                Token::Keyword(Struct)
+            } else if token == "with" {
+                Token::Keyword(With)
             } else {
                 Token::Identifier { name: token }
-            }
+            };
+
+            (token, span)
         }));
     }
 
-    pub fn take_from_end(&mut self, s: &str) -> bool {
-        if self.inner.ends_with(s) {
-            self.inner.truncate(self.inner.len() - s.len());
-            true
-        } else {
-            false
+    /// # Take the provided string from the end of the buffer, if it's there
+    ///
+    /// Returns the span of the string that was taken. Expects the string not to
+    /// contain any line breaks.
+    pub fn take_from_end(&mut self, s: &str) -> Option<Span> {
+        if !self.inner.ends_with(s) {
+            return None;
         }
+
+        self.inner.truncate(self.inner.len() - s.len());
+
+        let span = self.span?;
+        let mut start = span.end;
+        start.byte -= s.len();
+        start.column -= s.chars().count();
+
+        self.span = if self.inner.is_empty() {
+            None
+        } else {
+            Some(Span {
+                module: span.module,
+                start: span.start,
+                end: start,
+            })
+        };
+
+        Some(Span {
+            module: span.module,
+            start,
+            end: span.end,
+        })
     }
 }
+
+/// # Parse an integer literal
+///
+/// Integer literals can have any value that one of the integer types can
+/// represent, from the smallest `S32` to the largest `U32`. Which type a
+/// literal actually has is decided later, by type inference.
+fn parse_integer_literal(token: &str) -> Option<i64> {
+    let value = token.parse::<i64>().ok()?;
+
+    if value < i64::from(i32::MIN) || value > i64::from(u32::MAX) {
+        return None;
+    }
+
+    Some(value)
+}
@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use crate::code::{syntax::Module, Index, Span};
 
 use super::{tokenize::tokenize, Token};
 
@@ -6,29 +6,76 @@ use super::{tokenize::tokenize, Token};
 ///
 /// See [parent module](super).
 pub struct Tokens {
-    inner: VecDeque<Token>,
+    inner: Vec<Token>,
+    spans: Vec<Span>,
+    position: usize,
 }
 
 impl Tokens {
     /// # Tokenize the provided input
     ///
     /// Takes raw text, as input by the developer, and creates its tokenized
-    /// form.
+    /// form. Assumes that the input is the source code of the main module. Use
+    /// [`Tokens::tokenize_module`] for any other module.
     pub fn tokenize(input: &str) -> Self {
-        let tokens = tokenize(input);
+        Self::tokenize_module(input, Module::main_index())
+    }
+
+    /// # Tokenize the source code of the module with the provided index
+    pub fn tokenize_module(input: &str, module: Index<Module>) -> Self {
+        let (inner, spans) = tokenize(input, module).into_iter().unzip();
         Self {
-            inner: tokens.into(),
+            inner,
+            spans,
+            position: 0,
         }
     }
 
     /// # Peek at the next token without taking it
     pub fn peek(&self) -> Result<&Token, NoMoreTokens> {
-        self.inner.front().ok_or(NoMoreTokens)
+        self.inner.get(self.position).ok_or(NoMoreTokens)
     }
 
     /// # Take the next token
     pub fn take(&mut self) -> Result<Token, NoMoreTokens> {
-        self.inner.pop_front().ok_or(NoMoreTokens)
+        let token = self.peek()?.clone();
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// # Access the current position
+    ///
+    /// The position can be used to later access all tokens that have been
+    /// taken since (see [`Tokens::taken_since`]), or to return to it (see
+    /// [`Tokens::rewind`]).
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// # Access all tokens that have been taken since the provided position
+    pub fn taken_since(&self, position: usize) -> &[Token] {
+        &self.inner[position..self.position]
+    }
+
+    /// # Compute the span of all tokens taken since the provided position
+    ///
+    /// Returns `None`, if no tokens have been taken since then.
+    pub fn span_since(&self, position: usize) -> Option<Span> {
+        self.spans[position..self.position]
+            .iter()
+            .copied()
+            .reduce(Span::merge)
+    }
+
+    /// # Return to a previous position
+    ///
+    /// Any tokens taken since then become available to be taken again.
+    pub fn rewind(&mut self, position: usize) {
+        assert!(
+            position <= self.position,
+            "Can only rewind to a previous position.",
+        );
+        self.position = position;
     }
 }
 
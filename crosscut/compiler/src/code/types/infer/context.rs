@@ -8,6 +8,7 @@ use crate::code::{
 use super::{
     function::InferredFunction,
     signature::IndirectSignature,
+    stack::LocalStack,
     types::{InferredType, InferredTypes},
 };
 
@@ -17,6 +18,7 @@ pub struct InferenceContext {
     pub functions: BTreeMap<FunctionLocation, InferredFunction>,
     pub bindings: BTreeMap<ParameterLocation, Index<InferredType>>,
     pub expressions: BTreeMap<MemberLocation, IndirectSignature>,
+    pub stacks: BTreeMap<MemberLocation, LocalStack>,
 }
 
 impl InferenceContext {
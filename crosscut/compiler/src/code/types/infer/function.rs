@@ -62,22 +62,22 @@ mod tests {
 
         let a = InferredFunction {
             inputs: vec![
-                types.push(InferredType::Direct(Type::Number)),
+                types.push(InferredType::Direct(Type::S32)),
                 types.push(InferredType::Unknown),
             ],
             outputs: Some(vec![
-                types.push(InferredType::Direct(Type::Number)),
+                types.push(InferredType::Direct(Type::S32)),
                 types.push(InferredType::Unknown),
             ]),
         };
         let b = InferredFunction {
             inputs: vec![
                 types.push(InferredType::Unknown),
-                types.push(InferredType::Direct(Type::Number)),
+                types.push(InferredType::Direct(Type::S32)),
             ],
             outputs: Some(vec![
                 types.push(InferredType::Unknown),
-                types.push(InferredType::Direct(Type::Number)),
+                types.push(InferredType::Direct(Type::S32)),
             ]),
         };
 
@@ -92,8 +92,8 @@ mod tests {
                 assert_eq!(
                     signature,
                     Signature {
-                        inputs: vec![Type::Number, Type::Number],
-                        outputs: vec![Type::Number, Type::Number]
+                        inputs: vec![Type::S32, Type::S32],
+                        outputs: vec![Type::S32, Type::S32]
                     },
                 )
             }
@@ -106,7 +106,7 @@ mod tests {
 
         let a = InferredFunction {
             inputs: vec![],
-            outputs: Some(vec![types.push(InferredType::Direct(Type::Number))]),
+            outputs: Some(vec![types.push(InferredType::Direct(Type::S32))]),
         };
         let b = InferredFunction {
             inputs: vec![],
@@ -125,7 +125,7 @@ mod tests {
                     signature,
                     Signature {
                         inputs: vec![],
-                        outputs: vec![Type::Number]
+                        outputs: vec![Type::S32]
                     },
                 )
             }
@@ -5,13 +5,14 @@ use array_util::ArrayExt;
 use crate::{
     code::{
         syntax::{
-            Branch, Expression, FunctionLocation, Located, MemberLocation,
-            Parameter, ParameterLocation, SyntaxTree,
+            Branch, Expression, FunctionLocation, Located, Member,
+            MemberLocation, Parameter, ParameterLocation, SyntaxTree,
         },
         types::repr::Stacks,
-        Bindings, Dependencies, DependencyCluster, Environment,
-        IdentifierTarget, Identifiers, Signature, Type, TypeAnnotations,
+        Bindings, Constructor, Dependencies, DependencyCluster, Environment,
+        IdentifierTarget, Identifiers, Index, Signature, Type, TypeAnnotations,
     },
+    diagnostics::{Diagnostic, Diagnostics},
     intrinsics::IntrinsicFunction,
 };
 
@@ -20,10 +21,16 @@ use super::{
     function::InferredFunction,
     signature::{self, IndirectSignature},
     stack::{LocalStack, MaybeLocalStack},
-    types::{ExpectedType, InferredType, InferredTypes, Result, TypeError},
+    types::{
+        ActualType, ExpectedType, InferredType, InferredTypes, Result,
+        TypeError,
+    },
 };
 
-pub fn infer(compiler_context: CompilerContext) -> InferenceOutput {
+pub fn infer(
+    compiler_context: CompilerContext,
+    diagnostics: &mut Diagnostics,
+) -> InferenceOutput {
     let mut output = InferenceOutput::default();
 
     for cluster in compiler_context.dependencies.clusters() {
@@ -34,24 +41,50 @@ pub fn infer(compiler_context: CompilerContext) -> InferenceOutput {
         }) = infer_cluster(cluster, compiler_context, &mut output)
         {
             let actual = actual
-                .map(|type_| format!("`{type_}`"))
+                .map(|type_| type_.to_string())
                 .unwrap_or_else(|| "nothing".to_string());
 
-            let location = location
-                .map(|location| {
-                    format!(
-                        "at {}\n",
-                        location.display(compiler_context.syntax_tree)
-                    )
-                })
-                .unwrap_or(String::new());
+            // Inference of this cluster stops at the first error. Whatever
+            // types we could not infer up to that point, remain unknown.
+            let span = location.as_ref().and_then(|location| {
+                compiler_context
+                    .syntax_tree
+                    .spans
+                    .of_member(location)
+                    .copied()
+            });
+
+            // Host functions are defined outside of the code, so the
+            // diagnostic needs to provide everything that's required to call
+            // them correctly.
+            let host_function = location.as_ref().and_then(|location| {
+                match compiler_context.identifiers.is_resolved(location) {
+                    Some(IdentifierTarget::HostFunction(host_function)) => {
+                        Some(host_function)
+                    }
+                    _ => None,
+                }
+            });
 
-            panic!(
-                "\n\
-                Type error: expected {expected}, got {actual}\n\
-                \n\
-                {location}",
-            );
+            let diagnostic = match host_function {
+                Some(host_function) => Diagnostic::error(
+                    format!(
+                        "Type error in call to host function `{}`: expected \
+                        {expected}, got {actual}",
+                        host_function.name,
+                    ),
+                    span,
+                )
+                .with_note(format!(
+                    "`{}` has the signature `{}`",
+                    host_function.name, host_function.signature,
+                )),
+                None => Diagnostic::error(
+                    format!("Type error: expected {expected}, got {actual}"),
+                    span,
+                ),
+            };
+            diagnostics.push(diagnostic);
         }
     }
 
@@ -148,11 +181,19 @@ fn infer_cluster(
             "Did not expect to overwrite and existing expression signature.",
         );
     }
+    for (location, local_stack) in inference_context.stacks {
+        let Some(local_stack) =
+            local_stack.make_direct(&mut inference_context.types)?
+        else {
+            continue;
+        };
+
+        output.stacks.insert(location, local_stack);
+    }
 
     Ok(())
 }
 
-#[allow(clippy::type_complexity)]
 fn infer_branch(
     branch: Located<&Branch>,
     environment: &Environment,
@@ -160,50 +201,90 @@ fn infer_branch(
     compiler_context: CompilerContext,
     output: &mut InferenceOutput,
 ) -> Result<InferredFunction> {
-    let mut register_binding =
-        |location: ParameterLocation,
-         parameters: &BTreeMap<ParameterLocation, Type>| {
-            inference_context
-                .binding(&location, parameters)
-                .unwrap_or_else(|| {
-                    let type_ =
-                        inference_context.types.push(InferredType::Unknown);
-                    inference_context.bindings.insert(location, type_);
-                    type_
-                })
-        };
-
     let parameters = branch
         .parameters()
         .map(|parameter| {
-            let type_ = match parameter.fragment {
-                Parameter::Binding { .. } => compiler_context
-                    .annotations
-                    .of_binding(&parameter.location)
-                    .cloned(),
-                Parameter::Literal { .. } => Some(Type::Number),
-            };
-
-            if let Some(type_) = type_ {
-                output.parameters.insert(parameter.location.clone(), type_);
+            match parameter.fragment {
+                Parameter::Binding { .. } => {
+                    if let Some(type_) = compiler_context
+                        .annotations
+                        .of_binding(&parameter.location)
+                        .cloned()
+                    {
+                        output
+                            .parameters
+                            .insert(parameter.location.clone(), type_);
+                    }
+                }
+                Parameter::Variant { enum_, .. } => {
+                    // The type of a variant parameter is fully determined by
+                    // the enum it names, just like an explicit annotation.
+                    if let Some(type_) = compiler_context
+                        .syntax_tree
+                        .enum_by_name(enum_)
+                        .and_then(|enum_| {
+                            compiler_context
+                                .annotations
+                                .of_enum(&enum_.location)
+                        })
+                        .cloned()
+                    {
+                        output
+                            .parameters
+                            .insert(parameter.location.clone(), type_);
+                    }
+                }
+                Parameter::Literal { value } => {
+                    let value = *value;
+                    let type_ = inference_context.types.push(
+                        InferredType::IntegerLiteral {
+                            min: value,
+                            max: value,
+                        },
+                    );
+                    inference_context
+                        .bindings
+                        .insert(parameter.location.clone(), type_);
+                }
+                Parameter::Error(_) => {}
             }
 
-            register_binding(parameter.location, &output.parameters)
+            register_binding(
+                parameter.location,
+                inference_context,
+                &output.parameters,
+            )
         })
         .collect::<Vec<_>>();
 
     for binding in environment.bindings(compiler_context.syntax_tree) {
-        register_binding(binding.location, &output.parameters);
+        register_binding(
+            binding.location,
+            inference_context,
+            &output.parameters,
+        );
     }
 
     let mut local_stack = MaybeLocalStack::default();
-    let mut stacks = BTreeMap::new();
 
-    for expression in branch.expressions() {
+    for member in branch.body() {
+        if let Member::Error(_) = member.fragment {
+            // We have no idea what the erroneous code would have done to the
+            // stack, so we can't know anything about it from here on.
+            local_stack.invalidate();
+            continue;
+        }
+
+        let Some((expression, _)) = member.into_expression() else {
+            continue;
+        };
         let location = expression.location.clone();
 
+        // Information from a later expression could allow us to infer the
+        // type of an earlier one. So the stacks are converted into concrete
+        // types, only after we looked at the whole cluster.
         if let Some(stack) = local_stack.get_mut().cloned() {
-            stacks.insert(location.clone(), stack);
+            inference_context.stacks.insert(location.clone(), stack);
         }
 
         if let Some(signature) = infer_expression(
@@ -217,19 +298,6 @@ fn infer_branch(
         }
     }
 
-    // Information from a later expression could have allowed us to infer the
-    // type of an earlier one. So let's handle the stacks we collected _after_
-    // we look at all of the expressions.
-    for (location, local_stack) in stacks {
-        let Some(local_stack) =
-            local_stack.make_direct(&mut inference_context.types)?
-        else {
-            continue;
-        };
-
-        output.stacks.insert(location, local_stack);
-    }
-
     let inputs = parameters;
     let outputs = local_stack
         .get()
@@ -239,6 +307,20 @@ fn infer_branch(
     Ok(InferredFunction { inputs, outputs })
 }
 
+fn register_binding(
+    location: ParameterLocation,
+    inference_context: &mut InferenceContext,
+    parameters: &BTreeMap<ParameterLocation, Type>,
+) -> Index<InferredType> {
+    inference_context
+        .binding(&location, parameters)
+        .unwrap_or_else(|| {
+            let type_ = inference_context.types.push(InferredType::Unknown);
+            inference_context.bindings.insert(location, type_);
+            type_
+        })
+}
+
 fn infer_expression(
     expression: Located<&Expression>,
     inference_context: &mut InferenceContext,
@@ -282,11 +364,42 @@ fn infer_expression(
                                 binding.display(compiler_context.syntax_tree),
                             );
                         };
-                        let signature = Signature {
-                            inputs: vec![],
-                            outputs: vec![output],
+
+                        // A binding in a variant parameter refers to the
+                        // variant's payload, not the enum value as a whole.
+                        let output = match compiler_context
+                            .syntax_tree
+                            .parameter_by_location(binding)
+                        {
+                            Some(Parameter::Variant { variant, .. }) => {
+                                infer_payload(
+                                    output,
+                                    variant,
+                                    &mut inference_context.types,
+                                )?
+                            }
+                            _ => Some(output),
                         };
-                        Some(signature)
+
+                        // The identifier might access a field of the binding,
+                        // like `position.x`.
+                        let fields = identifier.split('.').skip(1);
+
+                        output
+                            .map(|output| {
+                                infer_field_access(
+                                    output,
+                                    fields,
+                                    &expression.location,
+                                    &mut inference_context.types,
+                                )
+                            })
+                            .transpose()?
+                            .flatten()
+                            .map(|output| Signature {
+                                inputs: vec![],
+                                outputs: vec![output],
+                            })
                     }
                     IdentifierTarget::HostFunction(host) => {
                         let signature = IndirectSignature::from_direct(
@@ -312,29 +425,88 @@ fn infer_expression(
                     IdentifierTarget::UserDefinedFunction(location) => {
                         inference_context.function(location, &output.functions)
                     }
+                    IdentifierTarget::Constructor(constructor) => {
+                        infer_constructor(constructor, compiler_context).map(
+                            |signature| {
+                                IndirectSignature::from_direct(
+                                    signature,
+                                    &mut inference_context.types,
+                                )
+                            },
+                        )
+                    }
                 },
                 None => None,
             }
         }
-        Expression::LiteralNumber { .. } => {
-            let signature = Signature {
-                inputs: vec![],
-                outputs: vec![Type::Number],
+        Expression::FunctionReference { .. } => {
+            let signature = match compiler_context
+                .identifiers
+                .is_resolved(&expression.location)
+            {
+                Some(IdentifierTarget::HostFunction(host)) => {
+                    Some(IndirectSignature::from_direct(
+                        host.signature.clone(),
+                        &mut inference_context.types,
+                    ))
+                }
+                Some(IdentifierTarget::UserDefinedFunction(location)) => {
+                    inference_context.function(location, &output.functions)
+                }
+                _ => None,
             };
-            let signature = IndirectSignature::from_direct(
-                signature,
-                &mut inference_context.types,
-            );
-            Some(signature)
+
+            signature.map(|signature| {
+                // Named functions and host functions don't capture anything
+                // from an environment.
+                let function = inference_context.types.push(
+                    InferredType::IndirectFunction {
+                        signature,
+                        environment: Vec::new(),
+                    },
+                );
+
+                Signature {
+                    inputs: vec![],
+                    outputs: vec![function],
+                }
+            })
+        }
+        Expression::LiteralNumber { value } => {
+            let value = *value;
+            let output =
+                inference_context.types.push(InferredType::IntegerLiteral {
+                    min: value,
+                    max: value,
+                });
+
+            Some(Signature {
+                inputs: vec![],
+                outputs: vec![output],
+            })
         }
         Expression::LocalFunction { .. } => {
             let location = FunctionLocation::from(expression.location.clone());
+            let environment = compiler_context
+                .bindings
+                .environment_of(&location)
+                .bindings(compiler_context.syntax_tree)
+                .map(|binding| {
+                    register_binding(
+                        binding.location,
+                        inference_context,
+                        &output.parameters,
+                    )
+                })
+                .collect::<Vec<_>>();
+
             inference_context
                 .function(&location, &output.functions)
                 .map(|signature| {
                     let function = inference_context.types.push(
                         InferredType::IndirectFunction {
                             signature: signature.clone(),
+                            environment,
                         },
                     );
 
@@ -391,6 +563,17 @@ fn infer_expression(
                 match local_stack.inner.pop() {
                     Some(operand) => {
                         inference_context.types.unify([&operand, input]);
+
+                        // Resolve the unified type right away, so any
+                        // mismatch gets reported at this expression.
+                        inference_context.types.resolve(input).map_err(
+                            |err| TypeError {
+                                location: err
+                                    .location
+                                    .or(Some(expression.location.clone())),
+                                ..err
+                            },
+                        )?;
                     }
                     None => {
                         let input = inference_context.types.resolve(input)?;
@@ -418,6 +601,106 @@ fn infer_expression(
     Ok(None)
 }
 
+fn infer_constructor(
+    constructor: &Constructor,
+    compiler_context: CompilerContext,
+) -> Option<Signature> {
+    match constructor {
+        Constructor::Struct { index } => {
+            let type_ = compiler_context.annotations.of_struct(index)?;
+            let Type::Struct { fields, .. } = type_ else {
+                unreachable!("The type of a struct must be a struct.");
+            };
+
+            Some(Signature {
+                inputs: fields.iter().map(|(_, type_)| type_.clone()).collect(),
+                outputs: vec![type_.clone()],
+            })
+        }
+        Constructor::Variant { enum_, variant } => {
+            let type_ = compiler_context.annotations.of_enum(enum_)?;
+            let variant = compiler_context
+                .syntax_tree
+                .enums
+                .get(enum_)?
+                .variants
+                .get(variant)?;
+            let Some((_, payload)) = type_.variant(&variant.name) else {
+                unreachable!("The type of an enum must have its variants.");
+            };
+
+            Some(Signature {
+                inputs: payload.cloned().into_iter().collect(),
+                outputs: vec![type_.clone()],
+            })
+        }
+    }
+}
+
+fn infer_payload(
+    enum_: Index<InferredType>,
+    variant: &str,
+    types: &mut InferredTypes,
+) -> Result<Option<Index<InferredType>>> {
+    let InferredType::Direct(type_) = types.resolve(&enum_)? else {
+        return Ok(None);
+    };
+    let Some((_, Some(payload))) = type_.variant(variant) else {
+        return Ok(None);
+    };
+
+    Ok(Some(types.push(InferredType::Direct(payload.clone()))))
+}
+
+fn infer_field_access<'r>(
+    binding: Index<InferredType>,
+    fields: impl IntoIterator<Item = &'r str>,
+    location: &MemberLocation,
+    types: &mut InferredTypes,
+) -> Result<Option<Index<InferredType>>> {
+    let mut fields = fields.into_iter().peekable();
+    if fields.peek().is_none() {
+        return Ok(Some(binding));
+    }
+
+    let mut type_ = match types.resolve(&binding)? {
+        InferredType::Direct(type_) => type_,
+        InferredType::Unknown => {
+            return Ok(None);
+        }
+        InferredType::IntegerLiteral { .. } => {
+            return Err(TypeError {
+                expected: ExpectedType::Struct,
+                actual: Some(ActualType::IntegerLiteral),
+                location: Some(location.clone()),
+            });
+        }
+        InferredType::IndirectFunction { .. } => {
+            return Err(TypeError {
+                expected: ExpectedType::Struct,
+                actual: None,
+                location: Some(location.clone()),
+            });
+        }
+    };
+
+    for field in fields {
+        let Some(field_type) = type_.field(field) else {
+            return Err(TypeError {
+                expected: ExpectedType::Field {
+                    name: field.to_string(),
+                },
+                actual: Some(ActualType::Specific(type_)),
+                location: Some(location.clone()),
+            });
+        };
+
+        type_ = field_type.clone();
+    }
+
+    Ok(Some(types.push(InferredType::Direct(type_))))
+}
+
 fn infer_intrinsic(
     intrinsic: &IntrinsicFunction,
     location: &MemberLocation,
@@ -441,28 +724,83 @@ fn infer_intrinsic(
                 outputs: vec![],
             })
         }
-        IntrinsicFunction::Eval => {
+        IntrinsicFunction::Eq => {
+            // Structs, enums with payloads, and functions that capture values
+            // are made up of multiple values, but `eq` can only compare single
+            // values.
             let top_operand = local_stack
                 .inner
                 .last()
                 .map(|index| types.resolve(index))
                 .transpose()?;
 
-            let signature = match top_operand {
-                Some(InferredType::IndirectFunction { signature }) => {
-                    Some(signature)
+            let actual = match top_operand {
+                Some(InferredType::Direct(type_)) if type_.num_values() > 1 => {
+                    Some(Some(ActualType::Specific(type_)))
                 }
-                Some(InferredType::Direct(Type::Function { signature })) => {
-                    Some(IndirectSignature::from_direct(signature, types))
+                Some(InferredType::IndirectFunction {
+                    environment, ..
+                }) if !environment.is_empty() => Some(None),
+                _ => None,
+            };
+
+            if let Some(actual) = actual {
+                return Err(TypeError {
+                    expected: ExpectedType::SingleValue,
+                    actual,
+                    location: Some(location.clone()),
+                });
+            }
+
+            let type_ = types.push(InferredType::Unknown);
+            let output = types.push(InferredType::Direct(Type::Bool));
+
+            Some(Signature {
+                inputs: vec![type_, type_],
+                outputs: vec![output],
+            })
+        }
+        IntrinsicFunction::Eval => {
+            let top_operand = local_stack
+                .inner
+                .last()
+                .map(|index| types.resolve(index))
+                .transpose()?;
+
+            let function = match top_operand {
+                Some(InferredType::IndirectFunction {
+                    signature,
+                    environment,
+                }) => Some((signature, environment)),
+                Some(InferredType::Direct(Type::Function {
+                    signature,
+                    environment,
+                })) => {
+                    let signature =
+                        IndirectSignature::from_direct(signature, types);
+                    let environment = environment
+                        .into_vec()
+                        .into_iter()
+                        .map(|type_| types.push(InferredType::Direct(type_)))
+                        .collect();
+
+                    Some((signature, environment))
                 }
                 Some(InferredType::Direct(actual)) => {
                     return Err(TypeError {
                         expected: ExpectedType::Function,
-                        actual: Some(actual.clone()),
+                        actual: Some(ActualType::Specific(actual.clone())),
                         location: Some(location.clone()),
                     });
                 }
-                Some(InferredType::Unknown { .. }) => None,
+                Some(InferredType::IntegerLiteral { .. }) => {
+                    return Err(TypeError {
+                        expected: ExpectedType::Function,
+                        actual: Some(ActualType::IntegerLiteral),
+                        location: Some(location.clone()),
+                    });
+                }
+                Some(InferredType::Unknown) => None,
                 None => {
                     return Err(TypeError {
                         expected: ExpectedType::Function,
@@ -472,14 +810,16 @@ fn infer_intrinsic(
                 }
             };
 
-            signature.map(|signature| {
+            function.map(|(signature, environment)| {
                 let outputs = signature.outputs.clone();
                 let inputs = signature
                     .inputs
                     .clone()
                     .into_iter()
-                    .chain([types
-                        .push(InferredType::IndirectFunction { signature })])
+                    .chain([types.push(InferredType::IndirectFunction {
+                        signature,
+                        environment,
+                    })])
                     .collect();
 
                 IndirectSignature { inputs, outputs }
@@ -1,3 +1,8 @@
+// A `TypeError` carries the expected and actual types, which makes it large.
+// It ends inference of the whole cluster, so there's no hot path where this
+// matters. Haven't optimized it yet.
+#![allow(clippy::result_large_err)]
+
 mod context;
 mod function;
 mod infer;
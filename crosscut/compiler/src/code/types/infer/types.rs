@@ -73,34 +73,138 @@ fn merge_inferred_types(
     types: &mut InferredTypes,
 ) -> Result<InferredType> {
     let type_ = match (a, b) {
+        (InferredType::Direct(Type::Error), _)
+        | (_, InferredType::Direct(Type::Error)) => {
+            // An error has already been reported for this type. Reporting
+            // another one for each use wouldn't help anyone.
+            InferredType::Direct(Type::Error)
+        }
         (
-            InferredType::IndirectFunction { signature: a },
-            InferredType::IndirectFunction { signature: b },
+            InferredType::IndirectFunction {
+                signature: a,
+                environment: environment_a,
+            },
+            InferredType::IndirectFunction {
+                signature: b,
+                environment: environment_b,
+            },
         ) => {
+            if environment_a.len() != environment_b.len() {
+                return Err(TypeError {
+                    expected: ExpectedType::Function,
+                    actual: None,
+                    location: None,
+                });
+            }
+
             let signature = merge_signatures([a, b], types)?;
-            InferredType::IndirectFunction { signature }
+            let environment =
+                merge_type_list([environment_a, environment_b], types)?;
+
+            InferredType::IndirectFunction {
+                signature,
+                environment,
+            }
         }
         (
-            InferredType::IndirectFunction { signature: a },
+            InferredType::IndirectFunction {
+                signature: a,
+                environment: environment_a,
+            },
             InferredType::Direct(b),
         )
         | (
             InferredType::Direct(b),
-            InferredType::IndirectFunction { signature: a },
+            InferredType::IndirectFunction {
+                signature: a,
+                environment: environment_a,
+            },
         ) => {
-            let Type::Function { signature: b } = b else {
+            let Type::Function {
+                signature: signature_b,
+                environment: environment_b,
+            } = b.clone()
+            else {
                 return Err(TypeError {
                     expected: ExpectedType::Function,
-                    actual: Some(b),
+                    actual: Some(ActualType::Specific(b)),
                     location: None,
                 });
             };
 
-            let b = IndirectSignature::from_direct(b, types);
+            // The environment is part of the type, as it determines how many
+            // values the function occupies. A function that captures different
+            // values is a different type.
+            if environment_a.len() != environment_b.len() {
+                return Err(TypeError {
+                    expected: ExpectedType::Specific(b),
+                    actual: None,
+                    location: None,
+                });
+            }
+
+            let signature_b =
+                IndirectSignature::from_direct(signature_b, types);
+            let environment_b = environment_b
+                .into_vec()
+                .into_iter()
+                .map(|type_| types.push(InferredType::Direct(type_)))
+                .collect();
+
+            let signature = merge_signatures([a, signature_b], types)?;
+            let environment =
+                merge_type_list([environment_a, environment_b], types)?;
+
+            InferredType::IndirectFunction {
+                signature,
+                environment,
+            }
+        }
+        (
+            InferredType::IndirectFunction { .. },
+            InferredType::IntegerLiteral { .. },
+        )
+        | (
+            InferredType::IntegerLiteral { .. },
+            InferredType::IndirectFunction { .. },
+        ) => {
+            return Err(TypeError {
+                expected: ExpectedType::Function,
+                actual: Some(ActualType::IntegerLiteral),
+                location: None,
+            });
+        }
+        (
+            InferredType::IntegerLiteral { min, max },
+            InferredType::Direct(type_),
+        )
+        | (
+            InferredType::Direct(type_),
+            InferredType::IntegerLiteral { min, max },
+        ) => {
+            if !(type_.can_represent(min) && type_.can_represent(max)) {
+                return Err(TypeError {
+                    expected: ExpectedType::Specific(type_),
+                    actual: Some(ActualType::IntegerLiteral),
+                    location: None,
+                });
+            }
 
-            let signature = merge_signatures([a, b], types)?;
-            InferredType::IndirectFunction { signature }
+            InferredType::Direct(type_)
         }
+        (
+            InferredType::IntegerLiteral {
+                min: min_a,
+                max: max_a,
+            },
+            InferredType::IntegerLiteral {
+                min: min_b,
+                max: max_b,
+            },
+        ) => InferredType::IntegerLiteral {
+            min: min_a.min(min_b),
+            max: max_a.max(max_b),
+        },
         (InferredType::Direct(a), InferredType::Direct(b)) => {
             merge_direct_types([a, b])?
         }
@@ -135,7 +239,8 @@ fn merge_type_list(
     let mut merged = Vec::new();
 
     for (a, b) in a.into_iter().zip(b) {
-        let [a, b] = [a, b].map(|index| types.get(&index).clone());
+        let a = types.resolve(&a)?;
+        let b = types.resolve(&b)?;
         let type_ = merge_inferred_types([a, b], types)?;
         let type_ = types.push(type_);
         merged.push(type_);
@@ -150,7 +255,7 @@ fn merge_direct_types([a, b]: [Type; 2]) -> Result<InferredType> {
     } else {
         Err(TypeError {
             expected: ExpectedType::Specific(a),
-            actual: Some(b),
+            actual: Some(ActualType::Specific(b)),
             location: None,
         })
     }
@@ -158,19 +263,38 @@ fn merge_direct_types([a, b]: [Type; 2]) -> Result<InferredType> {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum InferredType {
-    IndirectFunction { signature: IndirectSignature },
+    IndirectFunction {
+        signature: IndirectSignature,
+        environment: Vec<Index<InferredType>>,
+    },
     Direct(Type),
+
+    /// # The type of one or more integer literals
+    ///
+    /// The specific type is not known yet, but it must be able to represent
+    /// all values within the provided range.
+    IntegerLiteral {
+        min: i64,
+        max: i64,
+    },
     Unknown,
 }
 
 impl InferredType {
     pub fn into_type(self, types: &mut InferredTypes) -> Result<Option<Type>> {
         let type_ = match self {
-            Self::IndirectFunction { signature } => signature
+            Self::IndirectFunction {
+                signature,
+                environment,
+            } => signature
                 .to_direct(types)?
-                .map(|signature| Type::Function { signature }),
+                .zip(environment_to_direct(&environment, types)?)
+                .map(|(signature, environment)| Type::Function {
+                    signature,
+                    environment,
+                }),
             Self::Direct(type_) => Some(type_),
-            Self::Unknown { .. } => None,
+            Self::IntegerLiteral { .. } | Self::Unknown { .. } => None,
         };
 
         Ok(type_)
@@ -181,13 +305,21 @@ impl InferredType {
         types: &mut InferredTypes,
     ) -> Result<ExpectedType> {
         let expected_type = match self {
-            InferredType::IndirectFunction { signature } => signature
+            InferredType::IndirectFunction {
+                signature,
+                environment,
+            } => signature
                 .to_direct(types)?
-                .map(|signature| {
-                    ExpectedType::Specific(Type::Function { signature })
+                .zip(environment_to_direct(&environment, types)?)
+                .map(|(signature, environment)| {
+                    ExpectedType::Specific(Type::Function {
+                        signature,
+                        environment,
+                    })
                 })
                 .unwrap_or(ExpectedType::Function),
             InferredType::Direct(type_) => ExpectedType::Specific(type_),
+            InferredType::IntegerLiteral { .. } => ExpectedType::Integer,
             InferredType::Unknown => ExpectedType::Unknown,
         };
 
@@ -195,27 +327,65 @@ impl InferredType {
     }
 }
 
+fn environment_to_direct(
+    environment: &[Index<InferredType>],
+    types: &mut InferredTypes,
+) -> Result<Option<Box<[Type]>>> {
+    environment
+        .iter()
+        .map(|index| {
+            let type_ = types.resolve(index)?;
+            type_.into_type(types)
+        })
+        .collect()
+}
+
 pub type Result<T> = result::Result<T, TypeError>;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct TypeError {
     pub expected: ExpectedType,
-    pub actual: Option<Type>,
+    pub actual: Option<ActualType>,
     pub location: Option<MemberLocation>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum ExpectedType {
+    Field { name: String },
     Function,
+    Integer,
+    SingleValue,
     Specific(Type),
+    Struct,
     Unknown,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub enum ActualType {
+    IntegerLiteral,
+    Specific(Type),
+}
+
+impl fmt::Display for ActualType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IntegerLiteral => write!(f, "integer literal"),
+            Self::Specific(type_) => write!(f, "`{type_}`"),
+        }
+    }
+}
+
 impl fmt::Display for ExpectedType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Self::Field { name } => write!(f, "struct with field `{name}`"),
             Self::Function => write!(f, "function"),
+            Self::Integer => write!(f, "integer"),
+            Self::SingleValue => {
+                write!(f, "type that is made up of a single value")
+            }
             Self::Specific(type_) => write!(f, "`{type_}`"),
+            Self::Struct => write!(f, "struct"),
             Self::Unknown => write!(f, "unknown type"),
         }
     }
@@ -224,7 +394,9 @@ impl fmt::Display for ExpectedType {
 #[cfg(test)]
 mod tests {
     use crate::code::{
-        types::infer::types::{ExpectedType, InferredType, TypeError},
+        types::infer::types::{
+            ActualType, ExpectedType, InferredType, TypeError,
+        },
         Signature, Type,
     };
 
@@ -234,7 +406,7 @@ mod tests {
     fn resolve_known() {
         let mut types = InferredTypes::default();
 
-        let type_ = InferredType::Direct(Type::Number);
+        let type_ = InferredType::Direct(Type::S32);
         let index = types.push(type_.clone());
 
         assert_eq!(types.resolve(&index), Ok(type_));
@@ -254,7 +426,7 @@ mod tests {
     fn resolve_unified() {
         let mut types = InferredTypes::default();
 
-        let type_ = InferredType::Direct(Type::Number);
+        let type_ = InferredType::Direct(Type::S32);
 
         let a = types.push(type_.clone());
         let b = types.push(InferredType::Unknown);
@@ -269,7 +441,7 @@ mod tests {
     fn resolve_unified_with_type_known_only_indirectly() {
         let mut types = InferredTypes::default();
 
-        let type_ = InferredType::Direct(Type::Number);
+        let type_ = InferredType::Direct(Type::S32);
 
         let a = types.push(type_.clone());
         let b = types.push(InferredType::Unknown);
@@ -285,12 +457,13 @@ mod tests {
     fn resolve_conflicting_unified() {
         let mut types = InferredTypes::default();
 
-        let a = Type::Number;
+        let a = Type::S32;
         let b = Type::Function {
             signature: Signature {
                 inputs: vec![],
-                outputs: vec![Type::Number],
+                outputs: vec![Type::S32],
             },
+            environment: Box::new([]),
         };
 
         let index_a = types.push(InferredType::Direct(a.clone()));
@@ -302,7 +475,7 @@ mod tests {
             types.resolve(&index_a),
             Err(TypeError {
                 expected: ExpectedType::Specific(a.clone()),
-                actual: Some(b.clone()),
+                actual: Some(ActualType::Specific(b.clone())),
                 location: None,
             })
         );
@@ -310,7 +483,7 @@ mod tests {
             types.resolve(&index_b),
             Err(TypeError {
                 expected: ExpectedType::Specific(b),
-                actual: Some(a),
+                actual: Some(ActualType::Specific(a)),
                 location: None,
             })
         );
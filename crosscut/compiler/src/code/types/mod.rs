@@ -13,6 +13,7 @@ mod tests {
             syntax::{Expression, SyntaxTree},
             Bindings, Dependencies, FunctionCalls, Identifiers, Tokens, Type,
         },
+        diagnostics::Diagnostics,
         host::NoHost,
     };
 
@@ -26,9 +27,9 @@ mod tests {
         let (syntax_tree, types) = infer_types(
             r"
                 f: fn
-                    br value: Number ->
-                        value: -> Number .
-                        not: Number -> Number .
+                    br value: Bool ->
+                        value: -> Bool .
+                        not: Bool -> Bool .
                     end
                 end
             ",
@@ -56,12 +57,13 @@ mod tests {
 
         let branch_with_known_type = r"
             br 0 ->
-                0: -> Number .
+                0: -> S32 .
+                neg_s32: S32 -> S32 .
             end
         ";
         let branch_with_unknown_type = r"
-            br x: Number ->
-                x: -> Number .
+            br x: S32 ->
+                x: -> S32 .
             end
         ";
 
@@ -103,18 +105,18 @@ mod tests {
         let (syntax_tree, types) = infer_types(
             r"
                 f: fn
-                    br value: Number ->
+                    br value: Bool ->
                         # We should know the type of `value` from its use within
                         # the local function.
-                        value: -> Number .
+                        value: -> Bool .
 
                         fn
                             br ->
-                                value: -> Number .
+                                value: -> Bool .
                                 # Type of `value` can be inferred from this.
-                                not: Number -> Number .
+                                not: Bool -> Bool .
                             end
-                        end: -> fn -> Number end .
+                        end: -> fn -> Bool with Bool end .
                     end
                 end
             ",
@@ -136,14 +138,17 @@ mod tests {
     }
 
     #[test]
-    fn infer_type_of_literal() {
-        // The type of a literal can be trivially inferred.
+    fn infer_type_of_literal_from_use() {
+        // An integer literal could have any integer type. Which one it has, can
+        // be inferred from its use.
 
         let (syntax_tree, types) = infer_types(
             r"
                 f: fn
                     br ->
-                        1: -> Number .
+                        1: -> U8 .
+                        1: -> U8 .
+                        add_u8: U8, U8 -> U8 .
                     end
                 end
             ",
@@ -173,15 +178,15 @@ mod tests {
             r"
                 f: fn
                     br ->
-                        0: -> Number .
-                        g: Number -> Number .
+                        0: -> Bool .
+                        g: Bool -> Bool .
                     end
                 end
 
                 g: fn
-                    br x: Number ->
-                        x: -> Number .
-                        not: Number -> Number .
+                    br x: Bool ->
+                        x: -> Bool .
+                        not: Bool -> Bool .
                     end
                 end
             ",
@@ -198,7 +203,7 @@ mod tests {
             .nth(1)
             .unwrap();
 
-        assert_eq!(types.stack_at(&g).unwrap(), &[Type::Number]);
+        assert_eq!(types.stack_at(&g).unwrap(), &[Type::Bool]);
     }
 
     #[test]
@@ -211,19 +216,21 @@ mod tests {
             r"
                 f: fn
                     br ->
-                        0: -> Number .
-                        0: -> Number .
-                        g: Number, Number -> Number .
+                        0: -> S32 .
+                        0: -> S32 .
+                        g: S32, S32 -> S32 .
                     end
                 end
 
                 g: fn
-                    br 0, x: Number ->
-                        x: -> Number .
+                    br 0, x: S32 ->
+                        x: -> S32 .
+                        neg_s32: S32 -> S32 .
                     end
 
-                    br x: Number, 0 ->
-                        x: -> Number .
+                    br x: S32, 0 ->
+                        x: -> S32 .
+                        neg_s32: S32 -> S32 .
                     end
                 end
             ",
@@ -240,7 +247,7 @@ mod tests {
             .nth(2)
             .unwrap();
 
-        assert_eq!(types.stack_at(&g).unwrap(), &[Type::Number, Type::Number]);
+        assert_eq!(types.stack_at(&g).unwrap(), &[Type::S32, Type::S32]);
     }
 
     #[test]
@@ -251,13 +258,14 @@ mod tests {
 
         let branch_recursive = r"
             br 0 ->
-                1: -> Number .
-                g: Number -> Number .
+                1: -> S32 .
+                g: S32 -> S32 .
             end
         ";
         let branch_non_recursive = r"
-            br x: Number ->
-                x: -> Number .
+            br x: S32 ->
+                x: -> S32 .
+                neg_s32: S32 -> S32 .
             end
         ";
 
@@ -269,8 +277,8 @@ mod tests {
                 r"
                     f: fn
                         br ->
-                            0: -> Number .
-                            g: Number -> Number .
+                            0: -> S32 .
+                            g: S32 -> S32 .
                         end
                     end
 
@@ -292,7 +300,7 @@ mod tests {
                 .nth(1)
                 .unwrap();
 
-            assert_eq!(types.stack_at(&g).unwrap(), &[Type::Number]);
+            assert_eq!(types.stack_at(&g).unwrap(), &[Type::S32]);
         }
     }
 
@@ -306,33 +314,34 @@ mod tests {
             r"
                 f: fn
                     br ->
-                        0: -> Number .
-                        g: Number -> Number .
-                        0: -> Number .
-                        h: Number -> Number .
+                        0: -> S32 .
+                        g: S32 -> S32 .
+                        0: -> S32 .
+                        h: S32 -> S32 .
                     end
                 end
 
                 g: fn
                     br 0 ->
-                        0: -> Number .
-                        h: Number -> Number .
+                        0: -> S32 .
+                        h: S32 -> S32 .
                     end
                     
-                    br _: Number ->
-                        1: -> Number .
-                        h: Number -> Number .
+                    br x: S32 ->
+                        x: -> S32 .
+                        h: S32 -> S32 .
                     end
                 end
 
                 h: fn
                     br 0 ->
-                        1: -> Number .
-                        g: Number -> Number .
+                        1: -> S32 .
+                        g: S32 -> S32 .
                     end
 
-                    br _: Number ->
-                        0: -> Number .
+                    br x: S32 ->
+                        x: -> S32 .
+                        neg_s32: S32 -> S32 .
                     end
                 end
             ",
@@ -370,9 +379,9 @@ mod tests {
             assert_eq!(types.stack_at(call).unwrap(), stack);
         };
 
-        check(&call_to_g_in_f, &[Type::Number]);
-        check(&call_to_h_in_f, &[Type::Number, Type::Number]);
-        check(&call_to_g_in_h, &[Type::Number]);
+        check(&call_to_g_in_f, &[Type::S32]);
+        check(&call_to_h_in_f, &[Type::S32, Type::S32]);
+        check(&call_to_g_in_h, &[Type::S32]);
     }
 
     #[test]
@@ -384,21 +393,23 @@ mod tests {
             r"
                 f: fn
                     br ->
-                        0: -> Number .
+                        0: -> S32 .
+                        neg_s32: S32 -> S32 .
                         fn
                             br 0 ->
-                                0: -> Number .
+                                0: -> S32 .
+                                neg_s32: S32 -> S32 .
                             end
 
                             # Add this branch to make sure that `f` and the
                             # local function are in the same cluster. This more
                             # complicated to handle, resulting in this test
                             # covering a bit more ground.
-                            br _: Number ->
-                                f: -> Number .
+                            br _: S32 ->
+                                f: -> S32 .
                             end
-                        end: -> fn Number -> Number end .
-                        eval: Number, fn Number -> Number end -> Number .
+                        end: -> fn S32 -> S32 end .
+                        eval: S32, fn S32 -> S32 end -> S32 .
                     end
                 end
             ",
@@ -412,20 +423,23 @@ mod tests {
             .unwrap()
             .expressions()
             .map(|expression| expression.location)
-            .nth(1)
+            .nth(2)
             .unwrap();
 
-        assert_eq!(types.stack_at(&f_local).unwrap(), &[Type::Number]);
+        assert_eq!(types.stack_at(&f_local).unwrap(), &[Type::S32]);
     }
 
     fn infer_types(input: &str) -> (SyntaxTree, Types) {
         let tokens = Tokens::tokenize(input);
         let syntax_tree = SyntaxTree::parse(tokens);
 
+        let mut diagnostics = Diagnostics::default();
+
         // Expect anything that _can_ be annotated to actually _be_ annotated.
         // These annotations will later be used to check the results of the
         // inference.
-        let type_annotations = TypeAnnotations::resolve(&syntax_tree);
+        let type_annotations =
+            TypeAnnotations::resolve(&syntax_tree, &mut diagnostics);
         for function in syntax_tree.all_functions() {
             for branch in function.branches() {
                 for binding in branch.bindings() {
@@ -451,10 +465,15 @@ mod tests {
             }
         }
 
-        let bindings = Bindings::resolve(&syntax_tree);
-        let function_calls = FunctionCalls::resolve(&syntax_tree, &NoHost);
-        let identifiers =
-            Identifiers::resolve(&syntax_tree, &bindings, &function_calls);
+        let bindings = Bindings::resolve(&syntax_tree, &mut diagnostics);
+        let function_calls =
+            FunctionCalls::resolve(&syntax_tree, &NoHost, &mut diagnostics);
+        let identifiers = Identifiers::resolve(
+            &syntax_tree,
+            &bindings,
+            &function_calls,
+            &mut diagnostics,
+        );
         let dependencies = Dependencies::resolve(&syntax_tree, &function_calls);
 
         // Don't use any of the type annotations for the inference. They'll be
@@ -465,6 +484,12 @@ mod tests {
             &identifiers,
             &dependencies,
             TypeAnnotations::none(),
+            &mut diagnostics,
+        );
+
+        assert!(
+            !diagnostics.has_errors(),
+            "Unexpected errors: {diagnostics:#?}"
         );
 
         for (location, type_) in type_annotations.of_all_bindings() {
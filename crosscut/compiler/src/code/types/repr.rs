@@ -1,13 +1,19 @@
 use std::{collections::BTreeMap, fmt};
 
-use crate::code::{
-    syntax::{FunctionLocation, MemberLocation, ParameterLocation, SyntaxTree},
-    Bindings, Dependencies, Identifiers,
+use crate::{
+    code::{
+        syntax::{
+            Enum, FunctionLocation, MemberLocation, ParameterLocation, Struct,
+            SyntaxTree,
+        },
+        Bindings, Dependencies, Identifiers, Index,
+    },
+    diagnostics::Diagnostics,
 };
 
 use super::{
     infer::{infer, CompilerContext, InferenceOutput},
-    resolve::resolve_type_annotations,
+    resolve::{resolve_type_annotations, TypeAnnotationsResolved},
 };
 
 /// # The types that are explicitly specified in the code
@@ -22,6 +28,8 @@ use super::{
 pub struct TypeAnnotations {
     bindings: BTreeMap<ParameterLocation, Type>,
     expressions: BTreeMap<MemberLocation, Signature>,
+    structs: BTreeMap<Index<Struct>, Type>,
+    enums: BTreeMap<Index<Enum>, Type>,
 }
 
 impl TypeAnnotations {
@@ -30,16 +38,31 @@ impl TypeAnnotations {
         Self {
             bindings: BTreeMap::default(),
             expressions: BTreeMap::default(),
+            structs: BTreeMap::default(),
+            enums: BTreeMap::default(),
         }
     }
 
     /// # Resolve all explicit type annotations
-    pub fn resolve(syntax_tree: &SyntaxTree) -> Self {
-        let (bindings, expressions) = resolve_type_annotations(syntax_tree);
+    ///
+    /// Reports annotations that can't be resolved. Those resolve to
+    /// [`Type::Error`].
+    pub fn resolve(
+        syntax_tree: &SyntaxTree,
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
+        let TypeAnnotationsResolved {
+            bindings,
+            expressions,
+            structs,
+            enums,
+        } = resolve_type_annotations(syntax_tree, diagnostics);
 
         Self {
             bindings,
             expressions,
+            structs,
+            enums,
         }
     }
 
@@ -56,6 +79,22 @@ impl TypeAnnotations {
         self.expressions.get(location)
     }
 
+    /// # Access the type that the struct at the given index defines
+    ///
+    /// Structs are not type annotations, strictly speaking. But their fields
+    /// are, and resolving them is the same process.
+    pub fn of_struct(&self, index: &Index<Struct>) -> Option<&Type> {
+        self.structs.get(index)
+    }
+
+    /// # Access the type that the enum at the given index defines
+    ///
+    /// Like structs, enums are not type annotations. See
+    /// [`TypeAnnotations::of_struct`].
+    pub fn of_enum(&self, index: &Index<Enum>) -> Option<&Type> {
+        self.enums.get(index)
+    }
+
     /// # Iterate over the type annotations of all bindings
     pub fn of_all_bindings(
         &self,
@@ -94,19 +133,23 @@ impl Types {
         identifiers: &Identifiers,
         dependencies: &Dependencies,
         annotations: TypeAnnotations,
+        diagnostics: &mut Diagnostics,
     ) -> Self {
         let InferenceOutput {
             functions,
             expressions,
             parameters,
             stacks,
-        } = infer(CompilerContext {
-            syntax_tree,
-            bindings,
-            identifiers,
-            dependencies,
-            annotations: &annotations,
-        });
+        } = infer(
+            CompilerContext {
+                syntax_tree,
+                bindings,
+                identifiers,
+                dependencies,
+                annotations: &annotations,
+            },
+            diagnostics,
+        );
 
         Self {
             functions,
@@ -238,31 +281,195 @@ impl fmt::Display for Signature {
 )]
 pub enum Type {
     /// # A function
+    ///
+    /// A value of a function is made up of the values that the function
+    /// captured from its environment, followed by the address of the function.
     Function {
         /// # The function's signature
         signature: Signature,
+
+        /// # The types of the values that the function captured
+        ///
+        /// These are part of the type, as they determine how many values a
+        /// value of the function occupies. Named functions, and local functions
+        /// that don't capture anything, have an empty environment.
+        environment: Box<[Type]>,
     },
 
-    /// # A number
+    /// # A boolean
     ///
     /// ## Implementation Note
     ///
-    /// Since the language is still mostly untyped, this actually covers many
-    /// different types that are all represented as a 32-bit number.
+    /// There are no boolean literals yet. Integer literals that are `0` or `1`
+    /// can be used as booleans instead.
+    Bool,
+
+    /// # A signed 8-bit integer
+    S8,
+
+    /// # A signed 32-bit integer
+    S32,
+
+    /// # An unsigned 8-bit integer
+    U8,
+
+    /// # An unsigned 32-bit integer
+    U32,
+
+    /// # A struct
+    ///
+    /// A value of a struct is made up of the values of its fields, which are
+    /// laid out one after the other.
+    Struct {
+        /// # The name of the struct
+        name: String,
+
+        /// # The names and types of the struct's fields, in order
+        fields: Box<[(String, Type)]>,
+    },
+
+    /// # An enum
+    ///
+    /// A value of an enum is made up of the payload of its variant, followed
+    /// by the tag that identifies the variant. Smaller payloads are padded, so
+    /// all values of the enum take up the same number of values.
+    Enum {
+        /// # The name of the enum
+        name: String,
+
+        /// # The names and payload types of the enum's variants, in order
+        ///
+        /// The tag of a variant is its position in this list.
+        variants: Box<[(String, Option<Type>)]>,
+    },
+
+    /// # A type that couldn't be resolved
+    ///
+    /// The compiler has already reported an error where this type comes from.
+    /// It is compatible with any other type, to prevent follow-up errors.
+    Error,
+}
+
+impl Type {
+    /// # Determine, if the type can represent the provided integer
+    ///
+    /// Returns `false`, if this is not an integer type.
+    pub fn can_represent(&self, value: i64) -> bool {
+        match self {
+            Self::Function { .. } | Self::Struct { .. } | Self::Enum { .. } => {
+                false
+            }
+            Self::Bool => matches!(value, 0 | 1),
+            Self::S8 => i8::try_from(value).is_ok(),
+            Self::S32 => i32::try_from(value).is_ok(),
+            Self::U8 => u8::try_from(value).is_ok(),
+            Self::U32 => u32::try_from(value).is_ok(),
+            Self::Error => true,
+        }
+    }
+
+    /// # The number of runtime values that a value of this type occupies
+    pub fn num_values(&self) -> usize {
+        match self {
+            Self::Function { environment, .. } => {
+                environment
+                    .iter()
+                    .map(|type_| type_.num_values())
+                    .sum::<usize>()
+                    + 1
+            }
+            Self::Struct { fields, .. } => {
+                fields.iter().map(|(_, type_)| type_.num_values()).sum()
+            }
+            Self::Enum { variants, .. } => {
+                let payload = variants
+                    .iter()
+                    .filter_map(|(_, payload)| payload.as_ref())
+                    .map(|payload| payload.num_values())
+                    .max()
+                    .unwrap_or(0);
+
+                payload + 1
+            }
+            _ => 1,
+        }
+    }
+
+    /// # Access the type of a field, if this is a struct
+    ///
+    /// Returns `None`, if this is not a struct, or if it has no field with the
+    /// provided name.
+    pub fn field(&self, name: &str) -> Option<&Type> {
+        let Self::Struct { fields, .. } = self else {
+            return None;
+        };
+
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, type_)| type_)
+    }
+
+    /// # Access the tag and payload type of a variant, if this is an enum
     ///
-    /// I expect that this will get split into multiple, more specific numeric
-    /// types at some point.
-    Number,
+    /// Returns `None`, if this is not an enum, or if it has no variant with the
+    /// provided name.
+    pub fn variant(&self, name: &str) -> Option<(u32, Option<&Type>)> {
+        let Self::Enum { variants, .. } = self else {
+            return None;
+        };
+
+        variants
+            .iter()
+            .zip(0..)
+            .find(|((variant, _), _)| variant == name)
+            .map(|((_, payload), tag)| (tag, payload.as_ref()))
+    }
 }
 
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Function { signature } => {
-                write!(f, "fn {signature} end")?;
+            Self::Function {
+                signature,
+                environment,
+            } => {
+                write!(f, "fn {signature}")?;
+
+                let mut environment = environment.iter().peekable();
+                if environment.peek().is_some() {
+                    write!(f, " with ")?;
+                }
+                while let Some(type_) = environment.next() {
+                    write!(f, "{type_}")?;
+
+                    if environment.peek().is_some() {
+                        write!(f, ", ")?;
+                    }
+                }
+
+                write!(f, " end")?;
+            }
+            Self::Bool => {
+                write!(f, "Bool")?;
+            }
+            Self::S8 => {
+                write!(f, "S8")?;
+            }
+            Self::S32 => {
+                write!(f, "S32")?;
+            }
+            Self::U8 => {
+                write!(f, "U8")?;
+            }
+            Self::U32 => {
+                write!(f, "U32")?;
+            }
+            Self::Struct { name, .. } | Self::Enum { name, .. } => {
+                write!(f, "{name}")?;
             }
-            Self::Number => {
-                write!(f, "Number")?;
+            Self::Error => {
+                write!(f, "{{error}}")?;
             }
         }
 
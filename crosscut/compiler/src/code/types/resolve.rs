@@ -1,17 +1,56 @@
 use std::collections::BTreeMap;
 
-use crate::code::syntax::{
-    MemberLocation, ParameterLocation, SyntaxTree, SyntaxType,
+use crate::{
+    code::{
+        syntax::{
+            Enum, Located, MemberLocation, ParameterLocation, Struct,
+            SyntaxTree, SyntaxType,
+        },
+        Index, Span,
+    },
+    diagnostics::{Diagnostic, Diagnostics},
 };
 
 use super::{Signature, Type};
 
+pub struct TypeAnnotationsResolved {
+    pub bindings: BTreeMap<ParameterLocation, Type>,
+    pub expressions: BTreeMap<MemberLocation, Signature>,
+    pub structs: BTreeMap<Index<Struct>, Type>,
+    pub enums: BTreeMap<Index<Enum>, Type>,
+}
+
 pub fn resolve_type_annotations(
     syntax_tree: &SyntaxTree,
-) -> (
-    BTreeMap<ParameterLocation, Type>,
-    BTreeMap<MemberLocation, Signature>,
-) {
+    diagnostics: &mut Diagnostics,
+) -> TypeAnnotationsResolved {
+    let mut context = Context {
+        syntax_tree,
+        diagnostics,
+        resolved: BTreeMap::new(),
+        parents: Vec::new(),
+    };
+
+    // Resolve structs and enums first. Annotations that refer to them can then
+    // use the result, instead of resolving them again, and reporting any errors
+    // in their definitions a second time.
+    let structs = syntax_tree
+        .structs()
+        .map(|struct_| {
+            let location = struct_.location;
+            let type_ = resolve_struct(struct_, &mut context);
+            (location, type_)
+        })
+        .collect();
+    let enums = syntax_tree
+        .enums()
+        .map(|enum_| {
+            let location = enum_.location;
+            let type_ = resolve_enum(enum_, &mut context);
+            (location, type_)
+        })
+        .collect();
+
     let mut bindings = BTreeMap::new();
     let mut expressions = BTreeMap::new();
 
@@ -22,7 +61,9 @@ pub fn resolve_type_annotations(
                     continue;
                 };
 
-                let type_ = resolve_type(type_);
+                let span =
+                    syntax_tree.spans.of_parameter(&binding.location).copied();
+                let type_ = resolve_type(type_, span, &mut context);
 
                 bindings.insert(binding.location, type_);
             }
@@ -32,37 +73,189 @@ pub fn resolve_type_annotations(
                     continue;
                 };
 
-                let signature = resolve_signature(signature);
+                let span =
+                    syntax_tree.spans.of_member(&expression.location).copied();
+                let signature =
+                    resolve_signature(signature, span, &mut context);
 
                 expressions.insert(expression.location, signature);
             }
         }
     }
 
-    (bindings, expressions)
+    TypeAnnotationsResolved {
+        bindings,
+        expressions,
+        structs,
+        enums,
+    }
+}
+
+struct Context<'r> {
+    syntax_tree: &'r SyntaxTree,
+    diagnostics: &'r mut Diagnostics,
+
+    /// # The structs and enums that have been resolved so far, by name
+    resolved: BTreeMap<String, Type>,
+
+    /// # The names of the structs and enums that are currently being resolved
+    parents: Vec<String>,
 }
 
-fn resolve_signature(signature: &Signature<SyntaxType>) -> Signature {
+/// # Resolve the type that a struct defines
+///
+/// Tracks the names of the structs that are currently being resolved, to
+/// detect structs that contain themselves. See [`resolve_type`].
+fn resolve_struct(struct_: Located<&Struct>, context: &mut Context) -> Type {
+    if let Some(type_) = context.resolved.get(&struct_.name) {
+        return type_.clone();
+    }
+
+    context.parents.push(struct_.name.clone());
+
+    let spans = &context.syntax_tree.spans;
+    let fields = struct_
+        .fields
+        .iter()
+        .map(|(index, field)| {
+            let span = spans.of_field(&struct_.location, index).copied();
+            let type_ = resolve_type(&field.type_, span, context);
+            (field.name.clone(), type_)
+        })
+        .collect();
+
+    context.parents.pop();
+
+    let type_ = Type::Struct {
+        name: struct_.name.clone(),
+        fields,
+    };
+    context.resolved.insert(struct_.name.clone(), type_.clone());
+
+    type_
+}
+
+/// # Resolve the type that an enum defines
+///
+/// Tracks the names of the enums that are currently being resolved, the same
+/// way [`resolve_struct`] does.
+fn resolve_enum(enum_: Located<&Enum>, context: &mut Context) -> Type {
+    if let Some(type_) = context.resolved.get(&enum_.name) {
+        return type_.clone();
+    }
+
+    context.parents.push(enum_.name.clone());
+
+    let spans = &context.syntax_tree.spans;
+    let variants = enum_
+        .variants
+        .iter()
+        .map(|(index, variant)| {
+            let span = spans.of_variant(&enum_.location, index).copied();
+            let payload = variant
+                .payload
+                .as_ref()
+                .map(|type_| resolve_type(type_, span, context));
+            (variant.name.clone(), payload)
+        })
+        .collect();
+
+    context.parents.pop();
+
+    let type_ = Type::Enum {
+        name: enum_.name.clone(),
+        variants,
+    };
+    context.resolved.insert(enum_.name.clone(), type_.clone());
+
+    type_
+}
+
+fn resolve_signature(
+    signature: &Signature<SyntaxType>,
+    span: Option<Span>,
+    context: &mut Context,
+) -> Signature {
     Signature {
-        inputs: resolve_types(&signature.inputs),
-        outputs: resolve_types(&signature.outputs),
+        inputs: resolve_types(&signature.inputs, span, context),
+        outputs: resolve_types(&signature.outputs, span, context),
     }
 }
 
-fn resolve_types(types: &[SyntaxType]) -> Vec<Type> {
-    types.iter().map(resolve_type).collect()
+fn resolve_types(
+    types: &[SyntaxType],
+    span: Option<Span>,
+    context: &mut Context,
+) -> Vec<Type> {
+    types
+        .iter()
+        .map(|type_| resolve_type(type_, span, context))
+        .collect()
 }
 
-fn resolve_type(type_: &SyntaxType) -> Type {
+/// # Resolve a type
+///
+/// The span is that of the syntax element the type is part of. Any error
+/// while resolving the type is reported there.
+///
+/// A struct or enum that contains itself, directly or through other structs
+/// and enums, would take up an infinite amount of space. If the type refers to
+/// a struct or enum that is currently being resolved, that is reported as an
+/// error, pointing at the field or variant that contains it.
+fn resolve_type(
+    type_: &SyntaxType,
+    span: Option<Span>,
+    context: &mut Context,
+) -> Type {
     match type_ {
-        SyntaxType::Function { signature } => {
-            let signature = resolve_signature(signature);
-            Type::Function { signature }
+        SyntaxType::Function {
+            signature,
+            environment,
+        } => {
+            let signature = resolve_signature(signature, span, context);
+            let environment = resolve_types(environment, span, context).into();
+
+            Type::Function {
+                signature,
+                environment,
+            }
         }
         SyntaxType::Identifier { name } => match name.as_str() {
-            "Number" => Type::Number,
-            type_ => {
-                panic!("Unknown type `{type_}`");
+            "Bool" => Type::Bool,
+            "S8" => Type::S8,
+            "S32" => Type::S32,
+            "U8" => Type::U8,
+            "U32" => Type::U32,
+            name => {
+                let syntax_tree = context.syntax_tree;
+
+                if let Some(struct_) = syntax_tree.struct_by_name(name) {
+                    if context.parents.iter().any(|parent| parent == name) {
+                        context.diagnostics.push(Diagnostic::error(
+                            format!("Struct `{name}` contains itself"),
+                            span,
+                        ));
+                        return Type::Error;
+                    }
+
+                    resolve_struct(struct_, context)
+                } else if let Some(enum_) = syntax_tree.enum_by_name(name) {
+                    if context.parents.iter().any(|parent| parent == name) {
+                        context.diagnostics.push(Diagnostic::error(
+                            format!("Enum `{name}` contains itself"),
+                            span,
+                        ));
+                        return Type::Error;
+                    }
+
+                    resolve_enum(enum_, context)
+                } else {
+                    context.diagnostics.push(Diagnostic::error(
+                        format!("Unknown type `{name}`"),
+                        span,
+                    ));
+                    Type::Error
+                }
             }
         },
     }
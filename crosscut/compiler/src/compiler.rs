@@ -1,16 +1,21 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crosscut_runtime::InstructionAddress;
 
 use crate::{
     code::{
-        syntax::{FunctionLocation, SyntaxTree},
-        Bindings, Dependencies, FunctionCalls, Functions, Identifiers,
-        Recursion, TailExpressions, Tokens, TypeAnnotations, Types,
+        syntax::{FunctionLocation, NamedFunction, SyntaxTree},
+        Bindings, Dependencies, FunctionCalls, Functions, Identifiers, Index,
+        Recursion, TailExpressions, TypeAnnotations, Types,
     },
+    diagnostics::Diagnostics,
     host::Host,
-    passes::{detect_changes, generate_instructions},
+    passes::{
+        check_patterns, detect_changes, find_erroneous_functions,
+        generate_instructions, report_syntax_errors,
+    },
     source_map::SourceMap,
+    sources::{SourceFile, Sources},
     Instructions,
 };
 
@@ -21,31 +26,90 @@ pub struct Compiler {
     instructions: Instructions,
     call_instructions_by_callee: CallInstructionsByCallee,
     compiled_functions_by_location:
-        BTreeMap<FunctionLocation, crosscut_runtime::Function>,
+        BTreeMap<FunctionLocation, InstructionAddress>,
     source_map: SourceMap,
+
+    /// # The last version of each named function that built without errors
+    ///
+    /// Keyed by the names of the module and the function, as the location of a
+    /// function can change between compilations.
+    ///
+    /// If a function fails to build, calls to it keep going to this version,
+    /// instead of to code that would trigger an error. See
+    /// [`Compiler::compile_sources`].
+    good_functions_by_name: BTreeMap<(String, String), InstructionAddress>,
 }
 
 impl Compiler {
     /// # Compile the provided source code
+    ///
+    /// Expects the source code of a main module that doesn't import any modules
+    /// besides the standard library. Use [`Compiler::compile_sources`] to
+    /// compile multiple modules.
     pub fn compile(&mut self, input: &str, host: &impl Host) -> CompilerOutput {
-        let tokens = Tokens::tokenize(input);
-        let syntax_tree = SyntaxTree::parse(tokens);
-        let type_annotations = TypeAnnotations::resolve(&syntax_tree);
-        let bindings = Bindings::resolve(&syntax_tree);
-        let function_calls = FunctionCalls::resolve(&syntax_tree, host);
-        let identifiers =
-            Identifiers::resolve(&syntax_tree, &bindings, &function_calls);
+        let sources = Sources::new(SourceFile::main("main.capi", input));
+        self.compile_sources(&sources, host)
+    }
+
+    /// # Compile the provided source files
+    ///
+    /// Modules of the standard library that the host doesn't support are
+    /// compiled as empty modules.
+    ///
+    /// If a named function contains errors, and a previous version of it built
+    /// without errors, that previous version stays in place. The errors are
+    /// still reported as diagnostics. Only functions that never built without
+    /// errors are compiled into code that triggers an error when it's reached.
+    pub fn compile_sources(
+        &mut self,
+        sources: &Sources,
+        host: &impl Host,
+    ) -> CompilerOutput {
+        let mut diagnostics = Diagnostics::default();
+
+        let syntax_tree =
+            SyntaxTree::parse_sources(&sources.supported_by(host));
+        report_syntax_errors(&syntax_tree, &mut diagnostics);
+        let type_annotations =
+            TypeAnnotations::resolve(&syntax_tree, &mut diagnostics);
+        let bindings = Bindings::resolve(&syntax_tree, &mut diagnostics);
+        let function_calls =
+            FunctionCalls::resolve(&syntax_tree, host, &mut diagnostics);
+        let identifiers = Identifiers::resolve(
+            &syntax_tree,
+            &bindings,
+            &function_calls,
+            &mut diagnostics,
+        );
         let tail_expressions = TailExpressions::find(&syntax_tree);
         let dependencies = Dependencies::resolve(&syntax_tree, &function_calls);
-        let recursion =
-            Recursion::find(&syntax_tree, &function_calls, &dependencies);
+        let recursion = Recursion::find(
+            &syntax_tree,
+            &function_calls,
+            &dependencies,
+            &tail_expressions,
+            &mut diagnostics,
+        );
         let types = Types::infer(
             &syntax_tree,
             &bindings,
             &identifiers,
             &dependencies,
             type_annotations,
+            &mut diagnostics,
         );
+        check_patterns(&syntax_tree, &types, &mut diagnostics);
+        let erroneous_functions =
+            find_erroneous_functions(&syntax_tree, &diagnostics);
+        let fallbacks = syntax_tree
+            .named_functions()
+            .filter(|function| erroneous_functions.contains(&function.location))
+            .filter_map(|function| {
+                let name = qualified_name(&syntax_tree, &function.location);
+                let address = self.good_functions_by_name.get(&name)?;
+                Some((function.location(), *address))
+            })
+            .collect();
         let functions = Functions {
             inner: syntax_tree
                 .all_functions()
@@ -66,11 +130,13 @@ impl Compiler {
             &types,
             &recursion,
             &changes,
+            &fallbacks,
             &mut self.instructions,
             &mut self.call_instructions_by_callee,
             &mut self.compiled_functions_by_location,
             &mut self.source_map,
         );
+        self.remember_good_functions(&syntax_tree, &erroneous_functions);
 
         CompilerOutput {
             syntax_tree,
@@ -78,10 +144,52 @@ impl Compiler {
             function_calls,
             dependencies,
             types,
+            diagnostics,
             instructions: self.instructions.clone(),
             source_map: self.source_map.clone(),
         }
     }
+
+    fn remember_good_functions(
+        &mut self,
+        syntax_tree: &SyntaxTree,
+        erroneous_functions: &BTreeSet<Index<NamedFunction>>,
+    ) {
+        for function in syntax_tree.named_functions() {
+            if erroneous_functions.contains(&function.location) {
+                continue;
+            }
+
+            let Some(address) = self
+                .compiled_functions_by_location
+                .get(&function.location())
+            else {
+                continue;
+            };
+
+            self.good_functions_by_name.insert(
+                qualified_name(syntax_tree, &function.location),
+                *address,
+            );
+        }
+    }
+}
+
+fn qualified_name(
+    syntax_tree: &SyntaxTree,
+    function: &Index<NamedFunction>,
+) -> (String, String) {
+    let function = syntax_tree
+        .named_functions
+        .get(function)
+        .expect("Function must exist in the syntax tree it was taken from.");
+    let module = syntax_tree
+        .modules
+        .get(&function.module)
+        .map(|module| module.name.clone())
+        .unwrap_or_default();
+
+    (module, function.name.clone())
 }
 
 #[derive(Default)]
@@ -96,6 +204,40 @@ pub struct CompilerOutput {
     pub function_calls: FunctionCalls,
     pub dependencies: Dependencies,
     pub types: Types,
+    pub diagnostics: Diagnostics,
     pub instructions: Instructions,
     pub source_map: SourceMap,
 }
+
+impl CompilerOutput {
+    /// # Describe the chain of function calls that led to some instructions
+    ///
+    /// Expects the instructions that are currently active in the runtime, as
+    /// returned by `Evaluator::active_instructions`, outermost first. Returns a
+    /// description of the function that each of them belongs to, in the same
+    /// order.
+    ///
+    /// This is used to report where a process ran out of stack space. Since
+    /// most of the stack frames in that situation usually belong to the same
+    /// few functions, the host might want to abbreviate the result.
+    pub fn call_chain(
+        &self,
+        active_instructions: impl IntoIterator<Item = InstructionAddress>,
+    ) -> Vec<String> {
+        active_instructions
+            .into_iter()
+            .map(|instruction| {
+                match self.source_map.instruction_to_function(&instruction) {
+                    Some(function) => {
+                        function.display(&self.syntax_tree).to_string()
+                    }
+                    None => {
+                        // Not all instructions are generated from a function.
+                        // Those that call `main` or host functions aren't.
+                        format!("compiler-generated code at {instruction}")
+                    }
+                }
+            })
+            .collect()
+    }
+}
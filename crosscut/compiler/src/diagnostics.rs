@@ -0,0 +1,257 @@
+use std::fmt;
+
+use crate::{code::Span, sources::Sources};
+
+/// # The diagnostics that the compiler produced
+///
+/// All compiler passes that can detect problems with the code report them
+/// here, instead of failing the build. Code that has errors is still compiled,
+/// but the parts that are affected trigger a build error at runtime.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct Diagnostics {
+    inner: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// # Add a diagnostic
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.inner.push(diagnostic);
+    }
+
+    /// # Iterate over all diagnostics, in the order they were reported
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.inner.iter()
+    }
+
+    /// # Iterate over all diagnostics of severity [`Severity::Error`]
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// # Iterate over all diagnostics of severity [`Severity::Warning`]
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+
+    /// # Determine, if any errors have been reported
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// # Determine, if no diagnostics have been reported at all
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// # Display the diagnostics in a format suitable for a terminal
+    ///
+    /// Requires the source files that the diagnostics were produced from.
+    pub fn display<'r>(
+        &'r self,
+        sources: &'r Sources,
+    ) -> DiagnosticsDisplay<'r> {
+        DiagnosticsDisplay {
+            diagnostics: self,
+            sources,
+        }
+    }
+}
+
+/// # A problem with the code, as detected by the compiler
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Diagnostic {
+    /// # How severe the problem is
+    pub severity: Severity,
+
+    /// # The message that describes the problem
+    pub message: String,
+
+    /// # The location of the problem in the source code, if available
+    pub span: Option<Span>,
+
+    /// # Additional information that might help with addressing the problem
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// # Create a diagnostic of severity [`Severity::Error`]
+    pub fn error(message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            notes: Vec::new(),
+        }
+    }
+
+    /// # Create a diagnostic of severity [`Severity::Warning`]
+    pub fn warning(message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+            notes: Vec::new(),
+        }
+    }
+
+    /// # Add a note to the diagnostic
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+/// # The severity of a [`Diagnostic`]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum Severity {
+    /// # The code is valid, but probably doesn't do what was intended
+    Warning,
+
+    /// # The code is invalid
+    ///
+    /// Invalid code still gets compiled, but triggers a build error when
+    /// executed.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// # Helper struct to display [`Diagnostics`]
+///
+/// Returned by [`Diagnostics::display`].
+pub struct DiagnosticsDisplay<'r> {
+    diagnostics: &'r Diagnostics,
+    sources: &'r Sources,
+}
+
+impl DiagnosticsDisplay<'_> {
+    fn fmt_diagnostic(
+        &self,
+        diagnostic: &Diagnostic,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        writeln!(f, "{}: {}", diagnostic.severity, diagnostic.message)?;
+
+        let gutter = diagnostic
+            .span
+            .map(|span| span.start.line.to_string().len())
+            .unwrap_or(0);
+        let empty = "";
+
+        let span_and_file = diagnostic
+            .span
+            .and_then(|span| Some((span, self.sources.get(&span.module)?)));
+
+        if let Some((span, file)) = span_and_file {
+            let start = span.start;
+            writeln!(f, "{empty:gutter$}--> {}:{start}", file.path.display())?;
+
+            if let Some(line) = file.code.lines().nth(start.line - 1) {
+                let offset = start.column - 1;
+                let length = if span.end.line == start.line {
+                    span.end.column.saturating_sub(start.column)
+                } else {
+                    line.chars().count().saturating_sub(offset)
+                };
+                let underline = "^".repeat(length.max(1));
+
+                writeln!(f, "{empty:gutter$} |")?;
+                writeln!(f, "{} | {line}", start.line)?;
+                writeln!(f, "{empty:gutter$} | {empty:offset$}{underline}")?;
+            }
+        }
+
+        for note in &diagnostic.notes {
+            writeln!(f, "{empty:gutter$} = note: {note}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiagnosticsDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, diagnostic) in self.diagnostics.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            self.fmt_diagnostic(diagnostic, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        code::{syntax::Module, Position, Span},
+        sources::{SourceFile, Sources},
+    };
+
+    use super::{Diagnostic, Diagnostics};
+
+    #[test]
+    fn display_diagnostic_with_span_and_note() {
+        // A diagnostic should be displayed with the source code it refers to,
+        // with the relevant part underlined.
+
+        let source = "f: fn\n    br ->\n        abc\n    end\nend\n";
+
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.push(
+            Diagnostic::error(
+                "Unresolved identifier `abc`",
+                Some(Span {
+                    module: Module::main_index(),
+                    start: Position {
+                        byte: 23,
+                        line: 3,
+                        column: 9,
+                    },
+                    end: Position {
+                        byte: 26,
+                        line: 3,
+                        column: 12,
+                    },
+                }),
+            )
+            .with_note("This is a note."),
+        );
+
+        let sources = Sources::new(SourceFile::main("main.capi", source));
+        let output = diagnostics.display(&sources).to_string();
+
+        assert_eq!(
+            output,
+            "error: Unresolved identifier `abc`\n \
+            --> main.capi:3:9\n  \
+            |\n\
+            3 |         abc\n  \
+            |         ^^^\n  \
+            = note: This is a note.\n",
+        );
+    }
+}
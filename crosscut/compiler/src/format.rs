@@ -0,0 +1,564 @@
+//! # Canonical formatting of source code
+//!
+//! The formatter parses the source code, then writes the [`SyntaxTree`] back
+//! out as text, with canonical indentation and spacing. It uses the spans of
+//! the syntax tree to keep expressions that the developer put on the same line
+//! together, and to preserve blank lines that separate groups of members.
+
+use crate::{
+    code::{
+        syntax::{
+            Branch, Comment, Enum, Expression, Function, Located, Member,
+            NamedFunction, Parameter, Struct, SyntaxTree, SyntaxType,
+        },
+        Signature, Span, Token, Tokens,
+    },
+    diagnostics::Diagnostics,
+    passes::report_syntax_errors,
+};
+
+/// # Format the provided source code
+///
+/// Returns an error, if the code has syntax errors, or if formatting it would
+/// change more than just whitespace. The latter can happen, if the code has
+/// comments in places where the syntax tree doesn't keep track of them.
+pub fn format_source(source: &str) -> Result<String, FormatError> {
+    let syntax_tree = SyntaxTree::parse(Tokens::tokenize(source));
+
+    let mut diagnostics = Diagnostics::default();
+    report_syntax_errors(&syntax_tree, &mut diagnostics);
+    if diagnostics.has_errors() {
+        return Err(FormatError::SyntaxErrors(diagnostics));
+    }
+
+    let formatted = format_syntax_tree(&syntax_tree);
+
+    if all_tokens(source) != all_tokens(&formatted) {
+        return Err(FormatError::WouldChangeCode);
+    }
+
+    Ok(formatted)
+}
+
+/// # An error that prevented code from being formatted
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    /// # The code has syntax errors
+    #[error("Can't format code that has syntax errors")]
+    SyntaxErrors(Diagnostics),
+
+    /// # Formatting the code would change more than just whitespace
+    #[error(
+        "Formatting would change the code. Is there a comment that isn't \
+        placed before a function, a branch, or a member of a branch?"
+    )]
+    WouldChangeCode,
+}
+
+fn format_syntax_tree(syntax_tree: &SyntaxTree) -> String {
+    let mut writer = Writer {
+        output: String::new(),
+        syntax_tree,
+    };
+
+    let imports = syntax_tree
+        .modules
+        .values()
+        .flat_map(|module| module.imports.iter())
+        .collect::<Vec<_>>();
+    if !imports.is_empty() {
+        writer.output.push_str("import ");
+
+        for (i, import) in imports.into_iter().enumerate() {
+            if i > 0 {
+                writer.output.push_str(", ");
+            }
+            writer.output.push_str(import);
+        }
+
+        writer.output.push_str(" .\n");
+    }
+
+    // Structs, enums, and named functions are tracked separately, but we want
+    // to keep them in the order they appear in the source code.
+    let mut items = syntax_tree
+        .structs()
+        .map(|struct_| {
+            let span = syntax_tree.spans.of_struct(&struct_.location);
+            (span.copied(), Item::Struct(struct_.fragment))
+        })
+        .chain(syntax_tree.enums().map(|enum_| {
+            let span = syntax_tree.spans.of_enum(&enum_.location);
+            (span.copied(), Item::Enum(enum_.fragment))
+        }))
+        .chain(syntax_tree.named_functions().map(|function| {
+            let span = syntax_tree.spans.of_named_function(&function.location);
+            (span.copied(), Item::Function(function))
+        }))
+        .collect::<Vec<_>>();
+    items.sort_by_key(|(span, _)| span.map(|span| span.start.byte));
+
+    for (_, item) in items {
+        if !writer.output.is_empty() {
+            writer.output.push('\n');
+        }
+
+        let comment = match &item {
+            Item::Function(function) => &function.comment,
+            Item::Struct(struct_) => &struct_.comment,
+            Item::Enum(enum_) => &enum_.comment,
+        };
+        if let Some(comment) = comment {
+            writer.write_comment(comment, 0);
+            writer.output.push('\n');
+        }
+
+        match item {
+            Item::Function(function) => {
+                writer.output.push_str(&function.name);
+                writer.output.push_str(": ");
+                writer.write_function(function.into_located_function(), 0);
+            }
+            Item::Struct(struct_) => {
+                writer.write_struct(struct_);
+            }
+            Item::Enum(enum_) => {
+                writer.write_enum(enum_);
+            }
+        }
+        writer.output.push('\n');
+    }
+
+    writer.output
+}
+
+enum Item<'r> {
+    Function(Located<&'r NamedFunction>),
+    Struct(&'r Struct),
+    Enum(&'r Enum),
+}
+
+struct Writer<'r> {
+    output: String,
+    syntax_tree: &'r SyntaxTree,
+}
+
+impl Writer<'_> {
+    fn write_struct(&mut self, struct_: &Struct) {
+        self.output.push_str(&struct_.name);
+        self.output.push_str(": struct\n");
+
+        for field in struct_.fields.values() {
+            self.write_indentation(1);
+            self.output.push_str(&field.name);
+            self.output.push_str(": ");
+            self.write_type(&field.type_);
+            self.output.push('\n');
+        }
+
+        self.output.push_str("end");
+    }
+
+    fn write_enum(&mut self, enum_: &Enum) {
+        self.output.push_str(&enum_.name);
+        self.output.push_str(": enum\n");
+
+        for variant in enum_.variants.values() {
+            self.write_indentation(1);
+            self.output.push_str(&variant.name);
+
+            if let Some(payload) = &variant.payload {
+                self.output.push_str(": ");
+                self.write_type(payload);
+            }
+
+            self.output.push('\n');
+        }
+
+        self.output.push_str("end");
+    }
+
+    fn write_function(&mut self, function: Located<&Function>, level: usize) {
+        self.output.push_str("fn\n");
+
+        for (i, branch) in function.branches().enumerate() {
+            if i > 0 {
+                self.output.push('\n');
+            }
+
+            self.write_branch(branch, level + 1);
+        }
+
+        self.write_indentation(level);
+        self.output.push_str("end");
+    }
+
+    fn write_branch(&mut self, branch: Located<&Branch>, level: usize) {
+        if let Some(comment) = &branch.comment {
+            self.write_comment(comment, level);
+            self.output.push('\n');
+        }
+
+        self.write_indentation(level);
+        self.output.push_str("br");
+
+        for (i, parameter) in branch.parameters.values().enumerate() {
+            self.output.push_str(if i > 0 { ", " } else { " " });
+
+            match parameter {
+                Parameter::Binding { binding, type_ } => {
+                    self.output.push_str(&binding.name);
+
+                    if let Some(type_) = type_ {
+                        self.output.push_str(": ");
+                        self.write_type(type_);
+                    }
+                }
+                Parameter::Literal { value } => {
+                    self.output.push_str(&value.to_string());
+                }
+                Parameter::Variant {
+                    enum_,
+                    variant,
+                    binding,
+                } => {
+                    self.output.push_str(enum_);
+                    self.output.push('.');
+                    self.output.push_str(variant);
+
+                    if let Some(binding) = binding {
+                        self.output.push(' ');
+                        self.output.push_str(&binding.name);
+                    }
+                }
+                Parameter::Error(error) => {
+                    self.output.push_str(&error.to_string());
+                }
+            }
+        }
+
+        self.output.push_str(" ->\n");
+
+        // Tracks the span of the previous member, and whether another member
+        // could go on the same line.
+        let mut previous: Option<(Option<Span>, bool)> = None;
+
+        for member in branch.body() {
+            let span = self.syntax_tree.spans.of_member(&member.location);
+            let is_inline = matches!(
+                member.fragment,
+                Member::Expression {
+                    expression: Expression::Identifier { .. }
+                        | Expression::FunctionReference { .. }
+                        | Expression::LiteralNumber { .. },
+                    ..
+                }
+            );
+
+            match previous {
+                Some((previous_span, previous_is_inline)) => {
+                    let lines = previous_span
+                        .zip(span)
+                        .map(|(a, b)| b.start.line.saturating_sub(a.end.line));
+
+                    if previous_is_inline && is_inline && lines == Some(0) {
+                        self.output.push(' ');
+                    } else {
+                        self.output.push('\n');
+
+                        if lines.unwrap_or(0) > 1 {
+                            // The developer separated these members with blank
+                            // lines. Keep one of them.
+                            self.output.push('\n');
+                        }
+
+                        self.write_indentation(level + 1);
+                    }
+                }
+                None => {
+                    self.write_indentation(level + 1);
+                }
+            }
+
+            self.write_member(member, level + 1);
+
+            previous = Some((span.copied(), is_inline));
+        }
+
+        if previous.is_some() {
+            self.output.push('\n');
+        }
+
+        self.write_indentation(level);
+        self.output.push_str("end\n");
+    }
+
+    fn write_member(&mut self, member: Located<&Member>, level: usize) {
+        match member.fragment {
+            Member::Comment(comment) => {
+                self.write_comment_lines(comment, level);
+            }
+            Member::Expression {
+                expression,
+                signature,
+            } => {
+                match expression {
+                    Expression::Identifier { name } => {
+                        self.output.push_str(name);
+                    }
+                    Expression::FunctionReference { name } => {
+                        self.output.push('&');
+                        self.output.push_str(name);
+                    }
+                    Expression::LiteralNumber { value } => {
+                        self.output.push_str(&value.to_string());
+                    }
+                    Expression::LocalFunction { .. } => {
+                        let Some(function) = member.into_expression().and_then(
+                            |(expression, _)| expression.into_local_function(),
+                        ) else {
+                            unreachable!(
+                                "Just matched expression as local function."
+                            );
+                        };
+
+                        self.write_function(function, level);
+                    }
+                }
+
+                if let Some(signature) = signature {
+                    self.output.push_str(": ");
+                    self.write_signature(signature, ".");
+                }
+            }
+            Member::Error(error) => {
+                self.output.push_str(&error.to_string());
+            }
+        }
+    }
+
+    fn write_comment(&mut self, comment: &Comment, level: usize) {
+        self.write_indentation(level);
+        self.write_comment_lines(comment, level);
+    }
+
+    /// # Write the lines of a comment
+    ///
+    /// Expects the first line to be indented already.
+    fn write_comment_lines(&mut self, comment: &Comment, level: usize) {
+        for (i, line) in comment.lines.iter().enumerate() {
+            if i > 0 {
+                self.output.push('\n');
+                self.write_indentation(level);
+            }
+
+            self.output.push('#');
+            if !line.is_empty() {
+                self.output.push(' ');
+                self.output.push_str(line);
+            }
+        }
+    }
+
+    fn write_signature(
+        &mut self,
+        signature: &Signature<SyntaxType>,
+        terminator: &str,
+    ) {
+        for (i, input) in signature.inputs.iter().enumerate() {
+            if i > 0 {
+                self.output.push_str(", ");
+            }
+            self.write_type(input);
+            self.output.push(' ');
+        }
+
+        self.output.push_str("->");
+
+        for (i, output) in signature.outputs.iter().enumerate() {
+            self.output.push_str(if i > 0 { ", " } else { " " });
+            self.write_type(output);
+        }
+
+        self.output.push(' ');
+        self.output.push_str(terminator);
+    }
+
+    fn write_type(&mut self, type_: &SyntaxType) {
+        match type_ {
+            SyntaxType::Function {
+                signature,
+                environment,
+            } => {
+                self.output.push_str("fn ");
+
+                if environment.is_empty() {
+                    self.write_signature(signature, "end");
+                } else {
+                    self.write_signature(signature, "with");
+
+                    for (i, type_) in environment.iter().enumerate() {
+                        self.output.push_str(if i > 0 { ", " } else { " " });
+                        self.write_type(type_);
+                    }
+
+                    self.output.push_str(" end");
+                }
+            }
+            SyntaxType::Identifier { name } => {
+                self.output.push_str(name);
+            }
+        }
+    }
+
+    fn write_indentation(&mut self, level: usize) {
+        for _ in 0..level {
+            self.output.push_str("    ");
+        }
+    }
+}
+
+fn all_tokens(source: &str) -> Vec<Token> {
+    let mut tokens = Tokens::tokenize(source);
+    let mut all = Vec::new();
+
+    while let Ok(token) = tokens.take() {
+        all.push(token);
+    }
+
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_source, FormatError};
+
+    #[test]
+    fn keep_canonical_code_as_it_is() {
+        // Code that is already formatted canonically should not change.
+
+        let source = "\
+import a, b .
+
+# A function.
+f: fn
+    br 0, value: S32 ->
+        # A comment about the members.
+        value 1 add_s32
+        g
+
+        fn
+            br ->
+            end
+        end: -> fn -> end .
+        eval
+        &g eval
+    end
+
+    # Another branch.
+    br _, _ ->
+    end
+end
+
+g: fn
+    br ->
+        1: -> S32 .
+    end
+end
+
+# A struct.
+Vec2: struct
+    x: U8
+    y: U8
+end
+
+h: fn
+    br v: Vec2 ->
+        v.x v.y Vec2
+    end
+end
+
+# An enum.
+Shape: enum
+    Circle: U8
+    Square: Vec2
+    Empty
+end
+
+i: fn
+    br Shape.Circle radius ->
+        radius Shape.Circle
+    end
+
+    br Shape.Empty ->
+        Shape.Empty
+    end
+
+    br _ ->
+        Shape.Empty
+    end
+end
+
+j: fn
+    br value: S32 ->
+        fn
+            br ->
+                value
+            end
+        end: -> fn -> S32 with S32 end .
+        eval
+    end
+end
+";
+
+        assert_eq!(format_source(source).unwrap(), source);
+    }
+
+    #[test]
+    fn fix_indentation_and_spacing() {
+        // Indentation and spacing should be made canonical, while keeping
+        // members that are on the same line together.
+
+        let source = "
+f:    fn
+  br a,b->
+            a b
+     # Comment.
+  fn br -> end end
+      eval
+end end
+";
+
+        assert_eq!(
+            format_source(source).unwrap(),
+            "\
+f: fn
+    br a, b ->
+        a b
+        # Comment.
+        fn
+            br ->
+            end
+        end
+        eval
+    end
+end
+",
+        );
+    }
+
+    #[test]
+    fn refuse_to_format_code_with_syntax_errors() {
+        let result = format_source("f: fn br -> -> end end");
+        assert!(matches!(result, Err(FormatError::SyntaxErrors(_))));
+    }
+
+    #[test]
+    fn refuse_to_drop_comments() {
+        // A comment at the end of the code doesn't belong to any function. The
+        // syntax tree doesn't keep track of it, so formatting would lose it.
+
+        let result = format_source("f: fn br -> end end\n# Comment.\n");
+        assert!(matches!(result, Err(FormatError::WouldChangeCode)));
+    }
+}
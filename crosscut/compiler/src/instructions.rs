@@ -1,7 +1,16 @@
 use crosscut_runtime::{Instruction, InstructionAddress};
 
 /// # Compiled instructions for the runtime to execute
-#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
 pub struct Instructions {
     inner: Vec<(InstructionAddress, Instruction)>,
 }
@@ -33,7 +42,9 @@ impl Instructions {
         *stored_instruction = instruction;
     }
 
-    pub fn to_runtime_instructions(&self) -> crosscut_runtime::Instructions {
+    pub fn to_runtime_instructions(
+        &self,
+    ) -> crosscut_runtime::Instructions<'_> {
         crosscut_runtime::Instructions { inner: &self.inner }
     }
 }
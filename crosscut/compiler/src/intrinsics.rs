@@ -1,4 +1,6 @@
-use std::fmt;
+use std::{fmt, iter};
+
+use crosscut_runtime::{Effect, Instruction, Value};
 
 use crate::code::{Signature, Type};
 
@@ -86,19 +88,19 @@ use Type::*;
 
 intrinsics! {
     /// # Add two signed 8-bit integers, triggering an error on overflow
-    "add_s8", AddS8, Some(([Number, Number], [Number]));
+    "add_s8", AddS8, Some(([S8, S8], [S8]));
 
     /// # Add two signed 32-bit integers, triggering an error on overflow
-    "add_s32", AddS32, Some(([Number, Number], [Number]));
+    "add_s32", AddS32, Some(([S32, S32], [S32]));
 
     /// # Add two unsigned 8-bit integers, triggering an error on overflow
-    "add_u8", AddU8, Some(([Number, Number], [Number]));
+    "add_u8", AddU8, Some(([U8, U8], [U8]));
 
     /// # Add two unsigned 8-bit integers, wrapping on overflow
-    "add_u8_wrap", AddU8Wrap, Some(([Number, Number], [Number]));
+    "add_u8_wrap", AddU8Wrap, Some(([U8, U8], [U8]));
 
     /// # Logical and
-    "and", And, Some(([Number, Number], [Number]));
+    "and", And, Some(([Bool, Bool], [Bool]));
 
     /// # Trigger a breakpoint
     "brk", Brk, Some(([], []));
@@ -107,56 +109,139 @@ intrinsics! {
     "copy", Copy, Option::<([Type; 0], [Type; 0])>::None;
 
     /// # Divide two signed 32-bit integers
-    "div_s32", DivS32, Some(([Number, Number], [Number]));
+    "div_s32", DivS32, Some(([S32, S32], [S32]));
 
     /// # Divide two unsigned 8-bit integers
-    "div_u8", DivU8, Some(([Number, Number], [Number]));
+    "div_u8", DivU8, Some(([U8, U8], [U8]));
 
     /// # Drop a value
     "drop", Drop, Option::<([Type; 0], [Type; 0])>::None;
 
     /// # Compare two values for equality
-    "eq", Eq, Some(([Number, Number], [Number]));
+    "eq", Eq, Option::<([Type; 0], [Type; 0])>::None;
 
     /// # Evaluate an anonymous function
     "eval", Eval, Option::<([Type; 0], [Type; 0])>::None;
 
     /// # Determine if the first of two signed 8-bit numbers is greater
-    "greater_s8", GreaterS8, Some(([Number, Number], [Number]));
+    "greater_s8", GreaterS8, Some(([S8, S8], [Bool]));
 
     /// # Determine if the first of two signed 32-bit numbers is greater
-    "greater_s32", GreaterS32, Some(([Number, Number], [Number]));
+    "greater_s32", GreaterS32, Some(([S32, S32], [Bool]));
 
     /// # Determine if the first of two unsigned 8-bit numbers is greater
-    "greater_u8", GreaterU8, Some(([Number, Number], [Number]));
+    "greater_u8", GreaterU8, Some(([U8, U8], [Bool]));
 
     /// # Multiply two signed 32-bit numbers, triggering an error on overflow
-    "mul_s32", MulS32, Some(([Number, Number], [Number]));
+    "mul_s32", MulS32, Some(([S32, S32], [S32]));
 
     /// # Multiply two unsigned 8-bit numbers, wrapping on overflow
-    "mul_u8_wrap", MulU8Wrap, Some(([Number, Number], [Number]));
+    "mul_u8_wrap", MulU8Wrap, Some(([U8, U8], [U8]));
 
     /// # Negate a signed 32-bit number
-    "neg_s32", NegS32, Some(([Number], [Number]));
+    "neg_s32", NegS32, Some(([S32], [S32]));
 
     /// No operation
     "nop", Nop, Some(([], []));
 
     /// # Logical not
-    "not", Not, Some(([Number], [Number]));
+    "not", Not, Some(([Bool], [Bool]));
 
     /// # Compute the remainder of the division of two signed 32-bit numbers
-    "remainder_s32", RemainderS32, Some(([Number, Number], [Number]));
+    "remainder_s32", RemainderS32, Some(([S32, S32], [S32]));
 
     /// # Convert a signed 32-bit number to a signed 8-bit number
-    "s32_to_s8", S32ToS8, Some(([Number], [Number]));
+    "s32_to_s8", S32ToS8, Some(([S32], [S8]));
+
+    /// # Convert a signed 32-bit number to an unsigned 8-bit number
+    "s32_to_u8", S32ToU8, Some(([S32], [U8]));
+
+    /// # Convert a signed 32-bit number to an unsigned 32-bit number
+    "s32_to_u32", S32ToU32, Some(([S32], [U32]));
 
     /// # Subtract two signed 32-bit numbers, triggering an error on overflow
-    "sub_s32", SubS32, Some(([Number, Number], [Number]));
+    "sub_s32", SubS32, Some(([S32, S32], [S32]));
 
     /// # Subtract two unsigned 8-bit numbers, triggering an error on overflow
-    "sub_u8", SubU8, Some(([Number, Number], [Number]));
+    "sub_u8", SubU8, Some(([U8, U8], [U8]));
 
     /// # Subtract two unsigned 8-bit numbers, wrapping on overflow
-    "sub_u8_wrap", SubU8Wrap, Some(([Number, Number], [Number]));
+    "sub_u8_wrap", SubU8Wrap, Some(([U8, U8], [U8]));
+
+    /// # Convert an unsigned 8-bit number to a signed 32-bit number
+    "u8_to_s32", U8ToS32, Some(([U8], [S32]));
+
+    /// # Convert an unsigned 32-bit number to a signed 32-bit number
+    "u32_to_s32", U32ToS32, Some(([U32], [S32]));
+}
+
+impl IntrinsicFunction {
+    /// # Translate a call to this intrinsic function into instructions
+    ///
+    /// `num_values` is the number of runtime values that the operand of `copy`
+    /// or `drop` is made up of. It is ignored for all other intrinsics.
+    /// `is_tail_call` is only relevant for `eval`.
+    ///
+    /// This is the single place where intrinsics are mapped to instructions.
+    /// Besides the compiler, the debugger's expression evaluator relies on it.
+    pub fn instructions(
+        &self,
+        num_values: usize,
+        is_tail_call: bool,
+    ) -> Vec<Instruction> {
+        let instruction = match self {
+            Self::AddS8 => Instruction::AddS8,
+            Self::AddS32 => Instruction::AddS32,
+            Self::AddU8 => Instruction::AddU8,
+            Self::AddU8Wrap => Instruction::AddU8Wrap,
+            Self::And => Instruction::LogicalAnd,
+            Self::Brk => Instruction::TriggerEffect {
+                effect: Effect::Breakpoint,
+            },
+            Self::Copy => {
+                // Copying the value at the same offset repeatedly copies all of
+                // its runtime values in order, since each copy pushes the ones
+                // that come after it further down.
+                let offset_from_top = Value::from(num_values as i32 - 1);
+
+                return iter::repeat_n(
+                    [
+                        Instruction::Push {
+                            value: offset_from_top,
+                        },
+                        Instruction::Copy,
+                    ],
+                    num_values,
+                )
+                .flatten()
+                .collect();
+            }
+            Self::DivS32 => Instruction::DivS32,
+            Self::DivU8 => Instruction::DivU8,
+            Self::Drop => {
+                return vec![Instruction::Drop; num_values];
+            }
+            Self::Eq => Instruction::Eq,
+            Self::Eval => Instruction::Eval { is_tail_call },
+            Self::GreaterS8 => Instruction::GreaterS8,
+            Self::GreaterS32 => Instruction::GreaterS32,
+            Self::GreaterU8 => Instruction::GreaterU8,
+            Self::MulS32 => Instruction::MulS32,
+            Self::MulU8Wrap => Instruction::MulU8Wrap,
+            Self::NegS32 => Instruction::NegS32,
+            Self::Nop => Instruction::Nop,
+            Self::Not => Instruction::LogicalNot,
+            Self::RemainderS32 => Instruction::RemainderS32,
+            Self::S32ToS8 => Instruction::ConvertS32ToS8,
+            Self::S32ToU8 => Instruction::ConvertS32ToU8,
+            Self::S32ToU32 => Instruction::ConvertS32ToU32,
+            Self::SubS32 => Instruction::SubS32,
+            Self::SubU8 => Instruction::SubU8,
+            Self::SubU8Wrap => Instruction::SubU8Wrap,
+            Self::U8ToS32 => Instruction::ConvertU8ToS32,
+            Self::U32ToS32 => Instruction::ConvertU32ToS32,
+        };
+
+        vec![instruction]
+    }
 }
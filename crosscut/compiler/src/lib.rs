@@ -1,7 +1,11 @@
 pub mod code;
+pub mod diagnostics;
+pub mod format;
 pub mod host;
 pub mod intrinsics;
 pub mod source_map;
+pub mod sources;
+pub mod standard_library;
 
 mod compiler;
 mod instructions;
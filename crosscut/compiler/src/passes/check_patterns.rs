@@ -0,0 +1,356 @@
+use crate::{
+    code::{
+        syntax::{Function, Located, Parameter, SyntaxTree},
+        Type, Types,
+    },
+    diagnostics::{Diagnostic, Diagnostics},
+};
+
+/// # Check the patterns in the parameters of all branches
+///
+/// Reports an error for every variant parameter that doesn't refer to an
+/// existing variant, or that binds the payload of a variant that doesn't have
+/// one.
+///
+/// Also reports a warning for every function whose branches don't cover all
+/// possible arguments. Without this check, calling such a function with an
+/// unmatched argument would only be detected at runtime. For functions that
+/// match on enum variants, this is an error instead, as their parameters are
+/// expected to be matched completely.
+///
+/// Finally, reports a warning for every branch that is unreachable, because the
+/// branches before it already match everything it would match.
+pub fn check_patterns(
+    syntax_tree: &SyntaxTree,
+    types: &Types,
+    diagnostics: &mut Diagnostics,
+) {
+    for function in syntax_tree.all_functions() {
+        let mut matches_on_variants = false;
+
+        for branch in function.branches() {
+            for parameter in branch.parameters() {
+                let Parameter::Variant {
+                    enum_,
+                    variant,
+                    binding,
+                } = parameter.fragment
+                else {
+                    continue;
+                };
+
+                matches_on_variants = true;
+
+                let span = syntax_tree
+                    .spans
+                    .of_parameter(&parameter.location)
+                    .copied();
+                let name = format!("{enum_}.{variant}");
+
+                let Some((_, index)) = syntax_tree.variant_by_name(&name)
+                else {
+                    diagnostics.push(Diagnostic::error(
+                        format!("Unknown variant `{name}`"),
+                        span,
+                    ));
+                    continue;
+                };
+
+                let has_payload = syntax_tree
+                    .enum_by_name(enum_)
+                    .and_then(|enum_| enum_.fragment.variants.get(&index))
+                    .is_some_and(|variant| variant.payload.is_some());
+                if binding.is_some() && !has_payload {
+                    diagnostics.push(Diagnostic::error(
+                        format!(
+                            "Variant `{name}` has no payload that could be \
+                            bound"
+                        ),
+                        span,
+                    ));
+                }
+            }
+        }
+
+        check_branches(
+            function,
+            matches_on_variants,
+            syntax_tree,
+            types,
+            diagnostics,
+        );
+    }
+}
+
+fn check_branches(
+    function: Located<&Function>,
+    matches_on_variants: bool,
+    syntax_tree: &SyntaxTree,
+    types: &Types,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut rows = Vec::new();
+    let mut columns = Vec::<Option<&Type>>::new();
+
+    for branch in function.branches() {
+        let mut row = Vec::new();
+
+        for (i, parameter) in branch.parameters().enumerate() {
+            let pattern = match parameter.fragment {
+                Parameter::Binding { .. } => Pattern::Wildcard,
+                Parameter::Literal { value } => Pattern::Literal(*value),
+                Parameter::Variant { variant, .. } => {
+                    Pattern::Variant(variant.clone())
+                }
+                Parameter::Error(_) => {
+                    // This has already been reported as a syntax error.
+                    return;
+                }
+            };
+            row.push(pattern);
+
+            let type_ = types.type_of_parameter(&parameter.location);
+            match columns.get_mut(i) {
+                Some(column) => {
+                    *column = column.or(type_);
+                }
+                None => {
+                    columns.push(type_);
+                }
+            }
+        }
+
+        rows.push((branch.location, row));
+    }
+
+    if rows.iter().any(|(_, row)| row.len() != columns.len()) {
+        // The branches don't agree on the number of parameters. That's a
+        // type error, and not something we can make sense of here.
+        return;
+    }
+
+    for (i, (location, row)) in rows.iter().enumerate() {
+        let previous = rows[..i]
+            .iter()
+            .map(|(_, row)| row.as_slice())
+            .collect::<Vec<_>>();
+
+        if !is_useful(&previous, row, &columns) {
+            let span = syntax_tree.spans.of_branch(location).copied();
+            diagnostics.push(
+                Diagnostic::warning("Branch is unreachable", span).with_note(
+                    "Previous branches already match all arguments that this \
+                    branch would match.",
+                ),
+            );
+        }
+    }
+
+    let rows = rows
+        .iter()
+        .map(|(_, row)| row.as_slice())
+        .collect::<Vec<_>>();
+    let Some(unmatched) = find_unmatched(&rows, &columns) else {
+        return;
+    };
+
+    let span = syntax_tree.spans.of_function(&function.location).copied();
+    let unmatched = unmatched
+        .iter()
+        .zip(&columns)
+        .map(|(pattern, type_)| pattern.display(*type_))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let message = "Branches don't cover all possible arguments";
+    let diagnostic = if matches_on_variants {
+        Diagnostic::error(message, span)
+    } else {
+        Diagnostic::warning(message, span)
+    };
+    diagnostics.push(
+        diagnostic.with_note(format!("No branch matches `{unmatched}`.")),
+    );
+}
+
+/// # Find arguments that none of the rows of patterns match, if any
+///
+/// Each row holds the patterns of one branch. Each column holds the patterns
+/// for one parameter, along with the type of that parameter.
+///
+/// Returns the patterns that describe the unmatched arguments. A wildcard in
+/// there means that any argument would do.
+fn find_unmatched(
+    rows: &[&[Pattern]],
+    columns: &[Option<&Type>],
+) -> Option<Vec<Pattern>> {
+    let Some((column, rest)) = columns.split_first() else {
+        // There are no more parameters to look at. If there's a row left, it
+        // matches whatever arguments made it this far.
+        return if rows.is_empty() {
+            Some(Vec::new())
+        } else {
+            None
+        };
+    };
+
+    let constructors = all_constructors(*column);
+    let missing = constructors.as_ref().map(|constructors| {
+        constructors
+            .iter()
+            .find(|constructor| !rows.iter().any(|row| row[0] == **constructor))
+    });
+
+    if let (Some(constructors), Some(None)) = (&constructors, missing) {
+        // Every possible argument is matched by a specific pattern in some
+        // row. Each of them might still be missing something in the following
+        // columns though.
+        for constructor in constructors {
+            let rows = specialize(rows, constructor);
+
+            if let Some(unmatched) = find_unmatched(&rows, rest) {
+                let first = constructor.clone();
+                return Some([first].into_iter().chain(unmatched).collect());
+            }
+        }
+
+        return None;
+    }
+
+    // Not all possible arguments for this column are covered by specific
+    // patterns. Only rows with a wildcard can match the ones that are not.
+    let unmatched = find_unmatched(&default(rows), rest)?;
+    let first = missing
+        .flatten()
+        .cloned()
+        .or_else(|| unmatched_literal(rows, *column))
+        .unwrap_or(Pattern::Wildcard);
+
+    Some([first].into_iter().chain(unmatched).collect())
+}
+
+/// # Determine, if a row matches any arguments that the other rows don't
+fn is_useful(
+    rows: &[&[Pattern]],
+    row: &[Pattern],
+    columns: &[Option<&Type>],
+) -> bool {
+    let (Some((column, rest)), Some((first, row))) =
+        (columns.split_first(), row.split_first())
+    else {
+        return rows.is_empty();
+    };
+
+    if let Pattern::Wildcard = first {
+        let constructors = all_constructors(*column).filter(|constructors| {
+            constructors.iter().all(|constructor| {
+                rows.iter().any(|row| row[0] == *constructor)
+            })
+        });
+
+        match constructors {
+            Some(constructors) => constructors.iter().any(|constructor| {
+                is_useful(&specialize(rows, constructor), row, rest)
+            }),
+            None => is_useful(&default(rows), row, rest),
+        }
+    } else {
+        is_useful(&specialize(rows, first), row, rest)
+    }
+}
+
+/// # Determine all specific patterns that could match a value of a type
+///
+/// Returns `None`, if there are too many of those to list.
+fn all_constructors(type_: Option<&Type>) -> Option<Vec<Pattern>> {
+    match type_? {
+        Type::Bool => Some(vec![Pattern::Literal(0), Pattern::Literal(1)]),
+        Type::Enum { variants, .. } => Some(
+            variants
+                .iter()
+                .map(|(variant, _)| Pattern::Variant(variant.clone()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// # Find a literal that isn't matched by the first column of the rows
+///
+/// Returns `None`, if there are no literals in that column, as any argument
+/// would be a valid example then.
+fn unmatched_literal(
+    rows: &[&[Pattern]],
+    type_: Option<&Type>,
+) -> Option<Pattern> {
+    let literals = rows
+        .iter()
+        .filter_map(|row| match row[0] {
+            Pattern::Literal(value) => Some(value),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if literals.is_empty() {
+        return None;
+    }
+
+    // There are only so many literals in the column, so one more candidate
+    // than that is guaranteed to include an unmatched one, unless the type
+    // can't represent it.
+    (0..)
+        .take(literals.len() + 1)
+        .filter(|value| type_.is_none_or(|type_| type_.can_represent(*value)))
+        .find(|value| !literals.contains(value))
+        .map(Pattern::Literal)
+}
+
+/// # Keep the rows that could match the specific pattern in the first column
+fn specialize<'r>(
+    rows: &[&'r [Pattern]],
+    constructor: &Pattern,
+) -> Vec<&'r [Pattern]> {
+    rows.iter()
+        .filter(|row| row[0] == *constructor || row[0] == Pattern::Wildcard)
+        .map(|row| &row[1..])
+        .collect()
+}
+
+/// # Keep the rows that match anything in the first column
+fn default<'r>(rows: &[&'r [Pattern]]) -> Vec<&'r [Pattern]> {
+    rows.iter()
+        .filter(|row| row[0] == Pattern::Wildcard)
+        .map(|row| &row[1..])
+        .collect()
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Pattern {
+    Wildcard,
+    Literal(i64),
+    Variant(String),
+}
+
+impl Pattern {
+    fn display(&self, type_: Option<&Type>) -> String {
+        match self {
+            Self::Wildcard => "_".to_string(),
+            Self::Literal(value) => value.to_string(),
+            Self::Variant(variant) => {
+                let has_payload = type_
+                    .and_then(|type_| type_.variant(variant))
+                    .is_some_and(|(_, payload)| payload.is_some());
+
+                let mut display = match type_ {
+                    Some(type_) => format!("{type_}.{variant}"),
+                    None => variant.clone(),
+                };
+                if has_payload {
+                    display.push_str(" _");
+                }
+
+                display
+            }
+        }
+    }
+}
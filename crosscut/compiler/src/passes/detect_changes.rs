@@ -1,9 +1,15 @@
 use std::collections::BTreeMap;
 
 use crate::code::{
-    syntax::SyntaxTree, Changes, FunctionInUpdate, FunctionUpdate, Hash,
+    syntax::{Located, NamedFunction, SyntaxTree},
+    Changes, FunctionInUpdate, FunctionUpdate, Hash,
 };
 
+/// # Detect which named functions have changed since the last compilation
+///
+/// Functions are compared to the functions of the same module in the old code.
+/// Modules are identified by name, as their indices can change between
+/// compilations, if the imports change.
 pub fn detect_changes(
     old_code: Option<SyntaxTree>,
     new_code: &SyntaxTree,
@@ -14,7 +20,15 @@ pub fn detect_changes(
     let mut updated = Vec::new();
 
     for new_function in new_code.named_functions() {
-        if old_code.named_functions.values().any(|old_function| {
+        let module = module_name(new_code, &new_function);
+        let old_functions_in_module = old_code
+            .named_functions()
+            .filter(|old_function| {
+                module_name(&old_code, old_function) == module
+            })
+            .collect::<Vec<_>>();
+
+        if old_functions_in_module.iter().any(|old_function| {
             Hash::new(&old_function.inner)
                 == Hash::new(&new_function.fragment.inner)
         }) {
@@ -22,8 +36,9 @@ pub fn detect_changes(
             continue;
         }
 
-        if let Some(old_function) =
-            old_code.function_by_name(&new_function.name)
+        if let Some(old_function) = old_functions_in_module
+            .iter()
+            .find(|old_function| old_function.name == new_function.name)
         {
             // Found a function with the same name. But it can't have the same
             // hash, or we wouldn't have made it here. Assuming the new function
@@ -49,3 +64,14 @@ pub fn detect_changes(
 
     Changes { added, updated }
 }
+
+fn module_name<'r>(
+    syntax_tree: &'r SyntaxTree,
+    function: &Located<&NamedFunction>,
+) -> &'r str {
+    syntax_tree
+        .modules
+        .get(&function.module)
+        .map(|module| module.name.as_str())
+        .unwrap_or_default()
+}
@@ -0,0 +1,42 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    code::{
+        syntax::{NamedFunction, SyntaxTree},
+        Index,
+    },
+    diagnostics::Diagnostics,
+};
+
+/// # Find the named functions that contain errors
+///
+/// A named function contains an error, if any error that has been reported
+/// points into its source code. This includes errors within the local functions
+/// that it defines.
+///
+/// Errors that don't point to any source code, or point to code outside of any
+/// function, are not attributed to a function.
+pub fn find_erroneous_functions(
+    syntax_tree: &SyntaxTree,
+    diagnostics: &Diagnostics,
+) -> BTreeSet<Index<NamedFunction>> {
+    syntax_tree
+        .named_functions()
+        .filter(|function| {
+            let Some(function_span) =
+                syntax_tree.spans.of_named_function(&function.location)
+            else {
+                return false;
+            };
+
+            diagnostics.errors().any(|error| {
+                error.span.is_some_and(|span| {
+                    span.module == function_span.module
+                        && span.start >= function_span.start
+                        && span.end <= function_span.end
+                })
+            })
+        })
+        .map(|function| function.location)
+        .collect()
+}
@@ -7,7 +7,8 @@ use crate::code::{syntax::FunctionLocation, DependencyCluster};
 use super::{
     compile_function::{
         compile_call_to_function, compile_definition_of_local_function,
-        compile_function, CallToFunction,
+        compile_function, compile_reference_to_function, CallToFunction,
+        DefinitionOfLocalFunction,
     },
     compile_functions::FunctionsContext,
 };
@@ -15,10 +16,9 @@ use super::{
 pub struct ClusterContext {
     /// # Recursive calls within the cluster that need to be replaced
     ///
-    /// When a recursive call is encountered, not all branches of the callee
-    /// (which might be the calling function itself, or another function in the
-    /// same cluster) might be compiled yet. But they're needed to compile the
-    /// call.
+    /// When a recursive call is encountered, the callee (which might be the
+    /// calling function itself, or another function in the same cluster) might
+    /// not be compiled yet. But its address is needed to compile the call.
     ///
     /// So instead of compiling the call right then and there, a placeholder
     /// instruction is emitted. An entry is also added to this map, so the
@@ -27,13 +27,20 @@ pub struct ClusterContext {
     pub recursive_calls_by_callee:
         BTreeMap<FunctionLocation, Vec<CallToFunction>>,
 
+    /// # Recursive references within the cluster that need to be replaced
+    ///
+    /// Works like `recursive_calls_by_callee`, but for references to functions
+    /// within the cluster, like `&f`.
+    pub recursive_references_by_function:
+        BTreeMap<FunctionLocation, Vec<InstructionAddress>>,
+
     /// # Recursive local functions within the cluster that need to be replaced
     ///
     /// When a recursive local function is encountered, it might not have been
     /// compiled yet. To deal with this, we generate a placeholder that needs to
     /// be replaced later. This map tracks those necessary replacements.
     pub recursive_local_function_definitions_by_local_function:
-        BTreeMap<FunctionLocation, InstructionAddress>,
+        BTreeMap<FunctionLocation, DefinitionOfLocalFunction>,
 }
 
 pub fn compile_cluster(
@@ -42,18 +49,21 @@ pub fn compile_cluster(
 ) {
     let mut context = ClusterContext {
         recursive_calls_by_callee: BTreeMap::new(),
+        recursive_references_by_function: BTreeMap::new(),
         recursive_local_function_definitions_by_local_function: BTreeMap::new(),
     };
 
     for function in cluster.functions(functions_context.syntax_tree) {
         let location = function.location.clone();
 
-        let runtime_function =
-            compile_function(function, &mut context, functions_context);
+        let address = match functions_context.fallbacks.get(&location) {
+            Some(address) => *address,
+            None => compile_function(function, &mut context, functions_context),
+        };
 
         functions_context
             .compiled_functions_by_location
-            .insert(location, runtime_function);
+            .insert(location, address);
     }
 
     for (callee, calls) in context.recursive_calls_by_callee {
@@ -66,12 +76,22 @@ pub fn compile_cluster(
             );
         }
     }
-    for (local_function, address) in
+    for (function, references) in context.recursive_references_by_function {
+        for address in references {
+            compile_reference_to_function(
+                &function,
+                address,
+                functions_context.compiled_functions_by_location,
+                functions_context.instructions,
+            );
+        }
+    }
+    for (local_function, definition) in
         context.recursive_local_function_definitions_by_local_function
     {
         compile_definition_of_local_function(
             local_function,
-            address,
+            definition,
             functions_context,
         );
     }
@@ -1,12 +1,16 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::{collections::BTreeMap, iter, mem};
 
 use crosscut_runtime::{Effect, Instruction, InstructionAddress, Value};
 
 use crate::{
-    code::syntax::{
-        Binding, Branch, Expression, Function, FunctionLocation, Located,
-        Parameter,
+    code::{
+        syntax::{
+            Binding, Branch, Expression, Function, FunctionLocation, Located,
+            Member, Parameter, SyntaxTree,
+        },
+        Bindings, Constructor, Type, Types,
     },
+    host::HostFunction,
     intrinsics::IntrinsicFunction,
     source_map::Mapping,
     Instructions,
@@ -24,22 +28,28 @@ pub fn compile_function(
     function: Located<&Function>,
     cluster_context: &mut ClusterContext,
     functions_context: &mut FunctionsContext,
-) -> crosscut_runtime::Function {
+) -> InstructionAddress {
     let mut context = FunctionContext {
         location: &function.location,
     };
-    let mut runtime_function = crosscut_runtime::Function::default();
+    let prologue_address = compile_prologue(&context, functions_context);
     let mut instruction_range = None;
+    let mut jumps_to_next_branch = Vec::new();
 
     for branch in function.branches() {
-        let (runtime_branch, [first_address, last_address]) = compile_branch(
+        let (jumps, [first_address, last_address]) = compile_branch(
             branch,
             &mut context,
             cluster_context,
             functions_context,
         );
 
-        runtime_function.branches.push(runtime_branch);
+        // If the previous branch doesn't match, it continues with this one.
+        compile_jumps(
+            mem::replace(&mut jumps_to_next_branch, jumps),
+            first_address,
+            functions_context.instructions,
+        );
 
         instruction_range = {
             let [first_in_function, _last_in_function] =
@@ -49,58 +59,174 @@ pub fn compile_function(
         };
     }
 
-    if let Some(instruction_range) = instruction_range {
-        functions_context
-            .source_map
-            .map_function_to_instructions(function.location, instruction_range);
+    if instruction_range.is_none() || !jumps_to_next_branch.is_empty() {
+        // There are arguments that no branch matches. If we get here, we're
+        // out of options.
+        let address = emit_instruction(
+            Instruction::TriggerEffect {
+                effect: Effect::NoMatch,
+            },
+            functions_context.instructions,
+            None,
+        );
+        compile_jumps(
+            jumps_to_next_branch,
+            address,
+            functions_context.instructions,
+        );
+
+        instruction_range = {
+            let [first_in_function, _last_in_function] =
+                instruction_range.unwrap_or([address, address]);
+
+            Some([first_in_function, address])
+        };
     }
 
-    runtime_function
+    let [first_address, last_address] = instruction_range
+        .expect("Just made sure that the function has instructions.");
+    let first_address = prologue_address.unwrap_or(first_address);
+
+    functions_context.source_map.map_function_to_instructions(
+        function.location,
+        [first_address, last_address],
+    );
+
+    first_address
 }
 
+/// # Compile the code that runs before any of the function's branches
+///
+/// A local function finds the values that it captured from its environment on
+/// top of the stack, above its arguments. See `Type::Function` for the layout.
+/// Before any branch can look at the arguments, those values need to be moved
+/// into the slots where the branches expect them.
+///
+/// Returns the address of the first instruction, if any code had to be
+/// generated.
+fn compile_prologue(
+    function_context: &FunctionContext,
+    functions_context: &mut FunctionsContext,
+) -> Option<InstructionAddress> {
+    let mut slots = Slots::default();
+    let indices = compile_environment(
+        function_context.location,
+        functions_context.bindings,
+        functions_context.syntax_tree,
+        functions_context.types,
+    )
+    .into_iter()
+    .map(|name| slots.insert(name))
+    .collect::<Vec<_>>();
+
+    let mut first_address = None;
+
+    for index in indices.into_iter().rev() {
+        let address = emit_instruction(
+            Instruction::StoreSlot { index },
+            functions_context.instructions,
+            None,
+        );
+        first_address = first_address.or(Some(address));
+    }
+
+    first_address
+}
+
+/// # Compile a branch
+///
+/// Returns the addresses of the instructions that jump to the next branch, if
+/// the arguments don't match this one's parameters. Those need to be replaced
+/// with the actual jumps, once the address of the next branch is known.
 fn compile_branch(
     branch: Located<&Branch>,
     function_context: &mut FunctionContext,
     cluster_context: &mut ClusterContext,
     functions_context: &mut FunctionsContext,
-) -> (crosscut_runtime::Branch, [InstructionAddress; 2]) {
-    let parameters = branch.parameters.values().filter_map(|parameter| {
-        match parameter {
-            Parameter::Binding {
-                binding: Binding { name },
-                type_: _,
-            } => Some(name),
-            Parameter::Literal { .. } => {
-                // Literal patterns are only relevant when
-                // selecting the branch to execute. They no
-                // longer have meaning once the function
-                // actually starts executing.
-                None
-            }
-        }
-    });
-    let bindings_address =
-        compile_bindings(parameters, functions_context.instructions);
+) -> (Vec<InstructionAddress>, [InstructionAddress; 2]) {
+    let patterns = branch
+        .parameters()
+        .map(|parameter| compile_parameter(parameter, functions_context.types))
+        .collect::<Vec<_>>();
+    let is_erroneous = patterns.iter().any(|patterns| patterns.is_none());
+
+    let patterns = patterns
+        .into_iter()
+        .flat_map(|patterns| {
+            patterns.unwrap_or_else(|| {
+                // This parameter is going to match anything, so the branch can
+                // get selected and trigger the error.
+                vec![Pattern::Identifier {
+                    name: "_".to_string(),
+                }]
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let (jumps_to_next_branch, match_address) =
+        compile_pattern_matching(&patterns, functions_context.instructions);
+    let error_address = if is_erroneous {
+        // If we couldn't make sense of the parameters, then there's no point
+        // in executing the branch. Let's make sure nobody tries.
+        //
+        // This only happens, if the function never built without errors.
+        // Otherwise, its previous version is used instead.
+        Some(emit_instruction(
+            Instruction::TriggerEffect {
+                effect: Effect::BuildError,
+            },
+            functions_context.instructions,
+            None,
+        ))
+    } else {
+        None
+    };
+
+    let mut slots = Slots::default();
+    for name in compile_environment(
+        function_context.location,
+        functions_context.bindings,
+        functions_context.syntax_tree,
+        functions_context.types,
+    ) {
+        slots.insert(name);
+    }
+    let arguments_address = compile_arguments(
+        &patterns,
+        &mut slots,
+        functions_context.instructions,
+    );
+    functions_context
+        .source_map
+        .map_branch_to_slots(branch.location.clone(), slots.names.clone());
 
     let [body_address, last_address] = {
         let mut body_address = None;
 
-        for expression in branch.expressions() {
-            let bindings = branch
-                .bindings()
-                .map(|binding| binding.fragment.clone())
-                .chain(
-                    functions_context
-                        .bindings
-                        .environment_of(function_context.location)
-                        .bindings(functions_context.syntax_tree)
-                        .map(|binding| binding.fragment.clone()),
-                )
-                .collect();
+        for member in branch.body() {
+            if let Member::Error(_) = member.fragment {
+                let mut mapping = functions_context
+                    .source_map
+                    .map_expression_to_instructions(member.location);
+                let addr = emit_instruction(
+                    Instruction::TriggerEffect {
+                        effect: Effect::BuildError,
+                    },
+                    functions_context.instructions,
+                    Some(&mut mapping),
+                );
+                body_address = body_address.or(Some(addr));
+
+                continue;
+            }
+
+            let Some((expression, _)) = member.into_expression() else {
+                continue;
+            };
 
             let addr = compile_expression(
                 expression,
-                bindings,
+                &slots,
                 cluster_context,
                 functions_context,
             );
@@ -138,45 +264,153 @@ fn compile_branch(
         [first_instruction, last_instruction]
     };
 
-    let first_address = bindings_address.unwrap_or(body_address);
-
-    let branch = crosscut_runtime::Branch {
-        parameters: branch
-            .parameters
-            .values()
-            .cloned()
-            .map(|parameter| match parameter {
-                Parameter::Binding {
-                    binding: Binding { name },
-                    type_: _,
-                } => crosscut_runtime::Pattern::Identifier { name },
-                Parameter::Literal { value } => {
-                    crosscut_runtime::Pattern::Literal { value }
-                }
-            })
+    let first_address = match_address
+        .or(error_address)
+        .or(arguments_address)
+        .unwrap_or(body_address);
+
+    (jumps_to_next_branch, [first_address, last_address])
+}
+
+/// # Compile a parameter into the patterns that match its values
+///
+/// Returns `None`, if the parameter is erroneous.
+fn compile_parameter(
+    parameter: Located<&Parameter>,
+    types: &Types,
+) -> Option<Vec<Pattern>> {
+    let type_ = types.type_of_parameter(&parameter.location);
+
+    let patterns = match parameter.fragment {
+        Parameter::Binding {
+            binding: Binding { name },
+            type_: _,
+        } => binding_names(name, type_)
+            .into_iter()
+            .map(|name| Pattern::Identifier { name })
             .collect(),
-        start: first_address,
+        Parameter::Literal { value } => {
+            vec![Pattern::Literal {
+                value: encode_literal(*value, type_),
+            }]
+        }
+        Parameter::Variant {
+            variant, binding, ..
+        } => {
+            let type_ = type_?;
+            let (tag, payload) = type_.variant(variant)?;
+
+            // See `Type::Enum` for the layout of an enum value. Any values
+            // that are not bound to the name of the payload, are bound to the
+            // wildcard name, which can't be referenced.
+            let mut names = match (binding, payload) {
+                (Some(binding), Some(payload)) => {
+                    binding_names(&binding.name, Some(payload))
+                }
+                (None, Some(payload)) => {
+                    vec!["_".to_string(); payload.num_values()]
+                }
+                (Some(_), None) => {
+                    return None;
+                }
+                (None, None) => Vec::new(),
+            };
+            names.resize(type_.num_values() - 1, "_".to_string());
+
+            names
+                .into_iter()
+                .map(|name| Pattern::Identifier { name })
+                .chain([Pattern::Literal { value: tag.into() }])
+                .collect()
+        }
+        Parameter::Error(_) => {
+            return None;
+        }
     };
 
-    (branch, [first_address, last_address])
+    Some(patterns)
 }
 
-fn compile_bindings<'r, N>(
-    names: N,
+/// # Compile the code that decides whether the arguments match the patterns
+///
+/// The arguments stay on the stack, regardless of whether they match. If they
+/// don't, the code jumps to the next branch, which needs to look at them too.
+///
+/// Returns the addresses of the placeholders for those jumps, along with the
+/// address of the first instruction, if any code had to be generated.
+fn compile_pattern_matching(
+    patterns: &[Pattern],
     instructions: &mut Instructions,
-) -> Option<InstructionAddress>
-where
-    N: IntoIterator<Item = &'r String>,
-    N::IntoIter: DoubleEndedIterator,
-{
+) -> (Vec<InstructionAddress>, Option<InstructionAddress>) {
+    let mut jumps_to_next_branch = Vec::new();
     let mut first_address = None;
 
-    for name in names.into_iter().rev() {
+    for (i, pattern) in patterns.iter().enumerate() {
+        let Pattern::Literal { value } = pattern else {
+            // Identifier patterns match anything.
+            continue;
+        };
+
+        let offset_from_top = u32::try_from(patterns.len() - 1 - i)
+            .expect("Number of parameters must fit into `u32`.");
+
         let address = emit_instruction(
-            Instruction::Bind { name: name.clone() },
+            Instruction::CompareOperand {
+                offset_from_top,
+                value: *value,
+            },
             instructions,
             None,
         );
+
+        // We don't know the address of the next branch yet. Let's emit a
+        // placeholder that will get replaced, once we do.
+        let jump = emit_instruction(
+            Instruction::TriggerEffect {
+                effect: Effect::CompilerBug,
+            },
+            instructions,
+            None,
+        );
+
+        jumps_to_next_branch.push(jump);
+        first_address = first_address.or(Some(address));
+    }
+
+    (jumps_to_next_branch, first_address)
+}
+
+fn compile_jumps(
+    jumps: Vec<InstructionAddress>,
+    address: InstructionAddress,
+    instructions: &mut Instructions,
+) {
+    for jump in jumps {
+        instructions.replace(&jump, Instruction::JumpIfZero { address });
+    }
+}
+
+/// # Compile the code that takes the arguments off the stack
+///
+/// Literal patterns are only relevant when selecting the branch to execute.
+/// They no longer have meaning once the function actually starts executing.
+/// Only the values matched by identifier patterns need to be stored in slots.
+fn compile_arguments(
+    patterns: &[Pattern],
+    slots: &mut Slots,
+    instructions: &mut Instructions,
+) -> Option<InstructionAddress> {
+    let mut first_address = None;
+
+    for pattern in patterns.iter().rev() {
+        let instruction = match pattern {
+            Pattern::Identifier { name } => Instruction::StoreSlot {
+                index: slots.insert(name.clone()),
+            },
+            Pattern::Literal { .. } => Instruction::Drop,
+        };
+
+        let address = emit_instruction(instruction, instructions, None);
         first_address = first_address.or(Some(address));
     }
 
@@ -185,7 +419,7 @@ where
 
 fn compile_expression(
     expression: Located<&Expression>,
-    _: BTreeSet<Binding>,
+    slots: &Slots,
     cluster_context: &mut ClusterContext,
     functions_context: &mut FunctionsContext,
 ) -> InstructionAddress {
@@ -204,17 +438,59 @@ fn compile_expression(
                 .is_binding(&expression.location)
                 .is_some()
             {
-                emit_instruction(
-                    Instruction::BindingEvaluate { name: name.clone() },
+                let type_ = functions_context
+                    .types
+                    .signature_of_expression(&expression.location)
+                    .and_then(|signature| signature.outputs.first());
+
+                let mut address = None;
+                for name in binding_names(name, type_) {
+                    let instruction = match slots.get(&name) {
+                        Some(index) => Instruction::LoadSlot { index },
+                        None => Instruction::TriggerEffect {
+                            // Every binding that can be accessed from the
+                            // branch has a slot.
+                            effect: Effect::CompilerBug,
+                        },
+                    };
+
+                    let addr = emit_instruction(
+                        instruction,
+                        functions_context.instructions,
+                        Some(&mut mapping),
+                    );
+                    address = address.or(Some(addr));
+                }
+
+                address.expect("A binding consists of at least one name.")
+            } else if let Some(constructor) = functions_context
+                .function_calls
+                .is_call_to_constructor(&expression.location)
+            {
+                let type_ = functions_context
+                    .types
+                    .signature_of_expression(&expression.location)
+                    .and_then(|signature| signature.outputs.first());
+
+                compile_constructor(
+                    constructor,
+                    type_,
+                    functions_context.syntax_tree,
                     functions_context.instructions,
-                    Some(&mut mapping),
+                    &mut mapping,
                 )
             } else if let Some(function) = functions_context
                 .function_calls
                 .is_call_to_intrinsic_function(&expression.location)
             {
+                let operand = functions_context
+                    .types
+                    .stack_at(&expression.location)
+                    .and_then(|stack| stack.last());
+
                 compile_intrinsic(
                     function,
+                    operand,
                     is_tail_expression,
                     functions_context.instructions,
                     &mut mapping,
@@ -223,21 +499,11 @@ fn compile_expression(
                 .function_calls
                 .is_call_to_host_function(&expression.location)
             {
-                let address = emit_instruction(
-                    Instruction::Push {
-                        value: function.number.into(),
-                    },
-                    functions_context.instructions,
-                    Some(&mut mapping),
-                );
-                emit_instruction(
-                    Instruction::TriggerEffect {
-                        effect: Effect::Host,
-                    },
+                compile_call_to_host_function(
+                    function,
                     functions_context.instructions,
                     Some(&mut mapping),
-                );
-                address
+                )
             } else if let Some(callee_location) = functions_context
                 .function_calls
                 .is_call_to_user_defined_function(&expression.location)
@@ -276,7 +542,7 @@ fn compile_expression(
 
                     address
                 } else {
-                    let Some(function) = functions_context
+                    let Some(callee_address) = functions_context
                         .compiled_functions_by_location
                         .get(callee_location)
                     else {
@@ -302,10 +568,7 @@ fn compile_expression(
                     };
 
                     emit_instruction(
-                        Instruction::CallFunction {
-                            callee: function.clone(),
-                            is_tail_call: is_tail_expression,
-                        },
+                        compile_call(*callee_address, is_tail_expression),
                         functions_context.instructions,
                         Some(&mut mapping),
                     )
@@ -335,13 +598,138 @@ fn compile_expression(
                 )
             }
         }
-        Expression::LiteralNumber { value } => emit_instruction(
-            Instruction::Push { value: *value },
-            functions_context.instructions,
-            Some(&mut mapping),
-        ),
+        Expression::FunctionReference { name } => {
+            if let Some(function) = functions_context
+                .function_calls
+                .is_call_to_host_function(&expression.location)
+            {
+                let Some(address) =
+                    functions_context.host_functions.get(&function.number)
+                else {
+                    unreachable!(
+                        "Compiling reference to host function `{name}`. \
+                        Expecting all host functions that are referred to, to \
+                        be compiled before any references to them.",
+                    );
+                };
+
+                emit_instruction(
+                    compile_reference(*address),
+                    functions_context.instructions,
+                    Some(&mut mapping),
+                )
+            } else if let Some(callee_location) = functions_context
+                .function_calls
+                .is_call_to_user_defined_function(&expression.location)
+            {
+                let address = if functions_context
+                    .recursion
+                    .is_recursive_expression(&expression.location)
+                {
+                    // Same as with recursive calls, we can't assume that the
+                    // function we refer to has been compiled yet. Let's emit a
+                    // placeholder, which gets replaced later.
+                    let address = emit_instruction(
+                        Instruction::TriggerEffect {
+                            effect: Effect::CompilerBug,
+                        },
+                        functions_context.instructions,
+                        Some(&mut mapping),
+                    );
+                    cluster_context
+                        .recursive_references_by_function
+                        .entry(callee_location.clone())
+                        .or_default()
+                        .push(address);
+
+                    address
+                } else {
+                    let Some(callee_address) = functions_context
+                        .compiled_functions_by_location
+                        .get(callee_location)
+                    else {
+                        unreachable!(
+                            "Compiling reference to user-defined function \
+                            `{name}`. Expecting functions to be compiled \
+                            before any non-recursive references to them, but \
+                            can't find the compiled version of this one.",
+                        );
+                    };
+
+                    emit_instruction(
+                        compile_reference(*callee_address),
+                        functions_context.instructions,
+                        Some(&mut mapping),
+                    )
+                };
+
+                // Like a call, the reference needs to be updated, if the
+                // function it refers to gets updated.
+                functions_context
+                    .call_instructions_by_callee
+                    .inner
+                    .entry(callee_location.clone())
+                    .or_default()
+                    .push(address);
+
+                address
+            } else {
+                emit_instruction(
+                    Instruction::TriggerEffect {
+                        effect: Effect::BuildError,
+                    },
+                    functions_context.instructions,
+                    Some(&mut mapping),
+                )
+            }
+        }
+        Expression::LiteralNumber { value } => {
+            let type_ = functions_context
+                .types
+                .signature_of_expression(&expression.location)
+                .and_then(|signature| signature.outputs.first());
+
+            emit_instruction(
+                Instruction::Push {
+                    value: encode_literal(*value, type_),
+                },
+                functions_context.instructions,
+                Some(&mut mapping),
+            )
+        }
         Expression::LocalFunction { function: _ } => {
-            if functions_context
+            let location = FunctionLocation::from(expression.location.clone());
+
+            // See `Type::Function` for the layout of a function value. First
+            // come the values that the function captures from its environment.
+            let environment = match compile_environment_slots(
+                &location,
+                slots,
+                functions_context.bindings,
+                functions_context.syntax_tree,
+                functions_context.types,
+            ) {
+                Some(environment) => environment
+                    .into_iter()
+                    .map(|index| Instruction::LoadSlot { index })
+                    .collect(),
+                None => vec![Instruction::TriggerEffect {
+                    effect: Effect::CompilerBug,
+                }],
+            };
+
+            let mut address = None;
+            for instruction in environment {
+                let addr = emit_instruction(
+                    instruction,
+                    functions_context.instructions,
+                    Some(&mut mapping),
+                );
+                address = address.or(Some(addr));
+            }
+
+            // Then comes the address of the function.
+            let function_address = if functions_context
                 .recursion
                 .is_recursive_expression(&expression.location)
             {
@@ -360,13 +748,11 @@ fn compile_expression(
                 );
                 cluster_context
                     .recursive_local_function_definitions_by_local_function
-                    .insert(expression.location.into(), address);
+                    .insert(location, DefinitionOfLocalFunction { address });
 
                 address
             } else {
-                let location = FunctionLocation::from(expression.location);
-
-                let Some(runtime_function) = functions_context
+                let Some(function_address) = functions_context
                     .compiled_functions_by_location
                     .get(&location)
                 else {
@@ -377,22 +763,14 @@ fn compile_expression(
                     )
                 };
 
-                let environment = functions_context
-                    .bindings
-                    .environment_of(&location)
-                    .bindings(functions_context.syntax_tree)
-                    .map(|binding| binding.name.clone())
-                    .collect();
-
                 emit_instruction(
-                    Instruction::MakeAnonymousFunction {
-                        branches: runtime_function.branches.clone(),
-                        environment,
-                    },
+                    compile_reference(*function_address),
                     functions_context.instructions,
                     Some(&mut mapping),
                 )
-            }
+            };
+
+            address.unwrap_or(function_address)
         }
     }
 }
@@ -400,7 +778,7 @@ fn compile_expression(
 pub fn compile_call_to_function(
     callee: &FunctionLocation,
     call: CallToFunction,
-    functions: &mut BTreeMap<FunctionLocation, crosscut_runtime::Function>,
+    functions: &mut BTreeMap<FunctionLocation, InstructionAddress>,
     instructions: &mut Instructions,
 ) {
     let callee = functions.get(callee).expect(
@@ -408,21 +786,92 @@ pub fn compile_call_to_function(
         have been compiled already.",
     );
 
-    instructions.replace(
-        &call.address,
-        Instruction::CallFunction {
-            callee: callee.clone(),
-            is_tail_call: call.is_tail_call,
+    instructions
+        .replace(&call.address, compile_call(*callee, call.is_tail_call));
+}
+
+/// # Compile a call to the function at the provided address
+///
+/// Tail calls reuse the stack frame of the calling function, all other calls
+/// need a new one.
+pub fn compile_call(
+    address: InstructionAddress,
+    is_tail_call: bool,
+) -> Instruction {
+    if is_tail_call {
+        Instruction::ReuseFrame { address }
+    } else {
+        Instruction::PushFrame { address }
+    }
+}
+
+pub fn compile_reference_to_function(
+    function: &FunctionLocation,
+    address: InstructionAddress,
+    functions: &mut BTreeMap<FunctionLocation, InstructionAddress>,
+    instructions: &mut Instructions,
+) {
+    let function = functions.get(function).expect(
+        "Attempting to compile reference to function. Expecting that function \
+        to have been compiled already.",
+    );
+
+    instructions.replace(&address, compile_reference(*function));
+}
+
+/// # Compile a reference to the function at the provided address
+///
+/// See `Type::Function` for the layout of a function value. This only covers
+/// the address. Any values the function captured must already be on the stack.
+pub fn compile_reference(address: InstructionAddress) -> Instruction {
+    Instruction::Push {
+        value: address.index.into(),
+    }
+}
+
+/// # Compile a host function, so it can be referred to as a value
+///
+/// Calls to a host function are compiled in place. But a reference to a host
+/// function needs an address that `Instruction::Eval` can jump to.
+pub fn compile_host_function(
+    function: &HostFunction,
+    instructions: &mut Instructions,
+) -> InstructionAddress {
+    let address = compile_call_to_host_function(function, instructions, None);
+    emit_instruction(Instruction::Return, instructions, None);
+
+    address
+}
+
+fn compile_call_to_host_function(
+    function: &HostFunction,
+    instructions: &mut Instructions,
+    mut mapping: Option<&mut Mapping>,
+) -> InstructionAddress {
+    let address = emit_instruction(
+        Instruction::Push {
+            value: function.number.into(),
         },
+        instructions,
+        mapping.as_deref_mut(),
     );
+    emit_instruction(
+        Instruction::TriggerEffect {
+            effect: Effect::Host,
+        },
+        instructions,
+        mapping,
+    );
+
+    address
 }
 
 pub fn compile_definition_of_local_function(
     local_function: FunctionLocation,
-    address: InstructionAddress,
+    definition: DefinitionOfLocalFunction,
     functions_context: &mut FunctionsContext,
 ) {
-    let Some(runtime_function) = functions_context
+    let Some(function_address) = functions_context
         .compiled_functions_by_location
         .get(&local_function)
     else {
@@ -432,72 +881,172 @@ pub fn compile_definition_of_local_function(
         )
     };
 
-    let environment = functions_context
-        .bindings
-        .environment_of(&local_function)
-        .bindings(functions_context.syntax_tree)
-        .map(|binding| binding.name.clone())
-        .collect();
-
-    functions_context.instructions.replace(
-        &address,
-        Instruction::MakeAnonymousFunction {
-            branches: runtime_function.branches.clone(),
-            environment,
-        },
-    );
+    functions_context
+        .instructions
+        .replace(&definition.address, compile_reference(*function_address));
 }
 
-fn compile_intrinsic(
-    intrinsic: &IntrinsicFunction,
-    is_tail_call: bool,
+fn compile_environment(
+    local_function: &FunctionLocation,
+    bindings: &Bindings,
+    syntax_tree: &SyntaxTree,
+    types: &Types,
+) -> Vec<String> {
+    bindings
+        .environment_of(local_function)
+        .bindings(syntax_tree)
+        .flat_map(|binding| {
+            binding_names(
+                &binding.name,
+                types.type_of_parameter(&binding.location),
+            )
+        })
+        .collect()
+}
+
+/// # Compute the slots that a local function captures its environment from
+///
+/// The slots are returned in the order that the values of the environment are
+/// laid out in a value of the function.
+///
+///
+/// Returns `None`, if any of the values in the environment don't have a slot
+/// in the branch that defines the local function. That would be a compiler
+/// bug.
+fn compile_environment_slots(
+    local_function: &FunctionLocation,
+    slots: &Slots,
+    bindings: &Bindings,
+    syntax_tree: &SyntaxTree,
+    types: &Types,
+) -> Option<Vec<u32>> {
+    compile_environment(local_function, bindings, syntax_tree, types)
+        .iter()
+        .map(|name| slots.get(name))
+        .collect()
+}
+
+/// # Compute the names that the values of a binding are bound to at runtime
+///
+/// A binding of a struct is made up of multiple values, one for each field.
+/// Each of those gets its own name, made up of the binding name and the field
+/// names, as they appear in a field access. Nested structs are flattened the
+/// same way.
+fn binding_names(name: &str, type_: Option<&Type>) -> Vec<String> {
+    match type_ {
+        Some(Type::Struct { fields, .. }) => fields
+            .iter()
+            .flat_map(|(field, type_)| {
+                binding_names(&format!("{name}.{field}"), Some(type_))
+            })
+            .collect(),
+        Some(type_ @ (Type::Enum { .. } | Type::Function { .. })) => {
+            // The values of an enum or a function can't be accessed
+            // individually, so the names don't need to mean anything. They
+            // just need to be unique, and numbers can't clash with field
+            // names.
+            (0..type_.num_values())
+                .map(|i| format!("{name}.{i}"))
+                .collect()
+        }
+        _ => vec![name.to_string()],
+    }
+}
+
+fn compile_constructor(
+    constructor: &Constructor,
+    type_: Option<&Type>,
+    syntax_tree: &SyntaxTree,
     instructions: &mut Instructions,
     mapping: &mut Mapping,
 ) -> InstructionAddress {
-    let instruction = match intrinsic {
-        IntrinsicFunction::AddS8 => Instruction::AddS8,
-        IntrinsicFunction::AddS32 => Instruction::AddS32,
-        IntrinsicFunction::AddU8 => Instruction::AddU8,
-        IntrinsicFunction::AddU8Wrap => Instruction::AddU8Wrap,
-        IntrinsicFunction::And => Instruction::LogicalAnd,
-        IntrinsicFunction::Brk => Instruction::TriggerEffect {
-            effect: Effect::Breakpoint,
-        },
-        IntrinsicFunction::Copy => {
-            let offset_from_top = Value::from(0);
-
-            let address = emit_instruction(
-                Instruction::Push {
-                    value: offset_from_top,
-                },
+    let (enum_, variant) = match constructor {
+        Constructor::Struct { .. } => {
+            // The values of a struct's fields are already on the stack, in the
+            // order the struct expects. Constructing it is purely a matter of
+            // the type system.
+            return emit_instruction(
+                Instruction::Nop,
                 instructions,
                 Some(mapping),
             );
-            emit_instruction(Instruction::Copy, instructions, Some(mapping));
-
-            return address;
         }
-        IntrinsicFunction::DivS32 => Instruction::DivS32,
-        IntrinsicFunction::DivU8 => Instruction::DivU8,
-        IntrinsicFunction::Drop => Instruction::Drop,
-        IntrinsicFunction::Eq => Instruction::Eq,
-        IntrinsicFunction::Eval => Instruction::Eval { is_tail_call },
-        IntrinsicFunction::GreaterS8 => Instruction::GreaterS8,
-        IntrinsicFunction::GreaterS32 => Instruction::GreaterS32,
-        IntrinsicFunction::GreaterU8 => Instruction::GreaterU8,
-        IntrinsicFunction::MulS32 => Instruction::MulS32,
-        IntrinsicFunction::MulU8Wrap => Instruction::MulU8Wrap,
-        IntrinsicFunction::NegS32 => Instruction::NegS32,
-        IntrinsicFunction::Nop => Instruction::Nop,
-        IntrinsicFunction::Not => Instruction::LogicalNot,
-        IntrinsicFunction::RemainderS32 => Instruction::RemainderS32,
-        IntrinsicFunction::S32ToS8 => Instruction::ConvertS32ToS8,
-        IntrinsicFunction::SubS32 => Instruction::SubS32,
-        IntrinsicFunction::SubU8 => Instruction::SubU8,
-        IntrinsicFunction::SubU8Wrap => Instruction::SubU8Wrap,
+        Constructor::Variant { enum_, variant } => (enum_, variant),
     };
 
-    emit_instruction(instruction, instructions, Some(mapping))
+    let variant = syntax_tree
+        .enums
+        .get(enum_)
+        .and_then(|enum_| enum_.variants.get(variant));
+    let Some((type_, (tag, payload))) =
+        type_.zip(variant).and_then(|(type_, variant)| {
+            Some((type_, type_.variant(&variant.name)?))
+        })
+    else {
+        return emit_instruction(
+            Instruction::TriggerEffect {
+                effect: Effect::BuildError,
+            },
+            instructions,
+            Some(mapping),
+        );
+    };
+
+    // The payload is already on the stack. What's left, is to pad it to the
+    // size of the enum, and add the tag. See `Type::Enum`.
+    let payload = payload.map(|payload| payload.num_values()).unwrap_or(0);
+    let padding = type_.num_values() - 1 - payload;
+
+    let mut address = None;
+    for value in iter::repeat_n(0, padding).chain([tag]) {
+        let addr = emit_instruction(
+            Instruction::Push {
+                value: value.into(),
+            },
+            instructions,
+            Some(mapping),
+        );
+        address = address.or(Some(addr));
+    }
+
+    address.expect("Pushed at least the tag.")
+}
+
+fn compile_intrinsic(
+    intrinsic: &IntrinsicFunction,
+    operand: Option<&Type>,
+    is_tail_call: bool,
+    instructions: &mut Instructions,
+    mapping: &mut Mapping,
+) -> InstructionAddress {
+    // `copy` and `drop` operate on whole values, which could be made up of
+    // multiple runtime values.
+    let num_values = operand.map(|type_| type_.num_values()).unwrap_or(1);
+
+    let mut address = None;
+    for instruction in intrinsic.instructions(num_values, is_tail_call) {
+        let addr = emit_instruction(instruction, instructions, Some(mapping));
+        address = address.or(Some(addr));
+    }
+
+    address.expect("Every intrinsic compiles to at least one instruction.")
+}
+
+/// # Encode the value of a literal, according to its type
+///
+/// Most types are represented the same way, but a signed 8-bit integer only
+/// occupies the lowest byte of a value, and an unsigned 32-bit integer can
+/// have values that don't fit into a signed one. If the type is not known, the
+/// literal is encoded as a signed 32-bit integer.
+///
+/// Type inference has already made sure that the type can represent the
+/// literal, so none of the conversions here can lose information.
+fn encode_literal(value: i64, type_: Option<&Type>) -> Value {
+    match type_ {
+        Some(Type::S8) => Value::from(value as i8),
+        Some(Type::U32) => Value::from(value as u32),
+        _ => Value::from(value as i32),
+    }
 }
 
 fn emit_instruction(
@@ -512,7 +1061,53 @@ fn emit_instruction(
     addr
 }
 
+/// # A pattern in a function argument
+///
+/// A parameter can be made up of multiple patterns, one for each of the values
+/// that its type is made up of at runtime.
+enum Pattern {
+    Identifier { name: String },
+    Literal { value: Value },
+}
+
+/// # The slots that the values of a branch's bindings are stored in
+///
+/// The values that a function captures from its environment come first, in
+/// the order that `compile_environment` returns them. That's where the
+/// function's prologue puts them. They are followed by the values of the
+/// branch's parameters.
+#[derive(Default)]
+struct Slots {
+    names: Vec<String>,
+}
+
+impl Slots {
+    fn insert(&mut self, name: String) -> u32 {
+        if let Some(index) = self.get(&name) {
+            return index;
+        }
+
+        self.names.push(name);
+        Self::index(self.names.len() - 1)
+    }
+
+    fn get(&self, name: &str) -> Option<u32> {
+        self.names
+            .iter()
+            .position(|slot| slot == name)
+            .map(Self::index)
+    }
+
+    fn index(index: usize) -> u32 {
+        u32::try_from(index).expect("Number of slots must fit into `u32`.")
+    }
+}
+
 pub struct CallToFunction {
     pub address: InstructionAddress,
     pub is_tail_call: bool,
 }
+
+pub struct DefinitionOfLocalFunction {
+    pub address: InstructionAddress,
+}
@@ -1,10 +1,10 @@
 use std::collections::BTreeMap;
 
-use crosscut_runtime::Instruction;
+use crosscut_runtime::{Instruction, InstructionAddress};
 
 use crate::{
     code::{
-        syntax::{FunctionLocation, SyntaxTree},
+        syntax::{Expression, FunctionLocation, SyntaxTree},
         Bindings, Changes, Dependencies, FunctionCalls, Functions, Recursion,
         TailExpressions, Types,
     },
@@ -13,7 +13,12 @@ use crate::{
     Instructions,
 };
 
-use super::compile_cluster::compile_cluster;
+use super::{
+    compile_cluster::compile_cluster,
+    compile_function::{
+        compile_call, compile_host_function, compile_reference,
+    },
+};
 
 pub struct FunctionsContext<'r> {
     pub syntax_tree: &'r SyntaxTree,
@@ -22,11 +27,23 @@ pub struct FunctionsContext<'r> {
     pub function_calls: &'r FunctionCalls,
     pub tail_expressions: &'r TailExpressions,
     pub recursion: &'r Recursion,
+    pub types: &'r Types,
     pub instructions: &'r mut Instructions,
     pub source_map: &'r mut SourceMap,
     pub call_instructions_by_callee: &'r mut CallInstructionsByCallee,
     pub compiled_functions_by_location:
-        &'r mut BTreeMap<FunctionLocation, crosscut_runtime::Function>,
+        &'r mut BTreeMap<FunctionLocation, InstructionAddress>,
+
+    /// # Previously compiled versions of functions that contain errors
+    ///
+    /// These functions are not compiled. Calls to them go to the previous
+    /// version instead.
+    pub fallbacks: &'r BTreeMap<FunctionLocation, InstructionAddress>,
+
+    /// # The addresses of the host functions that are referred to as values
+    ///
+    /// Keyed by the number of the host function.
+    pub host_functions: BTreeMap<u8, InstructionAddress>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -38,16 +55,20 @@ pub fn compile_functions(
     bindings: &Bindings,
     function_calls: &FunctionCalls,
     tail_expressions: &TailExpressions,
-    _: &Types,
+    types: &Types,
     recursion: &Recursion,
+    fallbacks: &BTreeMap<FunctionLocation, InstructionAddress>,
     instructions: &mut Instructions,
     source_map: &mut SourceMap,
     call_instructions_by_callee: &mut CallInstructionsByCallee,
     compiled_functions_by_location: &mut BTreeMap<
         FunctionLocation,
-        crosscut_runtime::Function,
+        InstructionAddress,
     >,
 ) {
+    let host_functions =
+        compile_host_functions(syntax_tree, function_calls, instructions);
+
     let mut context = FunctionsContext {
         syntax_tree,
         functions,
@@ -55,10 +76,13 @@ pub fn compile_functions(
         function_calls,
         tail_expressions,
         recursion,
+        types,
         instructions,
         source_map,
         call_instructions_by_callee,
         compiled_functions_by_location,
+        fallbacks,
+        host_functions,
     };
 
     for cluster in dependencies.clusters() {
@@ -66,26 +90,18 @@ pub fn compile_functions(
     }
 
     for update in &changes.updated {
-        for calling_address in context
+        let calling_addresses = context
             .call_instructions_by_callee
             .inner
             .remove(&update.old.location)
-            .unwrap_or_default()
-        {
+            .unwrap_or_default();
+
+        for &calling_address in &calling_addresses {
             let calling_instruction = context
                 .instructions
                 .get(&calling_address)
                 .expect("Instruction referenced from source map must exist.");
-            let Instruction::CallFunction { is_tail_call, .. } =
-                calling_instruction
-            else {
-                panic!(
-                    "Calling instruction referenced from source map is not a \
-                    function call."
-                );
-            };
-
-            let function = context
+            let address = context
                 .compiled_functions_by_location
                 .get(&update.new.location)
                 .expect(
@@ -93,13 +109,60 @@ pub fn compile_functions(
                     compiled; is expected to exist.",
                 );
 
-            context.instructions.replace(
-                &calling_address,
-                Instruction::CallFunction {
-                    callee: function.clone(),
-                    is_tail_call: *is_tail_call,
-                },
-            );
+            let instruction = match calling_instruction {
+                Instruction::PushFrame { .. } => compile_call(*address, false),
+                Instruction::ReuseFrame { .. } => compile_call(*address, true),
+                Instruction::Push { .. } => compile_reference(*address),
+                _ => {
+                    panic!(
+                        "Calling instruction referenced from source map is \
+                        neither a function call nor a reference."
+                    );
+                }
+            };
+
+            context.instructions.replace(&calling_address, instruction);
+        }
+
+        // The function might get updated again. Or, if the new version
+        // contains errors, the calls still go to the previous version, and
+        // need to be updated once those errors are fixed.
+        context
+            .call_instructions_by_callee
+            .inner
+            .entry(update.new.location.clone())
+            .or_default()
+            .extend(calling_addresses);
+    }
+}
+
+/// # Compile all host functions that are referred to as values
+fn compile_host_functions(
+    syntax_tree: &SyntaxTree,
+    function_calls: &FunctionCalls,
+    instructions: &mut Instructions,
+) -> BTreeMap<u8, InstructionAddress> {
+    let mut host_functions = BTreeMap::new();
+
+    for function in syntax_tree.all_functions() {
+        for branch in function.branches() {
+            for expression in branch.expressions() {
+                let Expression::FunctionReference { .. } = expression.fragment
+                else {
+                    continue;
+                };
+                let Some(host_function) = function_calls
+                    .is_call_to_host_function(&expression.location)
+                else {
+                    continue;
+                };
+
+                host_functions.entry(host_function.number).or_insert_with(
+                    || compile_host_function(host_function, instructions),
+                );
+            }
         }
     }
+
+    host_functions
 }
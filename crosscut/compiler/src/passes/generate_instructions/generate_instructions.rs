@@ -4,7 +4,7 @@ use crosscut_runtime::{Effect, Instruction, InstructionAddress};
 
 use crate::{
     code::{
-        syntax::{FunctionLocation, SyntaxTree},
+        syntax::{FunctionLocation, Module, SyntaxTree},
         Bindings, Changes, Dependencies, FunctionCalls, Functions, Recursion,
         TailExpressions, Types,
     },
@@ -29,11 +29,12 @@ pub fn generate_instructions(
     types: &Types,
     recursion: &Recursion,
     changes: &Changes,
+    fallbacks: &BTreeMap<FunctionLocation, InstructionAddress>,
     instructions: &mut Instructions,
     call_instructions_by_callee: &mut CallInstructionsByCallee,
     compiled_functions_by_location: &mut BTreeMap<
         FunctionLocation,
-        crosscut_runtime::Function,
+        InstructionAddress,
     >,
     source_map: &mut SourceMap,
 ) {
@@ -60,6 +61,7 @@ pub fn generate_instructions(
         tail_expressions,
         types,
         recursion,
+        fallbacks,
         instructions,
         source_map,
         call_instructions_by_callee,
@@ -91,10 +93,12 @@ fn compile_call_to_main(
     instructions: &mut Instructions,
     compiled_functions_by_location: &mut BTreeMap<
         FunctionLocation,
-        crosscut_runtime::Function,
+        InstructionAddress,
     >,
 ) {
-    let Some(main) = syntax_tree.function_by_name("main") else {
+    let Some(main) =
+        syntax_tree.function_by_name_in_module("main", &Module::main_index())
+    else {
         // If we can't find the call to `main`, that is a result of invalid
         // code. Leaving the placeholder instruction is appropriate in that
         // case.
@@ -1,7 +1,12 @@
+mod check_patterns;
 mod detect_changes;
+mod find_erroneous_functions;
 mod generate_instructions;
+mod report_syntax_errors;
 
 pub use {
-    detect_changes::detect_changes,
+    check_patterns::check_patterns, detect_changes::detect_changes,
+    find_erroneous_functions::find_erroneous_functions,
     generate_instructions::generate_instructions,
+    report_syntax_errors::report_syntax_errors,
 };
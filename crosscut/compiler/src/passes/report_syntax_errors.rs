@@ -0,0 +1,79 @@
+use crate::{
+    code::{
+        syntax::{Member, Parameter, SyntaxError, SyntaxTree},
+        Span, Token,
+    },
+    diagnostics::{Diagnostic, Diagnostics},
+};
+
+pub fn report_syntax_errors(
+    syntax_tree: &SyntaxTree,
+    diagnostics: &mut Diagnostics,
+) {
+    for (index, error) in syntax_tree.errors.iter().enumerate() {
+        let span = syntax_tree.spans.of_error(index).copied();
+        diagnostics.push(syntax_error_to_diagnostic(error, span));
+    }
+
+    for function in syntax_tree.all_functions() {
+        for branch in function.branches() {
+            for parameter in branch.parameters() {
+                if let Parameter::Error(error) = parameter.fragment {
+                    let span = syntax_tree
+                        .spans
+                        .of_parameter(&parameter.location)
+                        .copied();
+                    let mut diagnostic =
+                        syntax_error_to_diagnostic(error, span);
+
+                    if let Some(struct_) = destructured_struct(error) {
+                        if syntax_tree.struct_by_name(struct_).is_some() {
+                            diagnostic = diagnostic.with_note(format!(
+                                "Structs can't be destructured in branch \
+                                parameters. Bind the struct to a name \
+                                instead, like `value: {struct_}`, then access \
+                                its fields through that name, like \
+                                `value.field`."
+                            ));
+                        }
+                    }
+
+                    diagnostics.push(diagnostic);
+                }
+            }
+
+            for member in branch.body() {
+                if let Member::Error(error) = member.fragment {
+                    let span =
+                        syntax_tree.spans.of_member(&member.location).copied();
+                    diagnostics.push(syntax_error_to_diagnostic(error, span));
+                }
+            }
+        }
+    }
+}
+
+fn syntax_error_to_diagnostic(
+    error: &SyntaxError,
+    span: Option<Span>,
+) -> Diagnostic {
+    let message = match &error.unexpected {
+        Some(token) => format!("Syntax error: unexpected `{token}`"),
+        None => "Syntax error: unexpected end of input".to_string(),
+    };
+
+    Diagnostic::error(message, span)
+}
+
+/// # Check, if a parameter error looks like destructuring a struct
+///
+/// Returns the name that might be a struct, if the parameter started with two
+/// identifiers, like `Pair a b`.
+fn destructured_struct(error: &SyntaxError) -> Option<&str> {
+    match error.tokens.as_slice() {
+        [Token::Identifier { name }, Token::Identifier { .. }, ..] => {
+            Some(name)
+        }
+        _ => None,
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 
 use crosscut_runtime::InstructionAddress;
 
-use crate::code::syntax::{FunctionLocation, MemberLocation};
+use crate::code::syntax::{BranchLocation, FunctionLocation, MemberLocation};
 
 /// # Mapping of pre-compiled source code to fully compiled instructions
 #[derive(
@@ -14,6 +14,7 @@ pub struct SourceMap {
     instruction_to_expression: BTreeMap<InstructionAddress, MemberLocation>,
     function_to_instructions:
         BTreeMap<FunctionLocation, [InstructionAddress; 2]>,
+    branch_to_slots: BTreeMap<BranchLocation, Vec<String>>,
 }
 
 impl SourceMap {
@@ -24,7 +25,7 @@ impl SourceMap {
     pub fn map_expression_to_instructions(
         &mut self,
         expression: MemberLocation,
-    ) -> Mapping {
+    ) -> Mapping<'_> {
         // Make sure we don't have a previous mapping whose leftovers might
         // corrupt the new one.
         self.expression_to_instructions.remove(&expression);
@@ -44,6 +45,19 @@ impl SourceMap {
         self.function_to_instructions.insert(function, range);
     }
 
+    /// # Define the names of the bindings that a branch stores in its slots
+    ///
+    /// The names are in the order of the slots. A binding whose value consists
+    /// of multiple runtime values occupies multiple slots, and has one name for
+    /// each of them.
+    pub fn map_branch_to_slots(
+        &mut self,
+        branch: BranchLocation,
+        names: Vec<String>,
+    ) {
+        self.branch_to_slots.insert(branch, names);
+    }
+
     /// # Get the location of the expression that the given instruction maps to
     ///
     /// Can return `None`, as there are a few compiler-generated instructions
@@ -90,6 +104,16 @@ impl SourceMap {
             },
         )
     }
+
+    /// # Access the names of the bindings that a branch stores in its slots
+    ///
+    /// See [`SourceMap::map_branch_to_slots`].
+    pub fn slots_of_branch(&self, branch: &BranchLocation) -> &[String] {
+        self.branch_to_slots
+            .get(branch)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
 }
 
 /// # A mapping of an expression to a number of instructions
@@ -0,0 +1,202 @@
+//! # The source code that the compiler takes as input
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    code::{
+        syntax::{Module, SyntaxTree},
+        Index, Tokens,
+    },
+    host::Host,
+    standard_library,
+};
+
+/// # The source code of a game
+///
+/// A game consists of one or more source files, each of which is a module.
+/// The first source file is always the main module. It is followed by the
+/// modules of the [standard library](crate::standard_library).
+#[derive(Clone, Debug)]
+pub struct Sources {
+    files: BTreeMap<Index<Module>, SourceFile>,
+}
+
+impl Sources {
+    /// # Create an instance of `Sources`, starting with the main module
+    ///
+    /// The modules of the standard library are added automatically. Unless the
+    /// main module is itself one of them, in which case it replaces that.
+    pub fn new(main: SourceFile) -> Self {
+        let mut sources = Self {
+            files: BTreeMap::new(),
+        };
+        sources.push(main);
+        for file in standard_library::source_files() {
+            if !sources.contains(&file.module) {
+                sources.push(file);
+            }
+        }
+        sources
+    }
+
+    /// # Add a source file
+    ///
+    /// Returns the index of the module that the source file is going to
+    /// become.
+    pub fn push(&mut self, file: SourceFile) -> Index<Module> {
+        let index = Index::from(
+            u32::try_from(self.files.len())
+                .expect("Number of source files should fit into a `u32`."),
+        );
+        self.files.insert(index, file);
+        index
+    }
+
+    /// # Access the source file of the module with the provided index
+    pub fn get(&self, module: &Index<Module>) -> Option<&SourceFile> {
+        self.files.get(module)
+    }
+
+    /// # Mutably access the source file of the module with the provided index
+    pub fn get_mut(
+        &mut self,
+        module: &Index<Module>,
+    ) -> Option<&mut SourceFile> {
+        self.files.get_mut(module)
+    }
+
+    /// # Determine, if a source file for the module with this name exists
+    pub fn contains(&self, module: &str) -> bool {
+        self.files.values().any(|file| file.module == module)
+    }
+
+    /// # Remove the code of library modules that the host doesn't support
+    ///
+    /// The modules themselves are kept, so the index of each module stays the
+    /// same. Any call to a function of a removed module is going to be
+    /// reported as an unresolved identifier.
+    pub fn supported_by(&self, host: &impl Host) -> Self {
+        let mut sources = self.clone();
+
+        for file in sources.files.values_mut() {
+            if standard_library::contains(&file.module)
+                && !standard_library::is_supported_by(&file.module, host)
+            {
+                file.code.clear();
+            }
+        }
+
+        sources
+    }
+
+    /// # Load the modules that the source files import, directly or indirectly
+    ///
+    /// Modules are looked for in the directories of the provided search path,
+    /// in order. See [`search_path`]. The compiler doesn't access the file
+    /// system itself, so the caller provides `read`. It is called with the path
+    /// of each file that could contain a module, and returns the code of that
+    /// file, or `None`, if it doesn't exist.
+    ///
+    /// Modules that can't be found are skipped. The compiler is going to report
+    /// them, once it compiles the sources.
+    ///
+    /// Returns the paths of all files that were looked at, whether they exist
+    /// or not. Creating any of the missing ones could change which modules are
+    /// loaded.
+    pub fn load_imports<E>(
+        &mut self,
+        search_path: &[PathBuf],
+        mut read: impl FnMut(&Path) -> Result<Option<String>, E>,
+    ) -> Result<Vec<PathBuf>, E> {
+        let mut paths = Vec::new();
+        let mut imports = self
+            .files
+            .values()
+            .flat_map(|file| file.imports())
+            .collect::<VecDeque<_>>();
+
+        while let Some(module) = imports.pop_front() {
+            if self.contains(&module) {
+                continue;
+            }
+
+            for dir in search_path {
+                let path = dir.join(module_file_name(&module));
+                paths.push(path.clone());
+
+                if let Some(code) = read(&path)? {
+                    let file = SourceFile { module, path, code };
+                    imports.extend(file.imports());
+                    self.push(file);
+                    break;
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// # Iterate over all source files, in order
+    pub fn iter(&self) -> impl Iterator<Item = (Index<Module>, &SourceFile)> {
+        self.files.iter().map(|(&index, file)| (index, file))
+    }
+}
+
+/// # A source file
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    /// # The name of the module that this source file defines
+    pub module: String,
+
+    /// # The path that the source file was loaded from
+    ///
+    /// This is only used to refer to the source file in messages to the
+    /// developer.
+    pub path: PathBuf,
+
+    /// # The source code
+    pub code: String,
+}
+
+impl SourceFile {
+    /// # Create a source file for the main module
+    pub fn main(path: impl Into<PathBuf>, code: impl Into<String>) -> Self {
+        Self {
+            module: Module::MAIN.to_string(),
+            path: path.into(),
+            code: code.into(),
+        }
+    }
+
+    /// # Find the names of the modules that this source file imports
+    ///
+    /// This can be used to determine which other source files need to be
+    /// loaded, before the code can be compiled.
+    pub fn imports(&self) -> Vec<String> {
+        let syntax_tree = SyntaxTree::parse(Tokens::tokenize(&self.code));
+
+        syntax_tree
+            .modules
+            .into_values()
+            .flat_map(|module| module.imports)
+            .collect()
+    }
+}
+
+/// # The directories that the modules of a game are looked for in
+///
+/// That's the game directory first, then its parent directory, where modules
+/// that are shared between games can live.
+pub fn search_path(game_dir: &Path) -> Vec<PathBuf> {
+    let mut search_path = vec![game_dir.to_path_buf()];
+    search_path.extend(game_dir.parent().map(Path::to_path_buf));
+    search_path
+}
+
+/// # The name of the file that contains the module with the provided name
+pub fn module_file_name(module: &str) -> String {
+    format!("{module}.capi")
+}
@@ -0,0 +1,108 @@
+//! # The standard library
+//!
+//! A collection of modules, written in Crosscut, that are available to all
+//! games without having to be imported.
+
+use std::path::{Path, PathBuf};
+
+use crate::{host::Host, sources::SourceFile};
+
+/// # The modules of the standard library
+///
+/// ## Implementation Note
+///
+/// The source code is included in the compiler, so the standard library is
+/// available regardless of where the compiler runs. Tools that want to support
+/// changes to the standard library, while a game is running, can load the
+/// source code from [`SourceFile::path`] instead.
+const MODULES: &[LibraryModule] = &[
+    LibraryModule {
+        name: "cmp",
+        host_functions: &[],
+        code: include_str!("../std/cmp.capi"),
+    },
+    LibraryModule {
+        name: "math",
+        host_functions: &[],
+        code: include_str!("../std/math.capi"),
+    },
+    LibraryModule {
+        name: "vec",
+        host_functions: &["load", "store"],
+        code: include_str!("../std/vec.capi"),
+    },
+    LibraryModule {
+        name: "vec_buf",
+        host_functions: &["load", "store"],
+        code: include_str!("../std/vec_buf.capi"),
+    },
+];
+
+/// # Determine, if the module with the provided name is part of the library
+pub fn contains(module: &str) -> bool {
+    MODULES
+        .iter()
+        .any(|library_module| library_module.name == module)
+}
+
+/// # Determine, if the provided host supports a module of the library
+///
+/// Some modules call host functions. Those are only supported by hosts that
+/// provide these functions.
+///
+/// Returns `false`, if the module is not part of the library.
+pub fn is_supported_by(module: &str, host: &impl Host) -> bool {
+    MODULES
+        .iter()
+        .find(|library_module| library_module.name == module)
+        .is_some_and(|library_module| {
+            library_module
+                .host_functions
+                .iter()
+                .all(|name| host.function_by_name(name).is_some())
+        })
+}
+
+/// # Access the source files of the standard library
+///
+/// The path of each source file points to where it is located within the
+/// Crosscut repository.
+pub fn source_files() -> impl Iterator<Item = SourceFile> {
+    MODULES.iter().map(|library_module| SourceFile {
+        module: library_module.name.to_string(),
+        path: path(library_module.name),
+        code: library_module.code.to_string(),
+    })
+}
+
+struct LibraryModule {
+    name: &'static str,
+    host_functions: &'static [&'static str],
+    code: &'static str,
+}
+
+fn path(module: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("std")
+        .join(format!("{module}.capi"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::format_source;
+
+    use super::source_files;
+
+    #[test]
+    fn standard_library_is_formatted() {
+        for file in source_files() {
+            let formatted = format_source(&file.code).unwrap();
+            assert_eq!(
+                formatted,
+                file.code,
+                "`{}` is not formatted.",
+                file.path.display(),
+            );
+        }
+    }
+}
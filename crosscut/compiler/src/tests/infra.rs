@@ -1,9 +1,10 @@
-use crosscut_runtime::{Effect, Heap, Runtime};
+use crosscut_runtime::{Effect, Runtime, RuntimeState, StackLimits, Value};
 
 use crate::{
     code::Type,
     host::{Host, HostFunction},
-    Compiler, Instructions,
+    sources::Sources,
+    Compiler, CompilerOutput,
 };
 
 pub fn runtime() -> TestRuntime {
@@ -14,27 +15,59 @@ pub fn runtime() -> TestRuntime {
 pub struct TestRuntime {
     compiler: Compiler,
     runtime: Runtime,
-    instructions: Option<Instructions>,
-    heap: Heap,
+    code: Option<CompilerOutput>,
 }
 
 impl TestRuntime {
+    pub fn with_stack_limits(&mut self, limits: StackLimits) -> &mut Self {
+        self.runtime = Runtime::with_stack_limits(limits);
+        self
+    }
+
     pub fn update_code(&mut self, source: &str) -> &mut Self {
         let output = self.compiler.compile(source, &TestHost {});
-        self.instructions = Some(output.instructions);
+        self.code = Some(output);
         self
     }
 
-    pub fn run_until_effect(&mut self) -> Option<Effect> {
-        let instructions = self
-            .instructions
+    pub fn update_sources(&mut self, sources: &Sources) -> &mut Self {
+        let output = self.compiler.compile_sources(sources, &TestHost {});
+        self.code = Some(output);
+        self
+    }
+
+    pub fn call_chain(&self) -> Vec<String> {
+        let code = self
+            .code
             .as_ref()
             .expect("Must call `update_code` before running.");
 
+        code.call_chain(self.runtime.evaluator().active_instructions())
+    }
+
+    pub fn run_with_budget(&mut self, budget: &mut u32) -> RuntimeState {
+        let instructions = &self
+            .code
+            .as_ref()
+            .expect("Must call `update_code` before running.")
+            .instructions;
+
+        self.runtime
+            .run(instructions.to_runtime_instructions(), budget);
+
+        self.runtime.state()
+    }
+
+    pub fn run_until_effect(&mut self) -> Option<Effect> {
+        let instructions = &self
+            .code
+            .as_ref()
+            .expect("Must call `update_code` before running.")
+            .instructions;
+
         while self.runtime.state().is_running() {
             self.runtime.evaluate_next_instruction(
                 instructions.to_runtime_instructions(),
-                &mut self.heap,
             );
 
             if let Some(effect) = self.runtime.effect_mut().handle() {
@@ -45,6 +78,21 @@ impl TestRuntime {
         None
     }
 
+    pub fn run_until_finished(&mut self) -> Vec<Value> {
+        if let Some(effect) = self.run_until_effect() {
+            panic!(
+                "Waited for the process to finish, but it triggered an effect \
+                instead: {effect}"
+            );
+        }
+
+        let Some(return_values) = self.runtime.return_values() else {
+            panic!("Expected the process to have finished.");
+        };
+
+        return_values.collect()
+    }
+
     pub fn run_until_receiving(&mut self, expected_channel: u32) -> &mut Self {
         let Some(effect) = self.run_until_effect() else {
             panic!(
@@ -72,7 +120,8 @@ impl TestRuntime {
                 }
             }
             effect => {
-                let instructions = self.instructions.as_ref();
+                let instructions =
+                    self.code.as_ref().map(|code| &code.instructions);
 
                 panic!(
                     "Unexpected effect: {effect}\n\
@@ -88,14 +137,14 @@ impl TestRuntime {
 }
 
 #[derive(Debug)]
-struct TestHost {}
+pub struct TestHost {}
 
 impl Host for TestHost {
     fn functions(&self) -> impl IntoIterator<Item = HostFunction> {
         [HostFunction {
             name: "send".into(),
             number: 0,
-            signature: ([Type::Number], []).into(),
+            signature: ([Type::U32], []).into(),
         }]
     }
 }
@@ -1,3 +1,5 @@
+use crosscut_runtime::Effect;
+
 use crate::tests::infra::runtime;
 
 #[test]
@@ -247,3 +249,76 @@ fn compile_call_to_function_that_has_not_been_updated() {
         )
         .run_until_receiving(1);
 }
+
+#[test]
+fn keep_running_previous_version_on_syntax_error() {
+    // A syntax error should not replace the code that is already running. The
+    // previous version of the function keeps running, until the error is
+    // fixed.
+
+    let mut runtime = runtime();
+
+    runtime
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        0 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(0);
+
+    runtime
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 send
+                        ->
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(0)
+        .run_until_receiving(0);
+
+    runtime
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1);
+}
+
+#[test]
+fn trigger_build_error_on_syntax_error_without_previous_version() {
+    // If there's no previous version to fall back to, a syntax error should
+    // not prevent the rest of the code from being compiled. The error itself
+    // should trigger an effect, once it is reached.
+
+    let mut runtime = runtime();
+
+    runtime.update_code(
+        r"
+            main: fn
+                br ->
+                    0 send
+                    ->
+                end
+            end
+        ",
+    );
+
+    runtime.run_until_receiving(0);
+    assert_eq!(runtime.run_until_effect(), Some(Effect::BuildError));
+}
@@ -0,0 +1,447 @@
+use crate::{host::NoHost, tests::infra::TestHost, Compiler};
+
+#[test]
+fn report_type_error_instead_of_panicking() {
+    // A type error should be reported as a diagnostic, pointing to the
+    // expression where it was detected.
+
+    let source = r"
+        main: fn
+            br ->
+                1 eval
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert!(error.message.starts_with("Type error"));
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "eval");
+}
+
+#[test]
+fn report_unresolved_identifier() {
+    // An identifier that can't be resolved should be reported.
+
+    let output = Compiler::default().compile(
+        r"
+            main: fn
+                br ->
+                    unknown
+                end
+            end
+        ",
+        &NoHost,
+    );
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Unresolved identifier `unknown`");
+}
+
+#[test]
+fn report_mismatched_integer_types() {
+    // Integer types are distinct from each other. Passing an `S32` to a
+    // function that expects a `U8` is an error.
+
+    let source = r"
+        main: fn
+            br ->
+                1 neg_s32 1 add_u8
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Type error: expected `U8`, got `S32`");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "add_u8");
+}
+
+#[test]
+fn report_integer_literal_out_of_range() {
+    // An integer literal can have any integer type that can represent its
+    // value.
+
+    let source = r"
+        main: fn
+            br ->
+                256 1 add_u8
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        error.message,
+        "Type error: expected `U8`, got integer literal",
+    );
+}
+
+#[test]
+fn report_host_function_call_with_wrong_argument_type() {
+    // Calls to host functions are checked against the signature that the host
+    // declares for them.
+
+    let source = r"
+        main: fn
+            br ->
+                1 neg_s32 send
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &TestHost {});
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        error.message,
+        "Type error in call to host function `send`: expected `U32`, got \
+        `S32`",
+    );
+    assert_eq!(error.notes, ["`send` has the signature `U32 ->`"]);
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "send");
+}
+
+#[test]
+fn report_host_function_call_with_missing_argument() {
+    // Calling a host function without providing all of its arguments is an
+    // error.
+
+    let output = Compiler::default().compile(
+        r"
+            main: fn
+                br ->
+                    send
+                end
+            end
+        ",
+        &TestHost {},
+    );
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        error.message,
+        "Type error in call to host function `send`: expected `U32`, got \
+        nothing",
+    );
+}
+
+#[test]
+fn report_access_to_field_that_does_not_exist() {
+    // Accessing a field that the struct doesn't have is a type error.
+
+    let source = r"
+        Pair: struct
+            a: S32
+            b: S32
+        end
+
+        main: fn
+            br pair: Pair ->
+                pair.c
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        error.message,
+        "Type error: expected struct with field `c`, got `Pair`",
+    );
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "pair.c");
+}
+
+#[test]
+fn report_attempt_to_destructure_struct_in_parameter() {
+    // Structs can't be destructured in branch parameters. The diagnostic should
+    // point out the alternative.
+
+    let source = r"
+        Pair: struct
+            a: S32
+            b: S32
+        end
+
+        main: fn
+            br Pair a b ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Syntax error: unexpected `a`");
+    assert_eq!(
+        error.notes,
+        [
+            "Structs can't be destructured in branch parameters. Bind the \
+            struct to a name instead, like `value: Pair`, then access its \
+            fields through that name, like `value.field`."
+        ],
+    );
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "Pair a b");
+}
+
+#[test]
+fn report_branches_that_do_not_cover_all_variants() {
+    // If a function matches on the variants of an enum, its branches need to
+    // cover all of them.
+
+    let source = r"
+        Direction: enum
+            Up
+            Down
+            Left
+        end
+
+        main: fn
+            br Direction.Up, _ ->
+            end
+
+            br Direction.Down, 0 ->
+            end
+
+            br Direction.Left, _ ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Branches don't cover all possible arguments");
+    assert_eq!(error.notes, ["No branch matches `Direction.Down, 1`."]);
+}
+
+#[test]
+fn warn_about_branches_that_do_not_cover_all_arguments() {
+    // If a function's branches don't cover all possible arguments, that should
+    // be reported, along with an example of an argument that isn't covered.
+
+    let source = r"
+        main: fn
+            br 0 ->
+            end
+
+            br 1 ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [warning] = output.diagnostics.warnings().collect::<Vec<_>>()[..]
+    else {
+        panic!("Expected exactly one warning: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        warning.message,
+        "Branches don't cover all possible arguments"
+    );
+    assert_eq!(warning.notes, ["No branch matches `2`."]);
+}
+
+#[test]
+fn warn_about_unreachable_branch() {
+    // A branch that only matches arguments that previous branches already
+    // match, is never going to be executed.
+
+    let source = r"
+        main: fn
+            br _, 0 ->
+            end
+
+            br _, _ ->
+            end
+
+            br 1, _ ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [warning] = output.diagnostics.warnings().collect::<Vec<_>>()[..]
+    else {
+        panic!("Expected exactly one warning: {:#?}", output.diagnostics);
+    };
+    assert_eq!(warning.message, "Branch is unreachable");
+
+    let span = warning.span.unwrap();
+    assert!(source[span.start.byte..span.end.byte].starts_with("br 1, _"));
+}
+
+#[test]
+fn report_reference_to_intrinsic_function() {
+    // Intrinsic functions are compiled in place, so there's nothing to refer
+    // to.
+
+    let output = Compiler::default().compile(
+        r"
+            main: fn
+                br ->
+                    &add_s32
+                    drop
+                end
+            end
+        ",
+        &NoHost,
+    );
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Can't refer to `add_s32` as a value");
+}
+
+#[test]
+fn report_unknown_type() {
+    // A type annotation that refers to a type that doesn't exist should be
+    // reported, instead of stopping the compiler.
+
+    let source = r"
+        main: fn
+            br ->
+                0 f
+            end
+        end
+
+        f: fn
+            br x: S ->
+                x
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Unknown type `S`");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "x: S");
+}
+
+#[test]
+fn report_struct_that_contains_itself() {
+    // A struct that contains itself would take up an infinite amount of space.
+    // That should be reported at the field that causes it.
+
+    let source = r"
+        A: struct
+            a: A
+        end
+
+        main: fn
+            br ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Struct `A` contains itself");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "a: A");
+}
+
+#[test]
+fn report_struct_that_contains_itself_through_enum() {
+    // The same goes for a struct that contains itself indirectly. The error
+    // points at the place where the cycle closes.
+
+    let source = r"
+        List: enum
+            Empty
+            Node: Node
+        end
+
+        Node: struct
+            value: S32
+            next: List
+        end
+
+        main: fn
+            br ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Struct `Node` contains itself");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "Node: Node");
+}
+
+#[test]
+fn report_enum_that_contains_itself() {
+    // Enums are subject to the same limitation as structs.
+
+    let source = r"
+        List: enum
+            Empty
+            Node: List
+        end
+
+        main: fn
+            br ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Enum `List` contains itself");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "Node: List");
+}
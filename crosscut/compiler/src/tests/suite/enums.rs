@@ -0,0 +1,134 @@
+use crate::tests::infra::runtime;
+
+#[test]
+fn select_branch_by_variant() {
+    // A function can select the branch to execute, based on the variant of an
+    // enum that it's called with.
+
+    runtime()
+        .update_code(
+            r"
+                Direction: enum
+                    Up
+                    Down
+                end
+
+                main: fn
+                    br ->
+                        Direction.Down
+                        to_channel
+                        send
+                        Direction.Up
+                        to_channel
+                        send
+                        main
+                    end
+                end
+
+                to_channel: fn
+                    br Direction.Up ->
+                        1
+                    end
+
+                    br Direction.Down ->
+                        2
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(2)
+        .run_until_receiving(1);
+}
+
+#[test]
+fn bind_payload_of_variant() {
+    // The payload of a variant can be bound to a name. Variants with smaller
+    // payloads, or none at all, take up the same space as the largest one.
+
+    runtime()
+        .update_code(
+            r"
+                Pair: struct
+                    a: U32
+                    b: U32
+                end
+
+                Value: enum
+                    Nothing
+                    One: U32
+                    Two: Pair
+                end
+
+                main: fn
+                    br ->
+                        Value.Nothing
+                        send_value
+                        1 Value.One
+                        send_value
+                        2 3 Pair Value.Two
+                        send_value
+                        main
+                    end
+                end
+
+                send_value: fn
+                    br Value.Nothing ->
+                        0 send
+                    end
+
+                    br Value.One value ->
+                        value send
+                    end
+
+                    br Value.Two pair ->
+                        pair.a send
+                        pair.b send
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(0)
+        .run_until_receiving(1)
+        .run_until_receiving(2)
+        .run_until_receiving(3);
+}
+
+#[test]
+fn copy_and_drop_enum() {
+    // An enum with a payload is made up of multiple values. Copying and
+    // dropping it must take all of them into account.
+
+    runtime()
+        .update_code(
+            r"
+                Value: enum
+                    Nothing
+                    One: U32
+                end
+
+                main: fn
+                    br ->
+                        1 Value.One
+                        Value.Nothing
+                        drop
+                        copy
+                        send_value
+                        send_value
+                        main
+                    end
+                end
+
+                send_value: fn
+                    br Value.One value ->
+                        value send
+                    end
+
+                    br Value.Nothing ->
+                        0 send
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1)
+        .run_until_receiving(1);
+}
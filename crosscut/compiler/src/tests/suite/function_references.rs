@@ -0,0 +1,85 @@
+use crate::tests::infra::runtime;
+
+#[test]
+fn evaluate_reference_to_named_function() {
+    // A named function can be passed to another function, which can evaluate
+    // it as often as it likes.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        &f twice
+                    end
+                end
+
+                twice: fn
+                    br g ->
+                        g eval
+                        g eval
+                    end
+                end
+
+                f: fn
+                    br ->
+                        1 send
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1)
+        .run_until_receiving(1);
+}
+
+#[test]
+fn evaluate_reference_to_host_function() {
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 &send eval
+                        2 &send apply
+                    end
+                end
+
+                apply: fn
+                    br value, f ->
+                        value f eval
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1)
+        .run_until_receiving(2);
+}
+
+#[test]
+fn evaluate_recursive_reference() {
+    // A function can refer to itself, before it has been fully compiled.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        0 f
+                    end
+                end
+
+                f: fn
+                    br 0 ->
+                        0 send
+                        1 &f eval
+                    end
+
+                    br _ ->
+                        1 send
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(0)
+        .run_until_receiving(1);
+}
@@ -1,4 +1,4 @@
-use crosscut_runtime::{Effect, PopOperandError};
+use crosscut_runtime::{Effect, PopOperandError, Value};
 
 use crate::tests::infra::runtime;
 
@@ -31,3 +31,112 @@ fn access_operand_from_parent_scope() {
         })
     );
 }
+
+#[test]
+fn select_branch_by_literal_patterns() {
+    // If the arguments don't match the literal patterns of a branch, the next
+    // branch gets a chance to match them, with all arguments still intact.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 2 f send
+                        2 1 f send
+                        1 1 f send
+                        main
+                    end
+                end
+
+                f: fn
+                    br 1, 1 ->
+                        1
+                    end
+
+                    br 1, b ->
+                        b
+                    end
+
+                    br a, b ->
+                        3
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(2)
+        .run_until_receiving(3)
+        .run_until_receiving(1);
+}
+
+#[test]
+fn trigger_effect_if_no_branch_matches() {
+    // Calling a function with arguments that none of its branches match
+    // triggers an effect.
+
+    let effect = runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 f
+                    end
+                end
+
+                f: fn
+                    br 0 ->
+                    end
+                end
+            ",
+        )
+        .run_until_effect();
+
+    assert_eq!(effect, Some(Effect::NoMatch));
+}
+
+#[test]
+fn return_values_to_host() {
+    // Once `main` returns, the process is finished. Whatever it left on the
+    // stack is available to the host.
+
+    let return_values = runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 2
+                    end
+                end
+            ",
+        )
+        .run_until_finished();
+
+    assert_eq!(return_values, [Value::from(1), Value::from(2)]);
+}
+
+#[test]
+fn preempt_endless_recursion_when_budget_runs_out() {
+    // A process that never finishes must not block its host. Once the budget
+    // runs out, the host gets back control, and the process can continue later.
+
+    let mut runtime = runtime();
+    runtime.update_code(
+        r"
+            main: fn
+                br ->
+                    main
+                end
+            end
+        ",
+    );
+
+    let mut budget = 1000;
+    let state = runtime.run_with_budget(&mut budget);
+    assert_eq!(budget, 0);
+    assert!(state.is_running());
+
+    let mut budget = 1000;
+    let state = runtime.run_with_budget(&mut budget);
+    assert_eq!(budget, 0);
+    assert!(state.is_running());
+}
@@ -0,0 +1,88 @@
+use crosscut_runtime::Effect;
+
+use crate::{
+    tests::infra::{runtime, TestHost},
+    Compiler,
+};
+
+#[test]
+fn u32_literals_beyond_the_range_of_s32() {
+    // `U32` values can be larger than any `S32` value, and literals need to be
+    // able to express those.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        4294967295 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(u32::MAX);
+}
+
+#[test]
+fn convert_between_s32_and_u32() {
+    // Values that both types can represent can be converted back and forth.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        2147483647 u32_to_s32 s32_to_u32 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(i32::MAX as u32);
+}
+
+#[test]
+fn convert_negative_s32_to_u32() {
+    // A negative number has no `U32` representation, so converting it is an
+    // error.
+
+    let effect = runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        -1 s32_to_u32 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_effect();
+
+    assert_eq!(effect, Some(Effect::OperandOutOfBounds));
+}
+
+#[test]
+fn reject_s32_literal_beyond_the_range_of_s32() {
+    // A literal that is only valid for `U32` must not be accepted as `S32`.
+
+    let output = Compiler::default().compile(
+        r"
+            main: fn
+                br ->
+                    2147483648 s32_to_u32 send
+                end
+            end
+        ",
+        &TestHost {},
+    );
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(
+        error.message,
+        "Type error: expected `S32`, got integer literal"
+    );
+}
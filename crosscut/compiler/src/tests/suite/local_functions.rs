@@ -104,3 +104,69 @@ fn captured_binding() {
         )
         .run_until_receiving(0);
 }
+
+#[test]
+fn captured_bindings_and_parameters() {
+    // Captured bindings and parameters are stored side by side. Neither may
+    // overwrite the other.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 2
+                        fn
+                            br a, b ->
+                                3
+                                fn
+                                    br c ->
+                                        b send
+                                        a send
+                                        c send
+                                    end
+                                end
+                                eval
+                            end
+                        end
+                        eval
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(2)
+        .run_until_receiving(1)
+        .run_until_receiving(3);
+}
+
+#[test]
+fn copy_and_drop_function_with_captured_binding() {
+    // A function, including the values it captured, is a regular value. It can
+    // be copied, dropped, and evaluated multiple times.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1
+                        fn
+                            br channel ->
+                                fn
+                                    br ->
+                                        channel send
+                                    end
+                                end
+                                copy drop
+                                copy eval
+                                eval
+                            end
+                        end
+                        eval
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1)
+        .run_until_receiving(1);
+}
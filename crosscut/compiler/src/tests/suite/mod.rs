@@ -1,3 +1,11 @@
 mod code_update;
+mod diagnostics;
+mod enums;
+mod function_references;
 mod functions;
+mod integers;
 mod local_functions;
+mod modules;
+mod stack_limits;
+mod standard_library;
+mod structs;
@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    host::NoHost,
+    sources::{search_path, SourceFile, Sources},
+    tests::infra::runtime,
+    Compiler,
+};
+
+#[test]
+fn call_function_from_imported_module() {
+    // A module can call the functions of the modules it imports.
+
+    let sources = sources(
+        r"
+            import util .
+
+            main: fn
+                br ->
+                    notify
+                    main
+                end
+            end
+        ",
+        r"
+            notify: fn
+                br ->
+                    0 send
+                end
+            end
+        ",
+    );
+
+    runtime().update_sources(&sources).run_until_receiving(0);
+}
+
+#[test]
+fn use_updated_code_from_imported_module() {
+    // If a function in an imported module is updated, we expect the next call
+    // to it to execute the new version.
+
+    let main = r"
+        import util .
+
+        main: fn
+            br ->
+                notify
+                main
+            end
+        end
+    ";
+
+    let mut runtime = runtime();
+
+    runtime
+        .update_sources(&sources(
+            main,
+            r"
+                notify: fn
+                    br ->
+                        0 send
+                    end
+                end
+            ",
+        ))
+        .run_until_receiving(0);
+
+    runtime
+        .update_sources(&sources(
+            main,
+            r"
+                notify: fn
+                    br ->
+                        1 send
+                    end
+                end
+            ",
+        ))
+        .run_until_receiving(1);
+}
+
+#[test]
+fn functions_of_modules_that_are_not_imported_are_not_visible() {
+    // Only the functions of imported modules can be called. Trying to call a
+    // function of another module should result in an error that points the
+    // developer in the right direction.
+
+    let sources = sources(
+        r"
+            main: fn
+                br ->
+                    f
+                end
+            end
+        ",
+        r"
+            f: fn
+                br ->
+                end
+            end
+        ",
+    );
+
+    let output = Compiler::default().compile_sources(&sources, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Unresolved identifier `f`");
+    assert_eq!(
+        error.notes,
+        ["There is a function `f` in module `util`, which isn't imported here."],
+    );
+}
+
+#[test]
+fn report_import_of_missing_module() {
+    // Importing a module that doesn't exist should be reported, pointing to the
+    // import.
+
+    let source = r"
+        import missing .
+
+        main: fn
+            br ->
+            end
+        end
+    ";
+
+    let output = Compiler::default().compile(source, &NoHost);
+
+    let [error] = output.diagnostics.errors().collect::<Vec<_>>()[..] else {
+        panic!("Expected exactly one error: {:#?}", output.diagnostics);
+    };
+    assert_eq!(error.message, "Module `missing` not found");
+
+    let span = error.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "missing");
+}
+
+#[test]
+fn load_modules_imported_directly_or_indirectly() {
+    // Imported modules are looked for along the search path. Modules that
+    // they import in turn are loaded too.
+
+    let files = BTreeMap::from([
+        ("game/main.capi", "import util ."),
+        ("util.capi", "import shapes ."),
+        ("game/shapes.capi", ""),
+    ]);
+
+    let mut sources = Sources::new(SourceFile::main(
+        "game/main.capi",
+        files["game/main.capi"],
+    ));
+    let Ok(paths) =
+        sources.load_imports(&search_path(Path::new("game")), |path| {
+            Ok::<_, Infallible>(
+                files
+                    .get(path.to_str().unwrap())
+                    .map(|code| code.to_string()),
+            )
+        });
+
+    let modules = sources
+        .iter()
+        .map(|(_, file)| (file.module.as_str(), file.path.to_str().unwrap()))
+        .filter(|(module, _)| ["util", "shapes"].contains(module))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        modules,
+        [("util", "util.capi"), ("shapes", "game/shapes.capi")]
+    );
+
+    // The file that didn't exist could change the result, if it's created.
+    assert_eq!(
+        paths,
+        [
+            PathBuf::from("game/util.capi"),
+            PathBuf::from("util.capi"),
+            PathBuf::from("game/shapes.capi"),
+        ],
+    );
+}
+
+fn sources(main: &str, util: &str) -> Sources {
+    let mut sources = Sources::new(SourceFile::main("main.capi", main));
+    sources.push(SourceFile {
+        module: "util".to_string(),
+        path: "util.capi".into(),
+        code: util.to_string(),
+    });
+    sources
+}
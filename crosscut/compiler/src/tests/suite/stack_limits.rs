@@ -0,0 +1,83 @@
+use crosscut_runtime::{
+    Effect, PushOperandError, PushStackFrameError, StackLimits,
+};
+
+use crate::tests::infra::runtime;
+
+const COUNT_DOWN: &str = r"
+    main: fn
+        br ->
+            100 count_down drop
+            0 send
+        end
+    end
+
+    count_down: fn
+        br 0 ->
+            0
+        end
+
+        br n ->
+            # Not a tail call, so every recursive call needs a new stack frame.
+            n 1 sub_s32 count_down
+            1 add_s32
+        end
+    end
+";
+
+#[test]
+fn non_tail_recursion_within_frame_limit() {
+    runtime().update_code(COUNT_DOWN).run_until_receiving(0);
+}
+
+#[test]
+fn report_call_chain_on_frame_overflow() {
+    let mut runtime = runtime();
+    runtime
+        .with_stack_limits(StackLimits {
+            frames: 16,
+            ..StackLimits::default()
+        })
+        .update_code(COUNT_DOWN);
+
+    let effect = runtime.run_until_effect();
+    assert_eq!(
+        effect,
+        Some(Effect::PushStackFrame {
+            source: PushStackFrameError::Overflow
+        })
+    );
+
+    let call_chain = runtime.call_chain();
+    assert_eq!(call_chain.len(), 16);
+    assert_eq!(call_chain[0], "named function `main`");
+    assert!(call_chain[1..]
+        .iter()
+        .all(|function| function == "named function `count_down`"));
+}
+
+#[test]
+fn trigger_effect_on_operand_overflow() {
+    let effect = runtime()
+        .with_stack_limits(StackLimits {
+            operands: 4,
+            ..StackLimits::default()
+        })
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        1 2 3 4 5
+                    end
+                end
+            ",
+        )
+        .run_until_effect();
+
+    assert_eq!(
+        effect,
+        Some(Effect::PushOperand {
+            source: PushOperandError::Overflow
+        })
+    );
+}
@@ -0,0 +1,116 @@
+use crate::{
+    sources::{SourceFile, Sources},
+    tests::infra::{runtime, TestHost},
+    Compiler,
+};
+
+#[test]
+fn call_function_from_standard_library() {
+    // The functions of the standard library can be called without importing
+    // any modules.
+
+    runtime()
+        .update_code(
+            r"
+                main: fn
+                    br ->
+                        3 5 max_s32 s32_to_u32 send
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(5);
+}
+
+#[test]
+fn shadow_function_from_standard_library() {
+    // If a game defines a function with the same name as one from the standard
+    // library, that should not be an error. The game's function should be
+    // called instead.
+
+    let source = r"
+        main: fn
+            br ->
+                3 5 max_s32 s32_to_u32 send
+                main
+            end
+        end
+
+        max_s32: fn
+            br a, _ ->
+                a
+            end
+        end
+    ";
+
+    let mut compiler = Compiler::default();
+    let output = compiler.compile(source, &TestHost {});
+    assert!(!output.diagnostics.has_errors());
+
+    runtime().update_code(source).run_until_receiving(3);
+}
+
+#[test]
+fn library_module_that_host_does_not_support_is_not_available() {
+    // Some modules of the standard library depend on host functions. If the
+    // host doesn't provide those, the module's functions can't be called.
+
+    let mut compiler = Compiler::default();
+    let output = compiler.compile(
+        r"
+            main: fn
+                br ->
+                    0 vec_load
+                end
+            end
+        ",
+        &TestHost {},
+    );
+
+    let errors = output
+        .diagnostics
+        .errors()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect::<Vec<_>>();
+    assert!(
+        errors.contains(&"Unresolved identifier `vec_load`"),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn use_updated_code_from_standard_library() {
+    // The standard library is compiled along with the game. If it is updated,
+    // we expect the next call to its functions to execute the new version.
+
+    let mut sources = Sources::new(SourceFile::main(
+        "main.capi",
+        r"
+            main: fn
+                br ->
+                    3 5 max_s32 s32_to_u32 send
+                    main
+                end
+            end
+        ",
+    ));
+
+    let mut runtime = runtime();
+    runtime.update_sources(&sources).run_until_receiving(5);
+
+    let (cmp, _) = sources
+        .iter()
+        .find(|(_, file)| file.module == "cmp")
+        .unwrap();
+    sources.get_mut(&cmp).unwrap().code = r"
+        max_s32: fn
+            br a, _ ->
+                a
+            end
+        end
+    "
+    .to_string();
+
+    runtime.update_sources(&sources).run_until_receiving(3);
+}
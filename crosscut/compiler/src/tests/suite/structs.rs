@@ -0,0 +1,117 @@
+use crate::tests::infra::runtime;
+
+#[test]
+fn pass_struct_to_function_and_return_it() {
+    // A struct can be passed to a function, and returned from it, without
+    // having to take it apart first.
+
+    runtime()
+        .update_code(
+            r"
+                Pair: struct
+                    a: U32
+                    b: U32
+                end
+
+                main: fn
+                    br ->
+                        1 2 Pair
+                        swap
+                        fn
+                            br pair: Pair ->
+                                pair.a send
+                                pair.b send
+                            end
+                        end
+                        eval
+                        main
+                    end
+                end
+
+                swap: fn
+                    br pair: Pair ->
+                        pair.b pair.a Pair
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(2)
+        .run_until_receiving(1);
+}
+
+#[test]
+fn access_fields_of_nested_struct_from_local_function() {
+    // The fields of nested structs are accessible too, including from a local
+    // function that captures the binding.
+
+    runtime()
+        .update_code(
+            r"
+                Inner: struct
+                    a: U32
+                    b: U32
+                end
+
+                Outer: struct
+                    inner: Inner
+                    c: U32
+                end
+
+                main: fn
+                    br ->
+                        1 2 Inner 3 Outer
+                        fn
+                            br outer: Outer ->
+                                fn
+                                    br ->
+                                        outer.inner.b send
+                                        outer.c send
+                                    end
+                                end
+                                eval
+                            end
+                        end
+                        eval
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(2)
+        .run_until_receiving(3);
+}
+
+#[test]
+fn copy_and_drop_struct() {
+    // `copy` and `drop` operate on the whole struct, not just on one of its
+    // fields.
+
+    runtime()
+        .update_code(
+            r"
+                Pair: struct
+                    a: U32
+                    b: U32
+                end
+
+                main: fn
+                    br ->
+                        1 2 Pair
+                        copy
+                        3 4 Pair
+                        drop
+                        fn
+                            br first: Pair, second: Pair ->
+                                first.a send
+                                second.b send
+                            end
+                        end
+                        eval
+                        main
+                    end
+                end
+            ",
+        )
+        .run_until_receiving(1)
+        .run_until_receiving(2);
+}
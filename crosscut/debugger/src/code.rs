@@ -44,10 +44,11 @@ async fn on_new_code(
     let code = code?.text().await?;
     let code: Versioned<CompilerOutput> = ron_options().from_str(&code)?;
 
-    let command = state.on_new_code(code.inner);
-    commands_to_runtime_tx.send(command.serialize()).expect(
-        "Command receiver lives in static variable, should never drop.",
-    );
+    for command in state.on_new_code(code.inner) {
+        commands_to_runtime_tx.send(command.serialize()).expect(
+            "Command receiver lives in static variable, should never drop.",
+        );
+    }
 
     Ok(code.timestamp)
 }
@@ -9,7 +9,7 @@ use crate::{
     code::CodeFetcher,
     commands::{CommandsToRuntimeRx, CommandsToRuntimeTx},
     model::{PersistentState, TransientState, UserAction},
-    ui,
+    recording, snapshot, ui,
 };
 
 pub struct Debugger {
@@ -103,6 +103,21 @@ impl Default for Debugger {
 fn on_update_from_runtime(update: Vec<u8>, state: &mut PersistentState) {
     let update = UpdateFromHost::deserialize(update);
     state.on_update_from_host(update);
+
+    if let Some(file) = state.snapshot_to_save.take() {
+        leptos::task::spawn_local(async {
+            if let Err(err) = snapshot::save(file).await {
+                log::error!("Failed to save snapshot: {err:?}");
+            }
+        });
+    }
+    if let Some(events) = state.recording_to_save.take() {
+        leptos::task::spawn_local(async {
+            if let Err(err) = recording::save(events).await {
+                log::error!("Failed to save recording: {err:?}");
+            }
+        });
+    }
 }
 
 fn on_ui_action(
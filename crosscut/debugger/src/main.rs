@@ -3,6 +3,8 @@ mod commands;
 mod debugger;
 mod ffi;
 mod model;
+mod recording;
+mod snapshot;
 mod ui;
 
 fn main() {
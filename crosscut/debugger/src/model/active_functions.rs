@@ -63,8 +63,10 @@ impl ActiveFunctions {
         let mut entries = VecDeque::new();
         let mut expected_next_function = Some("main".to_string());
 
-        if let Some(outer) = active_instructions.front() {
-            let (outer, _) = instruction_to_named_function(outer, code);
+        if let Some((outer, _)) = active_instructions
+            .front()
+            .and_then(|outer| instruction_to_named_function(outer, code))
+        {
             if Some(outer.name) != expected_next_function {
                 expected_next_function = reconstruct_function(
                     "main",
@@ -77,8 +79,14 @@ impl ActiveFunctions {
         }
 
         while let Some(address) = active_instructions.pop_front() {
-            let (named_function, function_index_in_root_context) =
-                instruction_to_named_function(&address, code);
+            let Some((named_function, function_index_in_root_context)) =
+                instruction_to_named_function(&address, code)
+            else {
+                // A host function that is referred to as a value, has some
+                // instructions of its own. They don't map to any function in
+                // the source code, so there's nothing to display.
+                continue;
+            };
             let active_expression =
                 code.source_map.instruction_to_expression(&address);
 
@@ -184,10 +192,7 @@ impl ActiveFunctionsEntries {
                 ActiveFunctionsEntry::Function(function) => Some(function),
                 ActiveFunctionsEntry::Gap => None,
             })
-            .filter_map(|function| match function.inner.active_branch() {
-                Ok(branch) => Some(branch),
-                Err(_) => None,
-            })
+            .filter_map(|function| function.inner.active_branch().ok())
             .find(|branch| {
                 !branch.body.iter().any(|f| f.data.location == *expression)
             });
@@ -257,10 +262,8 @@ impl fmt::Display for ActiveFunctionsMessage {
 fn instruction_to_named_function(
     address: &InstructionAddress,
     code: &CompilerOutput,
-) -> (NamedFunction, Index<NamedFunction>) {
-    let location = code.source_map.instruction_to_function(address).expect(
-        "Expecting instructions on call stack to all map to a function.",
-    );
+) -> Option<(NamedFunction, Index<NamedFunction>)> {
+    let location = code.source_map.instruction_to_function(address)?;
 
     let mut current_location = location.clone();
 
@@ -277,7 +280,7 @@ fn instruction_to_named_function(
                     )
                     .clone();
 
-                return (function, index);
+                return Some((function, index));
             }
             FunctionLocation::Local { location } => {
                 current_location = *location.parent.parent;
@@ -75,6 +75,27 @@ impl DebugBranch {
                     name: format!("{value:?}"),
                     type_: None,
                 },
+                Parameter::Variant {
+                    enum_,
+                    variant,
+                    binding,
+                } => {
+                    let binding = binding
+                        .as_ref()
+                        .map(|binding| format!(" {}", binding.name))
+                        .unwrap_or_default();
+
+                    DebugParameter {
+                        name: format!("{enum_}.{variant}{binding}"),
+                        type_: types
+                            .type_of_parameter(&parameter.location)
+                            .cloned(),
+                    }
+                }
+                Parameter::Error(error) => DebugParameter {
+                    name: error.to_string(),
+                    type_: None,
+                },
             })
             .collect();
 
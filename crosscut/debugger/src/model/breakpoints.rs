@@ -1,23 +1,35 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crosscut_runtime::InstructionAddress;
 
+pub use crosscut_game_engine::breakpoints::Breakpoint;
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Breakpoints {
-    durable: BTreeSet<InstructionAddress>,
+    durable: BTreeMap<InstructionAddress, Breakpoint>,
     ephemeral: BTreeSet<InstructionAddress>,
 }
 
 impl Breakpoints {
-    pub fn durable_at(&self, instruction: &InstructionAddress) -> bool {
-        self.durable.contains(instruction)
+    pub fn durable_at(
+        &self,
+        instruction: &InstructionAddress,
+    ) -> Option<&Breakpoint> {
+        self.durable.get(instruction)
     }
 
-    pub fn set_durable(&mut self, instruction: InstructionAddress) {
-        self.durable.insert(instruction);
+    pub fn set_durable(
+        &mut self,
+        instruction: InstructionAddress,
+        breakpoint: Breakpoint,
+    ) {
+        self.durable.insert(instruction, breakpoint);
     }
 
-    pub fn clear_durable(&mut self, instruction: &InstructionAddress) -> bool {
+    pub fn clear_durable(
+        &mut self,
+        instruction: &InstructionAddress,
+    ) -> Option<Breakpoint> {
         self.durable.remove(instruction)
     }
 
@@ -29,7 +41,19 @@ impl Breakpoints {
         self.ephemeral.clear();
     }
 
+    pub fn durable(
+        &self,
+    ) -> impl Iterator<Item = (InstructionAddress, &Breakpoint)> + '_ {
+        self.durable
+            .iter()
+            .map(|(instruction, breakpoint)| (*instruction, breakpoint))
+    }
+
+    pub fn ephemeral(&self) -> impl Iterator<Item = InstructionAddress> + '_ {
+        self.ephemeral.iter().copied()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = InstructionAddress> + '_ {
-        self.durable.iter().chain(self.ephemeral.iter()).copied()
+        self.durable.keys().chain(self.ephemeral.iter()).copied()
     }
 }
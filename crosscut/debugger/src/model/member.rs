@@ -9,7 +9,7 @@ use crosscut_compiler::{
 };
 use crosscut_runtime::Effect;
 
-use super::{Breakpoints, DebugFunction};
+use super::{Breakpoint, Breakpoints, DebugFunction};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DebugMember {
@@ -42,10 +42,11 @@ impl DebugMember {
             DebugMemberState::NotActive
         };
 
-        let has_durable_breakpoint = source_map
+        let durable_breakpoint = source_map
             .expression_to_instructions(&location)
             .iter()
-            .any(|instruction| breakpoints.durable_at(instruction));
+            .find_map(|instruction| breakpoints.durable_at(instruction))
+            .cloned();
 
         let active_effect = effect.and_then(|effect| {
             if state.is_innermost_active_expression() {
@@ -72,7 +73,7 @@ impl DebugMember {
             signature: types.signature_of_expression(&location).cloned(),
             location,
             state,
-            has_durable_breakpoint,
+            durable_breakpoint,
             effect: active_effect,
         };
 
@@ -85,7 +86,7 @@ pub struct DebugMemberData {
     pub signature: Option<Signature>,
     pub location: MemberLocation,
     pub state: DebugMemberState,
-    pub has_durable_breakpoint: bool,
+    pub durable_breakpoint: Option<Breakpoint>,
     pub effect: Option<Effect>,
 }
 
@@ -119,6 +120,7 @@ impl DebugMemberState {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DebugMemberKind {
     Comment { lines: Vec<String> },
+    Error { as_string: String },
     Function { function: DebugFunction },
     Identifier { name: String },
     Value { as_string: String },
@@ -141,8 +143,14 @@ impl DebugMemberKind {
     ) -> Self {
         match member {
             Member::Comment(Comment { lines }) => Self::Comment { lines },
+            Member::Error(error) => Self::Error {
+                as_string: error.to_string(),
+            },
             Member::Expression { expression, .. } => match expression {
                 Expression::Identifier { name } => Self::Identifier { name },
+                Expression::FunctionReference { name } => Self::Identifier {
+                    name: format!("&{name}"),
+                },
                 Expression::LiteralNumber { value } => Self::Value {
                     as_string: value.to_string(),
                 },
@@ -13,7 +13,7 @@ mod tests;
 pub use self::{
     active_functions::{ActiveFunctions, ActiveFunctionsEntry},
     branch::{DebugBranch, DebugParameter},
-    breakpoints::Breakpoints,
+    breakpoints::{Breakpoint, Breakpoints},
     code::DebugCode,
     function::{DebugFunction, DebugNamedFunction},
     member::{DebugMember, DebugMemberData, DebugMemberKind},
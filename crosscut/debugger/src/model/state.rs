@@ -1,12 +1,23 @@
+use anyhow::anyhow;
 use crosscut_compiler::{
-    code::syntax::MemberLocation, CompilerOutput, Instructions,
+    code::{syntax::MemberLocation, Hash},
+    CompilerOutput, Instructions,
+};
+use crosscut_game_engine::{
+    breakpoints::{self, DurableBreakpoint},
+    command::Command,
+    history::Timeline,
+    memory::Memory,
+    recording::RecordedEvent,
+};
+use crosscut_protocol::{
+    host_state::HostState, snapshot::SnapshotFile, updates::UpdateFromHost,
 };
-use crosscut_game_engine::{command::Command, memory::Memory};
-use crosscut_protocol::{host_state::HostState, updates::UpdateFromHost};
 use crosscut_runtime::{Effect, Instruction, Value};
 
 use super::{
-    ActiveFunctions, Breakpoints, DebugCode, DebugMemberKind, UserAction,
+    ActiveFunctions, Breakpoint, Breakpoints, DebugCode, DebugMemberKind,
+    UserAction,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -15,13 +26,23 @@ pub struct PersistentState {
     pub breakpoints: Breakpoints,
     pub host_state: Option<HostState>,
     pub memory: Option<Memory>,
+    pub timeline: Option<Timeline>,
+
+    /// # A snapshot that the host sent, which still needs to be saved
+    pub snapshot_to_save: Option<SnapshotFile>,
+
+    /// # A recording that the host sent, which still needs to be saved
+    pub recording_to_save: Option<Vec<RecordedEvent>>,
+
+    /// # The most recent messages that tracepoints have logged, oldest first
+    pub trace: Vec<String>,
 }
 
 impl PersistentState {
-    pub fn on_new_code(&mut self, code: CompilerOutput) -> Command {
-        let instructions = self.apply_breakpoints(&code);
+    pub fn on_new_code(&mut self, code: CompilerOutput) -> Vec<Command> {
+        let commands = self.update_code(&code).to_vec();
         self.code.inner = Some(code);
-        Command::UpdateCode { instructions }
+        commands
     }
 
     pub fn on_update_from_host(&mut self, update: UpdateFromHost) {
@@ -32,6 +53,28 @@ impl PersistentState {
             UpdateFromHost::State { state } => {
                 self.host_state = Some(state);
             }
+            UpdateFromHost::Timeline { timeline } => {
+                self.timeline = Some(timeline);
+            }
+            UpdateFromHost::Snapshot { snapshot } => {
+                // We can only get a snapshot, if we sent the host some code
+                // first. But if that isn't the case for some reason, there's no
+                // code to check a restored snapshot against later.
+                if let Some(code) = &self.code.inner {
+                    self.snapshot_to_save = Some(SnapshotFile {
+                        code: Hash::new(&code.instructions),
+                        snapshot,
+                    });
+                }
+            }
+            UpdateFromHost::Recording { events } => {
+                self.recording_to_save = Some(events);
+            }
+            UpdateFromHost::Trace { messages } => {
+                for message in messages {
+                    self.log(message);
+                }
+            }
         }
     }
 
@@ -50,20 +93,37 @@ impl PersistentState {
 
                 self.breakpoints.clear_durable(&address);
 
-                commands.push(Command::UpdateCode {
-                    instructions: self.apply_breakpoints(code),
-                });
+                commands.extend(self.update_code(code));
+            }
+            UserAction::BreakpointConfigure {
+                expression,
+                breakpoint,
+            } => {
+                if let Some(condition) = &breakpoint.condition {
+                    if condition.trim().is_empty() {
+                        // An empty condition can't be evaluated. Rejecting it
+                        // here is better than stopping every time the
+                        // breakpoint is hit, because evaluating it failed.
+                        return Err(anyhow!("Breakpoint condition is empty"));
+                    }
+                }
+
+                let code = self.code.get()?;
+                let address =
+                    self.code.expression_to_instruction(&expression)?;
+
+                self.breakpoints.set_durable(address, breakpoint);
+
+                commands.extend(self.update_code(code));
             }
             UserAction::BreakpointSet { expression } => {
                 let code = self.code.get()?;
                 let address =
                     self.code.expression_to_instruction(&expression)?;
 
-                self.breakpoints.set_durable(address);
+                self.breakpoints.set_durable(address, Breakpoint::default());
 
-                commands.push(Command::UpdateCode {
-                    instructions: self.apply_breakpoints(code),
-                });
+                commands.extend(self.update_code(code));
             }
             UserAction::Continue => {
                 let origin = &transient
@@ -85,6 +145,45 @@ impl PersistentState {
             UserAction::Reset => {
                 commands.push(Command::Reset);
             }
+            UserAction::RestoreSnapshot { file } => {
+                let code = self.code.get()?;
+
+                if file.code == Hash::new(&code.instructions) {
+                    commands.push(Command::RestoreSnapshot {
+                        snapshot: file.snapshot,
+                    });
+                } else {
+                    // This isn't a bug. The developer changed the code since
+                    // the snapshot was saved, and it's no longer compatible.
+                    log::error!(
+                        "Not restoring snapshot. It was taken while different \
+                        code was running."
+                    );
+                }
+            }
+            UserAction::ReverseContinue => {
+                commands.push(Command::ReverseContinue);
+            }
+            UserAction::SaveSnapshot => {
+                commands.push(Command::SaveSnapshot);
+            }
+            UserAction::StartRecording => {
+                let code = self.code.get()?;
+
+                // The recording starts with the version of the code that it was
+                // made with, so a replay can make sure it uses the same code.
+                // That needs to be the code without any breakpoints applied.
+                // Those are part of the recording, as the code update that
+                // follows.
+                commands.push(Command::UpdateCode {
+                    instructions: code.instructions.clone(),
+                });
+                commands.push(Command::StartRecording);
+                commands.extend(self.update_code(code));
+            }
+            UserAction::StepBack => {
+                commands.push(Command::StepBack);
+            }
             UserAction::StepIn => {
                 let code = self.code.get()?;
 
@@ -245,6 +344,12 @@ impl PersistentState {
             UserAction::Stop => {
                 commands.push(Command::Stop);
             }
+            UserAction::StopRecording => {
+                commands.push(Command::StopRecording);
+            }
+            UserAction::TimeTravel { frame } => {
+                commands.push(Command::TimeTravel { frame });
+            }
         };
 
         Ok(commands)
@@ -334,20 +439,39 @@ impl PersistentState {
         ]);
 
         // In case we removed a durable breakpoint, we need to revert that.
-        if durable_breakpoint_at_origin {
-            self.breakpoints.set_durable(origin);
+        if let Some(breakpoint) = durable_breakpoint_at_origin {
+            self.breakpoints.set_durable(origin, breakpoint);
         }
 
         // And finally, we can provide the latest code to the runtime, then send
         // it on its way.
-        commands.extend([
+        commands.extend(self.update_code(code));
+        commands.push(Command::ClearBreakpointAndContinue);
+
+        Ok(())
+    }
+
+    fn log(&mut self, message: String) {
+        // The trace is displayed in full, so it shouldn't grow without bounds.
+        const MAX_MESSAGES: usize = 100;
+
+        if self.trace.len() >= MAX_MESSAGES {
+            self.trace.remove(0);
+        }
+
+        self.trace.push(message);
+    }
+
+    /// # Provide the current code and breakpoints to the host
+    fn update_code(&self, code: &CompilerOutput) -> [Command; 2] {
+        [
             Command::UpdateCode {
                 instructions: self.apply_breakpoints(code),
             },
-            Command::ClearBreakpointAndContinue,
-        ]);
-
-        Ok(())
+            Command::UpdateBreakpoints {
+                breakpoints: self.breakpoints_for_host(code),
+            },
+        ]
     }
 
     fn apply_breakpoints(&self, code: &CompilerOutput) -> Instructions {
@@ -364,6 +488,49 @@ impl PersistentState {
 
         instructions
     }
+
+    /// # Describe the breakpoints, so the host can evaluate their settings
+    fn breakpoints_for_host(
+        &self,
+        code: &CompilerOutput,
+    ) -> breakpoints::Breakpoints {
+        let durable = self
+            .breakpoints
+            .durable()
+            .filter_map(|(address, breakpoint)| {
+                let instruction = match code.instructions.get(&address)? {
+                    // If the breakpoint doesn't stop the process, a `brk`
+                    // shouldn't either. Otherwise, it would be pointless to
+                    // configure a breakpoint there.
+                    Instruction::TriggerEffect {
+                        effect: Effect::Breakpoint,
+                    } => Instruction::Nop,
+                    instruction => instruction.clone(),
+                };
+                let bindings = code
+                    .source_map
+                    .instruction_to_expression(&address)
+                    .map(|expression| {
+                        code.source_map.slots_of_branch(&expression.parent)
+                    })
+                    .unwrap_or_default()
+                    .to_vec();
+
+                let breakpoint = DurableBreakpoint {
+                    settings: breakpoint.clone(),
+                    instruction,
+                    bindings,
+                };
+
+                Some((address, breakpoint))
+            })
+            .collect();
+
+        breakpoints::Breakpoints {
+            durable,
+            ephemeral: self.breakpoints.ephemeral().collect(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
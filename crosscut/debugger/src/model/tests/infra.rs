@@ -37,8 +37,8 @@ impl TestDebugger {
         let mut compiler = Compiler::default();
         let output = compiler.compile(source, &GameEngineHost);
 
-        let command = self.persistent.on_new_code(output);
-        self.queued_commands.push(command);
+        let commands = self.persistent.on_new_code(output);
+        self.queued_commands.extend(commands);
 
         self.update_transient_state();
 
@@ -86,9 +86,19 @@ impl TestDebugger {
     }
 
     fn process_updates(&mut self) {
-        if let Some(game_engine) = &self.game_engine {
-            self.updates
-                .queue_updates(&game_engine.runtime, &self.memory);
+        if let Some(game_engine) = &mut self.game_engine {
+            self.updates.queue_updates(
+                &game_engine.runtime,
+                &self.memory,
+                game_engine.timeline(),
+            );
+            if let Some(snapshot) = game_engine.take_saved_snapshot() {
+                self.updates.queue_snapshot(snapshot);
+            }
+            if let Some(events) = game_engine.take_finished_recording() {
+                self.updates.queue_recording(events);
+            }
+            self.updates.queue_trace(game_engine.take_trace());
             for update in self.updates.take_queued_updates() {
                 self.persistent.on_update_from_host(update);
             }
@@ -1,12 +1,14 @@
+use crosscut_compiler::code::syntax::MemberLocation;
+use crosscut_runtime::Value;
 use itertools::Itertools;
 
 use crate::model::{
     active_functions::ActiveFunctionsMessage,
     tests::infra::{
         debugger, ActiveFunctionsEntriesExt, ActiveFunctionsExt,
-        DebugFunctionExt, FunctionsExt,
+        DebugFunctionExt, FunctionsExt, TestDebugger,
     },
-    ActiveFunctions, UserAction,
+    ActiveFunctions, Breakpoint, UserAction,
 };
 
 #[test]
@@ -40,12 +42,20 @@ fn display_breakpoint_that_was_set() -> anyhow::Result<()> {
         .unwrap()
         .location;
 
-    assert!(!debugger.expect_expression(&nop).data.has_durable_breakpoint);
+    assert!(debugger
+        .expect_expression(&nop)
+        .data
+        .durable_breakpoint
+        .is_none());
 
     debugger.on_user_action(UserAction::BreakpointSet {
         expression: nop.clone(),
     })?;
-    assert!(debugger.expect_expression(&nop).data.has_durable_breakpoint);
+    assert!(debugger
+        .expect_expression(&nop)
+        .data
+        .durable_breakpoint
+        .is_some());
 
     Ok(())
 }
@@ -222,21 +232,20 @@ fn step_over_breakpoints() -> anyhow::Result<()> {
             .location,
         b,
     );
-    assert!(
-        debugger
-            .transient_state()
-            .active_functions
-            .expect_entries()
-            .expect_functions()
-            .expect_leaf("main")
-            .active_branch()?
-            .body
-            .iter()
-            .find(|expression| expression.data.location == a)
-            .unwrap()
-            .data
-            .has_durable_breakpoint
-    );
+    assert!(debugger
+        .transient_state()
+        .active_functions
+        .expect_entries()
+        .expect_functions()
+        .expect_leaf("main")
+        .active_branch()?
+        .body
+        .iter()
+        .find(|expression| expression.data.location == a)
+        .unwrap()
+        .data
+        .durable_breakpoint
+        .is_some());
 
     // Step to `c`, over the ephemeral breakpoint.
     debugger.on_user_action(UserAction::StepIn)?;
@@ -577,3 +586,303 @@ fn step_out_of_function() {
         b,
     );
 }
+
+#[test]
+fn conditional_breakpoint() -> anyhow::Result<()> {
+    // A breakpoint with a condition should only stop the process, if the
+    // condition holds.
+
+    let (mut debugger, nop) = count_to_three();
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            condition: Some(String::from("n 2 eq")),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.run_program();
+
+    assert_eq!(debugger.transient_state().operands, [Value::from(2)]);
+
+    Ok(())
+}
+
+#[test]
+fn breakpoint_condition_does_not_see_operands() {
+    // A condition is evaluated on a stack of its own. If it could access the
+    // game's operands, `copy` would succeed here. Instead, the evaluation
+    // should fail, which stops the process.
+
+    let (mut debugger, nop) = count_to_three();
+    debugger
+        .on_user_action(UserAction::BreakpointConfigure {
+            expression: nop,
+            breakpoint: Breakpoint {
+                condition: Some(String::from("copy")),
+                ..Breakpoint::default()
+            },
+        })
+        .unwrap();
+    debugger.run_program();
+
+    assert_eq!(debugger.transient_state().operands, [Value::from(0)]);
+    assert!(debugger.persistent_state().trace[0]
+        .starts_with("Failed to evaluate condition `copy`"));
+}
+
+#[test]
+fn reject_empty_breakpoint_condition() {
+    // A condition that consists of nothing but whitespace can't be evaluated.
+    // It should be rejected right away, instead of failing every time the
+    // breakpoint is hit.
+
+    let (mut debugger, nop) = count_to_three();
+    let result = debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            condition: Some(String::from("  ")),
+            ..Breakpoint::default()
+        },
+    });
+
+    assert!(result.is_err());
+    assert!(debugger
+        .persistent_state()
+        .breakpoints
+        .iter()
+        .next()
+        .is_none());
+}
+
+#[test]
+fn breakpoint_with_hit_count() -> anyhow::Result<()> {
+    // A breakpoint with a hit count should only stop the process, once it has
+    // been hit that many times.
+
+    let (mut debugger, nop) = count_to_three();
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            hit_count: Some(3),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.run_program();
+
+    assert_eq!(debugger.transient_state().operands, [Value::from(2)]);
+
+    Ok(())
+}
+
+#[test]
+fn tracepoint() -> anyhow::Result<()> {
+    // A tracepoint should log its message every time it is hit, without
+    // stopping the process.
+
+    let (mut debugger, nop) = count_to_three();
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            log_message: Some(String::from("n = {n}, next = {n 1 add_s32}")),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.run_program();
+
+    assert_eq!(
+        debugger.persistent_state().trace,
+        [
+            "n = 0x00000000, next = 0x00000001",
+            "n = 0x00000001, next = 0x00000002",
+            "n = 0x00000002, next = 0x00000003",
+        ],
+    );
+
+    // The process should only have stopped at the `brk` at the end.
+    assert_eq!(
+        debugger
+            .transient_state()
+            .active_functions
+            .expect_entries()
+            .expect_functions()
+            .expect_leaf("count")
+            .active_expression()
+            .data
+            .location,
+        debugger
+            .expect_code()
+            .function_by_name("count")
+            .unwrap()
+            .into_located_function()
+            .branches()
+            .next()
+            .unwrap()
+            .expressions()
+            .next()
+            .unwrap()
+            .location,
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tracepoint_does_not_affect_time_travel() -> anyhow::Result<()> {
+    // A tracepoint never stops the process, so stepping back or continuing in
+    // reverse should never end up there either.
+
+    let (mut debugger, nop) = count_to_three();
+    let n = expression_in_count(&debugger, 1, 0);
+    let brk = expression_in_count(&debugger, 0, 0);
+
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: n.clone(),
+        breakpoint: Breakpoint {
+            condition: Some(String::from("n 1 eq")),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            log_message: Some(String::from("n = {n}")),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.run_program();
+    assert_eq!(active_expression_in_count(&mut debugger), n);
+
+    debugger.on_user_action(UserAction::Continue)?;
+    assert_eq!(active_expression_in_count(&mut debugger), brk);
+
+    debugger.on_user_action(UserAction::StepBack)?;
+    assert_eq!(active_expression_in_count(&mut debugger), n);
+
+    debugger.on_user_action(UserAction::Continue)?;
+    assert_eq!(active_expression_in_count(&mut debugger), brk);
+
+    debugger.on_user_action(UserAction::ReverseContinue)?;
+    assert_eq!(active_expression_in_count(&mut debugger), n);
+
+    // The tracepoint logged its messages every time the process passed it.
+    assert_eq!(
+        debugger.persistent_state().trace,
+        [
+            "n = 0x00000000",
+            "n = 0x00000001",
+            "n = 0x00000002",
+            "n = 0x00000001",
+            "n = 0x00000002",
+        ],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn hit_count_goes_back_in_time() -> anyhow::Result<()> {
+    // Going back in time should also restore how often a breakpoint has been
+    // hit back then.
+
+    let (mut debugger, nop) = count_to_three();
+    let n = expression_in_count(&debugger, 1, 0);
+
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: n,
+        breakpoint: Breakpoint {
+            condition: Some(String::from("n 0 eq")),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.on_user_action(UserAction::BreakpointConfigure {
+        expression: nop,
+        breakpoint: Breakpoint {
+            hit_count: Some(2),
+            ..Breakpoint::default()
+        },
+    })?;
+    debugger.run_program();
+
+    debugger.on_user_action(UserAction::Continue)?;
+    assert_eq!(debugger.transient_state().operands, [Value::from(1)]);
+
+    debugger.on_user_action(UserAction::StepBack)?;
+    debugger.on_user_action(UserAction::Continue)?;
+    assert_eq!(debugger.transient_state().operands, [Value::from(1)]);
+
+    Ok(())
+}
+
+fn expression_in_count(
+    debugger: &TestDebugger,
+    branch: usize,
+    expression: usize,
+) -> MemberLocation {
+    debugger
+        .expect_code()
+        .function_by_name("count")
+        .unwrap()
+        .into_located_function()
+        .branches()
+        .nth(branch)
+        .unwrap()
+        .expressions()
+        .nth(expression)
+        .unwrap()
+        .location
+}
+
+fn active_expression_in_count(debugger: &mut TestDebugger) -> MemberLocation {
+    debugger
+        .transient_state()
+        .active_functions
+        .expect_entries()
+        .expect_functions()
+        .expect_leaf("count")
+        .active_expression()
+        .data
+        .location
+}
+
+/// # Provide a program that counts to three, stopping when it gets there
+///
+/// Returns the location of the `nop` that is evaluated for each number before
+/// three, while that number is on top of the stack.
+fn count_to_three() -> (TestDebugger, MemberLocation) {
+    let mut debugger = debugger();
+    debugger.provide_source_code(
+        r"
+            main: fn
+                br size_x, size_y ->
+                    0 count
+                end
+            end
+
+            count: fn
+                br 3 ->
+                    brk
+                end
+                br n ->
+                    n
+                    nop
+                    1 add_s32 count
+                end
+            end
+        ",
+    );
+
+    let nop = debugger
+        .expect_code()
+        .function_by_name("count")
+        .unwrap()
+        .into_located_function()
+        .branches()
+        .nth(1)
+        .unwrap()
+        .expressions()
+        .nth(1)
+        .unwrap()
+        .location;
+
+    (debugger, nop)
+}
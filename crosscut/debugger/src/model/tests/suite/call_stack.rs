@@ -5,7 +5,7 @@ use crate::model::{
         debugger, ActiveFunctionsEntriesExt, ActiveFunctionsExt,
         DebugBranchExt, DebugExpressionExt, DebugFunctionExt, FunctionsExt,
     },
-    ActiveFunctionsEntry, UserAction,
+    ActiveFunctionsEntry, DebugMemberKind, UserAction,
 };
 
 #[test]
@@ -134,6 +134,41 @@ fn stopped_in_anonymous_function() {
     );
 }
 
+#[test]
+fn stopped_at_syntax_error() {
+    // If execution is stopped at a syntax error, the function containing it
+    // should be displayed, with the syntax error as the active expression.
+
+    let mut debugger = debugger();
+    debugger
+        .provide_source_code(
+            r"
+                main: fn
+                    br size_x, size_y ->
+                        nop
+                        -> .
+                    end
+                end
+            ",
+        )
+        .run_program();
+
+    let expression = debugger
+        .transient_state()
+        .active_functions
+        .expect_entries()
+        .expect_functions()
+        .with_name("main")
+        .active_expression();
+    assert_eq!(expression.data.effect, Some(Effect::BuildError));
+    assert_eq!(
+        expression.kind,
+        DebugMemberKind::Error {
+            as_string: "->".to_string(),
+        },
+    );
+}
+
 #[test]
 fn call_stack_reconstruction_missing_main() {
     // Tail call elimination can leave gaps in the call stack. If the `main`
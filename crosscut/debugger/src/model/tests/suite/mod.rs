@@ -1,3 +1,5 @@
 mod basic_state;
 mod breakpoints;
 mod call_stack;
+mod recording;
+mod time_travel;
@@ -0,0 +1,70 @@
+use crosscut_game_engine::{
+    command::Command, game_engine::GameEngine, recording::Replay,
+};
+
+use crate::model::{tests::infra::debugger, UserAction};
+
+#[test]
+fn recording_includes_breakpoints() -> anyhow::Result<()> {
+    // A recording made in the debugger should include the breakpoints, as
+    // well as the debugger commands that were sent while recording. Replaying
+    // it should start from the plain code, like the headless host would.
+
+    let mut debugger = debugger();
+    debugger.provide_source_code(
+        r"
+            main: fn
+                br size_x, size_y ->
+                    nop # a
+                    nop # b
+                end
+            end
+        ",
+    );
+
+    let a = debugger
+        .expect_code()
+        .function_by_name("main")
+        .unwrap()
+        .into_located_function()
+        .find_single_branch()
+        .unwrap()
+        .expressions()
+        .next()
+        .unwrap()
+        .location;
+    debugger.on_user_action(UserAction::BreakpointSet { expression: a })?;
+    debugger.run_program();
+
+    debugger.on_user_action(UserAction::StartRecording)?;
+    debugger.on_user_action(UserAction::StepIn)?;
+    debugger.on_user_action(UserAction::Continue)?;
+    debugger.on_user_action(UserAction::StopRecording)?;
+
+    let events = debugger
+        .persistent_state()
+        .recording_to_save
+        .clone()
+        .expect("Expected recording to be saved");
+
+    let instructions = debugger
+        .persistent_state()
+        .code
+        .inner
+        .as_ref()
+        .unwrap()
+        .instructions
+        .clone();
+    let mut game_engine = GameEngine::new();
+    game_engine.on_command(Command::UpdateCode { instructions });
+
+    // The replay checks that the process stops wherever it stopped while
+    // recording. That only works, if the breakpoints are part of the
+    // recording.
+    let mut replay = Replay::new();
+    for event in events {
+        replay.replay_event(event, &mut game_engine)?;
+    }
+
+    Ok(())
+}
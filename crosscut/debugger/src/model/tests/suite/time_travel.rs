@@ -0,0 +1,153 @@
+use crosscut_compiler::code::syntax::MemberLocation;
+use itertools::Itertools;
+
+use crate::model::{
+    tests::infra::{
+        debugger, ActiveFunctionsEntriesExt, ActiveFunctionsExt,
+        DebugFunctionExt, FunctionsExt, TestDebugger,
+    },
+    UserAction,
+};
+
+#[test]
+fn step_back() -> anyhow::Result<()> {
+    // After stepping, the developer should be able to step back to where the
+    // process was stopped before.
+
+    let mut debugger = debugger();
+    debugger
+        .provide_source_code(
+            r"
+                main: fn
+                    br size_x, size_y ->
+                        brk
+                        nop # a
+                        nop # b
+                    end
+                end
+            ",
+        )
+        .run_program();
+
+    let (brk, a, b) = expressions_in_main(&debugger);
+    assert_eq!(active_expression(&mut debugger), brk);
+
+    debugger.on_user_action(UserAction::StepIn)?;
+    debugger.on_user_action(UserAction::StepIn)?;
+    assert_eq!(active_expression(&mut debugger), b);
+
+    debugger.on_user_action(UserAction::StepBack)?;
+    assert_eq!(active_expression(&mut debugger), a);
+
+    debugger.on_user_action(UserAction::StepBack)?;
+    assert_eq!(active_expression(&mut debugger), brk);
+
+    // Once we stepped back, we can continue from there, as usual.
+    debugger.on_user_action(UserAction::StepIn)?;
+    assert_eq!(active_expression(&mut debugger), a);
+
+    Ok(())
+}
+
+#[test]
+fn reverse_continue_to_breakpoint() -> anyhow::Result<()> {
+    // Continuing in reverse should go back to the last time the process
+    // stopped at a durable breakpoint.
+
+    let mut debugger = debugger();
+    debugger.provide_source_code(
+        r"
+            main: fn
+                br size_x, size_y ->
+                    nop # a
+                    nop # b
+                    nop # c
+                end
+            end
+        ",
+    );
+
+    let (a, _, c) = expressions_in_main(&debugger);
+    debugger.on_user_action(UserAction::BreakpointSet {
+        expression: a.clone(),
+    })?;
+
+    debugger.run_program();
+    assert_eq!(active_expression(&mut debugger), a);
+
+    debugger.on_user_action(UserAction::StepIn)?;
+    debugger.on_user_action(UserAction::StepIn)?;
+    assert_eq!(active_expression(&mut debugger), c);
+
+    debugger.on_user_action(UserAction::ReverseContinue)?;
+    assert_eq!(active_expression(&mut debugger), a);
+
+    Ok(())
+}
+
+#[test]
+fn restore_snapshot() -> anyhow::Result<()> {
+    // A saved snapshot can be restored later, no matter what happened in
+    // between.
+
+    let mut debugger = debugger();
+    debugger
+        .provide_source_code(
+            r"
+                main: fn
+                    br size_x, size_y ->
+                        brk
+                        nop # a
+                        nop # b
+                    end
+                end
+            ",
+        )
+        .run_program();
+
+    let (brk, _, b) = expressions_in_main(&debugger);
+
+    debugger.on_user_action(UserAction::SaveSnapshot)?;
+    let file = debugger
+        .persistent_state()
+        .snapshot_to_save
+        .clone()
+        .expect("Expected snapshot to be saved");
+
+    debugger.on_user_action(UserAction::StepIn)?;
+    debugger.on_user_action(UserAction::StepIn)?;
+    assert_eq!(active_expression(&mut debugger), b);
+
+    debugger.on_user_action(UserAction::RestoreSnapshot { file })?;
+    assert_eq!(active_expression(&mut debugger), brk);
+
+    Ok(())
+}
+
+fn expressions_in_main(
+    debugger: &TestDebugger,
+) -> (MemberLocation, MemberLocation, MemberLocation) {
+    debugger
+        .expect_code()
+        .function_by_name("main")
+        .unwrap()
+        .into_located_function()
+        .find_single_branch()
+        .unwrap()
+        .expressions()
+        .map(|expression| expression.location)
+        .collect_tuple()
+        .unwrap()
+}
+
+fn active_expression(debugger: &mut TestDebugger) -> MemberLocation {
+    debugger
+        .transient_state()
+        .active_functions
+        .expect_entries()
+        .expect_functions()
+        .expect_leaf("main")
+        .active_expression()
+        .data
+        .location
+}
@@ -1,13 +1,36 @@
 use crosscut_compiler::code::syntax::MemberLocation;
+use crosscut_protocol::snapshot::SnapshotFile;
 
+use super::Breakpoint;
+
+#[allow(clippy::large_enum_variant)] // haven't optimized this yet
 #[derive(Clone)]
 pub enum UserAction {
-    BreakpointClear { expression: MemberLocation },
-    BreakpointSet { expression: MemberLocation },
+    BreakpointClear {
+        expression: MemberLocation,
+    },
+    BreakpointConfigure {
+        expression: MemberLocation,
+        breakpoint: Breakpoint,
+    },
+    BreakpointSet {
+        expression: MemberLocation,
+    },
     Continue,
     Reset,
+    RestoreSnapshot {
+        file: SnapshotFile,
+    },
+    ReverseContinue,
+    SaveSnapshot,
+    StartRecording,
+    StepBack,
     StepIn,
     StepOut,
     StepOver,
     Stop,
+    StopRecording,
+    TimeTravel {
+        frame: u64,
+    },
 }
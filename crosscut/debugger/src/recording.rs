@@ -0,0 +1,21 @@
+use crosscut_game_engine::recording::RecordedEvent;
+use gloo_net::http::Request;
+
+use crate::snapshot::check_status;
+
+/// # Save a recording on the server, so it can be downloaded
+///
+/// Uses the same format as `crosscut headless --record`, one event per line, so
+/// the downloaded file can be replayed with `crosscut headless --replay`.
+pub async fn save(events: Vec<RecordedEvent>) -> anyhow::Result<()> {
+    let mut file = String::new();
+    for event in events {
+        file.push_str(&ron::to_string(&event)?);
+        file.push('\n');
+    }
+
+    let response = Request::put("/recording").body(file)?.send().await?;
+    check_status(&response)?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::anyhow;
+use crosscut_protocol::{ron_options, snapshot::SnapshotFile};
+use gloo_net::http::{Request, Response};
+
+pub async fn save(file: SnapshotFile) -> anyhow::Result<()> {
+    let file = ron_options().to_string(&file)?;
+
+    let response = Request::put("/snapshot").body(file)?.send().await?;
+    check_status(&response)?;
+
+    Ok(())
+}
+
+pub async fn load() -> anyhow::Result<SnapshotFile> {
+    let response = Request::get("/snapshot").send().await?;
+    check_status(&response)?;
+
+    let file = response.text().await?;
+    let file = ron_options().from_str(&file)?;
+
+    Ok(file)
+}
+
+pub fn check_status(response: &Response) -> anyhow::Result<()> {
+    if !response.ok() {
+        return Err(anyhow!(
+            "Server responded with `{} {}`",
+            response.status(),
+            response.status_text(),
+        ));
+    }
+
+    Ok(())
+}
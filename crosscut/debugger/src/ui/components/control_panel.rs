@@ -1,15 +1,33 @@
-use leptos::{component, view, IntoView};
+use crosscut_game_engine::history;
+use leptos::{
+    component,
+    prelude::{ClassAttribute, ElementChild, OnAttribute},
+    view, IntoView,
+};
 
 use crate::{
     model::UserAction,
+    snapshot,
     ui::{
-        components::{button::Button, panel::Panel},
+        actions::send_action,
+        components::{button::Button, panel::Panel, timeline::Timeline},
         ActionsTx,
     },
 };
 
 #[component]
-pub fn ControlPanel(actions: ActionsTx) -> impl IntoView {
+pub fn ControlPanel(
+    timeline: Option<history::Timeline>,
+    actions: ActionsTx,
+) -> impl IntoView {
+    let timeline = timeline.map(|timeline| {
+        view! {
+            <Timeline
+                timeline=timeline
+                actions=actions.clone() />
+        }
+    });
+
     view! {
         <Panel class="">
             <Button
@@ -23,7 +41,67 @@ pub fn ControlPanel(actions: ActionsTx) -> impl IntoView {
             <Button
                 label="Continue"
                 action=UserAction::Continue
+                actions=actions.clone() />
+            <Button
+                label="Step Back"
+                action=UserAction::StepBack
+                actions=actions.clone() />
+            <Button
+                label="Reverse Continue"
+                action=UserAction::ReverseContinue
+                actions=actions.clone() />
+            <Button
+                label="Save Snapshot"
+                action=UserAction::SaveSnapshot
+                actions=actions.clone() />
+            <RestoreSnapshotButton
+                actions=actions.clone() />
+            <Button
+                label="Start Recording"
+                action=UserAction::StartRecording
+                actions=actions.clone() />
+            <Button
+                label="Stop Recording"
+                action=UserAction::StopRecording
                 actions=actions />
+            <a
+                href="/recording"
+                download="recording.ron"
+                class="m-1 px-1 bg-gray-300 font-bold">
+                "Download Recording"
+            </a>
+            {timeline}
         </Panel>
     }
 }
+
+/// # A button that loads the saved snapshot, then restores it
+///
+/// Unlike [`Button`], this can't send a fixed action. The snapshot has to be
+/// loaded from the server first.
+#[component]
+fn RestoreSnapshotButton(actions: ActionsTx) -> impl IntoView {
+    let on_click = move |_| {
+        let actions = actions.clone();
+
+        leptos::task::spawn_local(async move {
+            match snapshot::load().await {
+                Ok(file) => {
+                    send_action(UserAction::RestoreSnapshot { file }, actions)
+                        .await;
+                }
+                Err(err) => {
+                    log::error!("Failed to load snapshot: {err:?}");
+                }
+            }
+        });
+    };
+
+    view! {
+        <input
+            type="button"
+            value="Restore Snapshot"
+            class="m-1 px-1 bg-gray-300 font-bold"
+            on:click=on_click />
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
         components::{
             active_functions::ActiveFunctions, control_panel::ControlPanel,
             memory_explorer::MemoryExplorer, stack_explorer::StackExplorer,
+            trace::Trace,
         },
         ActionsTx,
     },
@@ -37,11 +38,14 @@ pub fn Debugger(
         view! {
             <div>
                 <ControlPanel
+                    timeline=persistent.timeline
                     actions=actions.clone() />
                 <ActiveFunctions
                     active_functions=transient.active_functions
                     actions=actions.clone() />
                 {stack_explorer}
+                <Trace
+                    messages=persistent.trace />
                 {memory_explorer}
             </div>
         }
@@ -1,12 +1,13 @@
 use std::fmt::Write;
 
+use crosscut_compiler::code::syntax::MemberLocation;
 use crosscut_runtime::Effect;
 use leptos::{
     component,
-    ev::MouseEvent,
+    ev::{Event, MouseEvent},
     prelude::{
-        AnyView, ClassAttribute, CollectView, CustomAttribute, ElementChild,
-        IntoAny, OnAttribute,
+        event_target_value, AnyView, ClassAttribute, CollectView,
+        CustomAttribute, ElementChild, IntoAny, OnAttribute,
     },
     view,
     wasm_bindgen::JsCast,
@@ -16,8 +17,8 @@ use leptos::{
 
 use crate::{
     model::{
-        DebugFunction, DebugMember, DebugMemberData, DebugMemberKind,
-        DebugNamedFunction, DebugParameter, UserAction,
+        Breakpoint, DebugFunction, DebugMember, DebugMemberData,
+        DebugMemberKind, DebugNamedFunction, DebugParameter, UserAction,
     },
     ui::{actions::send_action, ActionsTx},
 };
@@ -115,6 +116,15 @@ fn Branch(
 pub fn Member(member: DebugMember, actions: ActionsTx) -> impl IntoView {
     let mut class_outer = String::from("py-1");
 
+    let gutter = member.data.durable_breakpoint.clone().map(|breakpoint| {
+        view! {
+            <BreakpointGutter
+                expression=member.data.location.clone()
+                breakpoint=breakpoint
+                actions=actions.clone() />
+        }
+    });
+
     let (expression, actions, error) = match member.kind {
         DebugMemberKind::Comment { lines } => {
             let lines = lines
@@ -138,6 +148,15 @@ pub fn Member(member: DebugMember, actions: ActionsTx) -> impl IntoView {
                 None,
             )
         }
+        DebugMemberKind::Error { as_string } => {
+            class_outer.push_str(" text-red-800 underline decoration-wavy");
+            make_single_member(
+                as_string,
+                member.data,
+                &mut class_outer,
+                actions,
+            )
+        }
         DebugMemberKind::Function { function } => (
             view! {
                 <Function
@@ -161,6 +180,7 @@ pub fn Member(member: DebugMember, actions: ActionsTx) -> impl IntoView {
 
     view! {
         <span>
+            {gutter}
             <span class=class_outer>
                 {expression}
             </span>
@@ -170,20 +190,111 @@ pub fn Member(member: DebugMember, actions: ActionsTx) -> impl IntoView {
     }
 }
 
+/// # The settings of a durable breakpoint, displayed next to its expression
+#[component]
+fn BreakpointGutter(
+    expression: MemberLocation,
+    breakpoint: Breakpoint,
+    actions: ActionsTx,
+) -> impl IntoView {
+    let configure = move |breakpoint: Breakpoint| {
+        leptos::task::spawn_local(send_action(
+            UserAction::BreakpointConfigure {
+                expression: expression.clone(),
+                breakpoint,
+            },
+            actions.clone(),
+        ));
+    };
+
+    let set_condition = {
+        let breakpoint = breakpoint.clone();
+        let configure = configure.clone();
+
+        move |event: Event| {
+            let mut breakpoint = breakpoint.clone();
+            breakpoint.condition = non_empty(event_target_value(&event));
+            configure(breakpoint);
+        }
+    };
+    let set_hit_count = {
+        let breakpoint = breakpoint.clone();
+        let configure = configure.clone();
+
+        move |event: Event| {
+            let mut breakpoint = breakpoint.clone();
+            // Anything that isn't a number means there's no threshold.
+            breakpoint.hit_count = event_target_value(&event).parse().ok();
+            configure(breakpoint);
+        }
+    };
+    let set_log_message = {
+        let breakpoint = breakpoint.clone();
+
+        move |event: Event| {
+            let mut breakpoint = breakpoint.clone();
+            breakpoint.log_message = non_empty(event_target_value(&event));
+            configure(breakpoint);
+        }
+    };
+
+    let condition = breakpoint.condition.unwrap_or_default();
+    let hit_count = breakpoint
+        .hit_count
+        .map(|hit_count| hit_count.to_string())
+        .unwrap_or_default();
+    let log_message = breakpoint.log_message.unwrap_or_default();
+
+    let class = "w-24 mr-1 px-1 bg-blue-100 text-sm";
+
+    view! {
+        <span class="mr-2">
+            <input
+                type="text"
+                class=class
+                placeholder="condition"
+                value=condition
+                on:change=set_condition />
+            <input
+                type="text"
+                class=class
+                placeholder="hit count"
+                value=hit_count
+                on:change=set_hit_count />
+            <input
+                type="text"
+                class=class
+                placeholder="log message"
+                value=log_message
+                on:change=set_log_message />
+        </span>
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.trim().is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
 fn make_single_member(
     expression: String,
     data: DebugMemberData,
     class_outer: &mut String,
     actions_tx: ActionsTx,
 ) -> (AnyView, Option<AnyView>, Option<AnyView>) {
-    if data.has_durable_breakpoint {
+    if data.durable_breakpoint.is_some() {
         class_outer.push_str(" bg-blue-300");
     }
 
     let mut class_inner = String::from("px-0.5");
     if let Some(effect) = &data.effect {
         match effect {
-            Effect::Breakpoint => class_inner.push_str(" bg-green-300"),
+            Effect::Breakpoint | Effect::BudgetExhausted => {
+                class_inner.push_str(" bg-green-300")
+            }
             _ => class_inner.push_str(" bg-red-300"),
         }
     }
@@ -194,7 +305,7 @@ fn make_single_member(
     let data_expression = ron::to_string(&data.location).expect(
         "Expecting serialization of `ExpressionLocation` to always work.",
     );
-    let data_breakpoint = data.has_durable_breakpoint;
+    let data_breakpoint = data.durable_breakpoint.is_some();
 
     let actions = if data.state.is_innermost_active_expression() {
         Some(
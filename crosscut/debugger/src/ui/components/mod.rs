@@ -6,3 +6,5 @@ pub mod function;
 pub mod memory_explorer;
 pub mod panel;
 pub mod stack_explorer;
+pub mod timeline;
+pub mod trace;
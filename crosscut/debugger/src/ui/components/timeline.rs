@@ -0,0 +1,48 @@
+use crosscut_game_engine::history;
+use leptos::{
+    component,
+    ev::Event,
+    prelude::{event_target_value, ClassAttribute, ElementChild, OnAttribute},
+    view, IntoView,
+};
+
+use crate::{
+    model::UserAction,
+    ui::{actions::send_action, ActionsTx},
+};
+
+#[component]
+pub fn Timeline(
+    timeline: history::Timeline,
+    actions: ActionsTx,
+) -> impl IntoView {
+    let frames = timeline.frames?;
+
+    let time_travel = move |event: Event| {
+        let Ok(frame) = event_target_value(&event).parse() else {
+            // The value of a range input is always a number, so this shouldn't
+            // happen. But if it does, there's nothing useful we can do.
+            return;
+        };
+
+        leptos::task::spawn_local(send_action(
+            UserAction::TimeTravel { frame },
+            actions.clone(),
+        ));
+    };
+
+    let current_frame = timeline.current_frame;
+
+    Some(view! {
+        <span class="m-1">
+            "Frame "{current_frame.to_string()}
+            <input
+                type="range"
+                class="mx-1 align-middle"
+                min=frames.start().to_string()
+                max=frames.end().to_string()
+                value=current_frame.to_string()
+                on:change=time_travel />
+        </span>
+    })
+}
@@ -0,0 +1,33 @@
+use leptos::{
+    component,
+    prelude::{ClassAttribute, CollectView, ElementChild},
+    view, IntoView,
+};
+
+use crate::ui::components::panel::Panel;
+
+/// # The messages that tracepoints have logged
+#[component]
+pub fn Trace(messages: Vec<String>) -> impl IntoView {
+    let messages = messages
+        .into_iter()
+        .map(|message| {
+            view! {
+                <li>{message}</li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <Panel class="max-h-32 overflow-y-auto">
+            <div>
+                <p>
+                    "Trace:"
+                </p>
+                <ol class="font-mono">
+                    {messages}
+                </ol>
+            </div>
+        </Panel>
+    }
+}
@@ -0,0 +1,94 @@
+//! # Benchmark the evaluation of the Snake game
+//!
+//! Runs a fixed number of frames as fast as possible, without waiting for the
+//! next frame to be due, and prints how long that took. The game is seeded, so
+//! every run does the same work.
+//!
+//! Run with `cargo bench -p crosscut-game-engine`.
+
+use std::{
+    convert::Infallible,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crosscut_compiler::{
+    sources::{search_path, SourceFile, Sources},
+    Compiler,
+};
+use crosscut_game_engine::{
+    command::Command, display::NUM_PIXEL_BYTES, game_engine::GameEngine,
+    host::GameEngineHost,
+};
+
+const NUM_FRAMES: u32 = 1000;
+const NUM_RUNS: u32 = 5;
+
+fn main() {
+    let game_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../games/snake")
+        .canonicalize()
+        .expect("Snake game should exist within the repository");
+
+    let path = game_dir.join("main.capi");
+    let code = fs::read_to_string(&path).unwrap();
+    let mut sources = Sources::new(SourceFile::main(path, code));
+    let Ok(_) = sources.load_imports(&search_path(&game_dir), |path| {
+        Ok::<_, Infallible>(fs::read_to_string(path).ok())
+    });
+
+    let code = Compiler::default().compile_sources(&sources, &GameEngineHost);
+    assert!(
+        !code.diagnostics.has_errors(),
+        "Snake should compile without errors",
+    );
+
+    let times = (0..NUM_RUNS)
+        .map(|_| {
+            let mut game_engine = GameEngine::new();
+            game_engine.on_command(Command::UpdateCode {
+                instructions: code.instructions.clone(),
+            });
+            game_engine.on_command(Command::SetSeed { seed: 0 });
+
+            run_frames(&mut game_engine)
+        })
+        .collect::<Vec<_>>();
+
+    let min = times.iter().min().unwrap();
+    let max = times.iter().max().unwrap();
+
+    println!(
+        "snake: {NUM_FRAMES} frames; \
+        min: {:.3} ms/frame; max: {:.3} ms/frame",
+        per_frame_ms(*min),
+        per_frame_ms(*max),
+    );
+}
+
+fn run_frames(game_engine: &mut GameEngine) -> Duration {
+    let mut pixels = [0; NUM_PIXEL_BYTES];
+
+    let start = Instant::now();
+
+    for frame in 0..NUM_FRAMES {
+        // Pretend that the game is running behind by a frame. This way, the
+        // game engine runs the next frame right away, instead of waiting for
+        // it to be due.
+        let current_time_s = f64::from(frame) * 2. / 30.;
+
+        while !game_engine.run_until_end_of_frame(current_time_s, &mut pixels) {
+            assert!(
+                game_engine.runtime.effect().inspect().is_none(),
+                "Snake should not trigger an unhandled effect",
+            );
+        }
+    }
+
+    start.elapsed()
+}
+
+fn per_frame_ms(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000. / f64::from(NUM_FRAMES)
+}
@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crosscut_runtime::{Instruction, InstructionAddress, Value};
+
+use crate::expression;
+
+/// # The breakpoints that the game engine needs to know about
+///
+/// The breakpoints themselves are part of the code that the game engine runs.
+/// This is what it needs to decide, whether a breakpoint that was hit actually
+/// stops the process.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct Breakpoints {
+    pub durable: BTreeMap<InstructionAddress, DurableBreakpoint>,
+
+    /// # Breakpoints that always stop the process
+    ///
+    /// The debugger sets these when stepping. They take precedence over any
+    /// durable breakpoint at the same instruction.
+    pub ephemeral: BTreeSet<InstructionAddress>,
+}
+
+/// # A durable breakpoint, with everything that is needed to evaluate it
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct DurableBreakpoint {
+    pub settings: Breakpoint,
+
+    /// # The instruction that the breakpoint replaces in the code
+    ///
+    /// If the breakpoint doesn't stop the process, the game engine evaluates
+    /// this instruction instead.
+    pub instruction: Instruction,
+
+    /// # The names of the bindings that are available at the breakpoint
+    ///
+    /// Ordered by the slot that stores the respective binding's value.
+    pub bindings: Vec<String>,
+}
+
+impl DurableBreakpoint {
+    /// # Decide whether this breakpoint stops the process
+    ///
+    /// Updates the number of times the breakpoint has been hit. Adds any
+    /// message that it logs to `trace`.
+    pub fn stops(
+        &self,
+        hits: &mut u32,
+        slots: &[Value],
+        trace: &mut Vec<String>,
+    ) -> bool {
+        let bindings = self
+            .bindings
+            .iter()
+            .cloned()
+            .zip(slots.iter().copied())
+            .collect::<Vec<_>>();
+
+        if let Some(condition) = &self.settings.condition {
+            match expression::evaluate(condition, &bindings) {
+                Ok(value) => {
+                    if value.to_i32() == 0 {
+                        return false;
+                    }
+                }
+                Err(err) => {
+                    // Stop, so the developer notices that something's wrong.
+                    trace.push(format!(
+                        "Failed to evaluate condition `{condition}`: {err}"
+                    ));
+                    return true;
+                }
+            }
+        }
+
+        *hits += 1;
+        if let Some(hit_count) = self.settings.hit_count {
+            if *hits < hit_count {
+                return false;
+            }
+        }
+
+        if let Some(message) = &self.settings.log_message {
+            trace.push(expression::interpolate(message, &bindings));
+            return false;
+        }
+
+        true
+    }
+}
+
+/// # The settings of a durable breakpoint
+///
+/// By default, a breakpoint stops the process every time it is hit. The
+/// settings can restrict that, or turn the breakpoint into a tracepoint.
+#[derive(
+    Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct Breakpoint {
+    /// # Only stop, if this expression evaluates to a non-zero value
+    ///
+    /// See [`expression::evaluate`] for what the expression can contain.
+    pub condition: Option<String>,
+
+    /// # Only stop, once the breakpoint has been hit this many times
+    ///
+    /// Hits where the condition doesn't hold don't count.
+    pub hit_count: Option<u32>,
+
+    /// # Log this message instead of stopping
+    ///
+    /// This turns the breakpoint into a tracepoint. Expressions in curly braces
+    /// are replaced by their values.
+    pub log_message: Option<String>,
+}
+
+impl Breakpoint {
+    /// # Determine whether this is a tracepoint, which never stops the process
+    pub fn is_tracepoint(&self) -> bool {
+        self.log_message.is_some()
+    }
+}
@@ -1,10 +1,72 @@
 use crosscut_compiler::Instructions;
 
-#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+use crate::{breakpoints::Breakpoints, history::Snapshot};
+
+#[allow(clippy::large_enum_variant)] // haven't optimized this yet
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum Command {
     ClearBreakpointAndContinue,
     ClearBreakpointAndEvaluateNextInstruction,
     Reset,
+
+    /// # Replace the game engine's state with a previously saved snapshot
+    ///
+    /// The process stays stopped after the snapshot has been restored.
+    RestoreSnapshot {
+        snapshot: Snapshot,
+    },
+
+    /// # Go back to the last time the process stopped at a breakpoint
+    ///
+    /// Only considers durable breakpoints that aren't tracepoints. Stops at the
+    /// earliest point in the history, if the process didn't stop at any of
+    /// them.
+    ReverseContinue,
+
+    /// # Save a snapshot of the game engine's current state
+    ///
+    /// The snapshot can be retrieved using `GameEngine::take_saved_snapshot`.
+    SaveSnapshot,
+
+    /// # Seed the game engine's random number generator
+    ///
+    /// The same seed always results in the same sequence of random numbers.
+    /// Resetting the game restarts that sequence from the beginning.
+    SetSeed {
+        seed: u64,
+    },
+
+    /// # Reset the game, then start recording the session
+    ///
+    /// Resetting makes sure that the recording can be replayed. See
+    /// `GameEngine::start_recording`.
+    StartRecording,
+
+    /// # Go back to the previous time the process stopped
+    StepBack,
+
     Stop,
-    UpdateCode { instructions: Instructions },
+
+    /// # Stop recording the session
+    ///
+    /// The recording can be retrieved using
+    /// `GameEngine::take_finished_recording`.
+    StopRecording,
+
+    /// # Go back to the start of the given frame
+    TimeTravel {
+        frame: u64,
+    },
+
+    /// # Tell the game engine which breakpoints the code contains
+    ///
+    /// The game engine uses this to evaluate the settings of durable
+    /// breakpoints itself. A hit that shouldn't stop the process never does.
+    UpdateBreakpoints {
+        breakpoints: Breakpoints,
+    },
+
+    UpdateCode {
+        instructions: Instructions,
+    },
 }
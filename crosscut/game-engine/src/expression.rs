@@ -0,0 +1,109 @@
+use anyhow::anyhow;
+use crosscut_compiler::{intrinsics::IntrinsicFunction, Instructions};
+use crosscut_runtime::{Instruction, Runtime, Value};
+
+/// # Evaluate an expression that the developer entered into the debugger
+///
+/// This is used for the conditions of breakpoints, and to fill in the messages
+/// of tracepoints.
+///
+/// The expression is a sequence of Crosscut expressions, separated by
+/// whitespace. Integer literals, the names of the bindings that are available
+/// in the active branch, and intrinsic functions that operate on values are
+/// supported. The result is the value that ends up on top of the stack.
+///
+/// ## Implementation Note
+///
+/// The expression is compiled into instructions and evaluated by a runtime of
+/// its own, so the intrinsic functions behave exactly as they do in the game.
+/// That runtime starts out with an empty stack. Whatever the game has on its
+/// stack is out of reach, so an expression can't depend on (or mess with)
+/// values that happen to be there.
+///
+/// Since the values of bindings are already known, there's no need to set up
+/// any slots. The compiled code just pushes them.
+pub fn evaluate(
+    source: &str,
+    bindings: &[(String, Value)],
+) -> anyhow::Result<Value> {
+    let mut instructions = Instructions::default();
+
+    for token in source.split_whitespace() {
+        for instruction in compile_token(token, bindings)? {
+            instructions.push(instruction);
+        }
+    }
+    instructions.push(Instruction::Return);
+
+    let mut runtime = Runtime::default();
+
+    // There are no jumps in the compiled code, so every instruction gets
+    // evaluated once at most.
+    let mut budget = u32::MAX;
+    runtime.run(instructions.to_runtime_instructions(), &mut budget);
+
+    if let Some(effect) = runtime.effect().inspect() {
+        return Err(anyhow!("{effect}"));
+    }
+
+    runtime
+        .return_values()
+        .and_then(|values| values.last())
+        .ok_or_else(|| anyhow!("Expression did not result in a value"))
+}
+
+/// # Fill in the expressions in a tracepoint's message
+///
+/// Every expression in curly braces is replaced by the value that it evaluates
+/// to. See [`evaluate`].
+pub fn interpolate(message: &str, bindings: &[(String, Value)]) -> String {
+    let mut output = String::new();
+    let mut rest = message;
+
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        output.push_str(&rest[..start]);
+
+        let source = &rest[start + 1..start + end];
+        match evaluate(source, bindings) {
+            Ok(value) => output.push_str(&value.to_string()),
+            Err(err) => output.push_str(&format!("<{err}>")),
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn compile_token(
+    token: &str,
+    bindings: &[(String, Value)],
+) -> anyhow::Result<Vec<Instruction>> {
+    let value = token
+        .parse::<i32>()
+        .map(Value::from)
+        .or_else(|_| token.parse::<u32>().map(Value::from));
+    if let Ok(value) = value {
+        return Ok(vec![Instruction::Push { value }]);
+    }
+
+    if let Some((_, value)) = bindings.iter().find(|(name, _)| name == token) {
+        return Ok(vec![Instruction::Push { value: *value }]);
+    }
+
+    let Some(intrinsic) = IntrinsicFunction::from_name(token) else {
+        return Err(anyhow!("Unknown identifier `{token}`"));
+    };
+
+    if let IntrinsicFunction::Brk | IntrinsicFunction::Eval = intrinsic {
+        return Err(anyhow!("`{token}` can't be used in an expression"));
+    }
+
+    // The values that the expression operates on are single runtime values.
+    Ok(intrinsic.instructions(1, false))
+}
@@ -1,44 +1,92 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    mem,
+};
 
-use crosscut_compiler::Instructions;
-use crosscut_runtime::{Effect, Heap, Runtime, Value};
+use crosscut_compiler::{code::Hash, Instructions};
+use crosscut_runtime::{
+    Effect, Instruction, InstructionAddress, Runtime, StackLimits, Value,
+};
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha8Rng,
+};
 
 use crate::{
+    breakpoints::Breakpoints,
     command::Command,
     display::{self, TILES_PER_AXIS},
+    history::{History, HistoryEntryKind, Snapshot, Timeline},
     host::GameEngineFunction,
     memory::Memory,
+    recording::{FrameHashes, RecordedEvent},
 };
 
+/// # The number of instructions to evaluate per call to run a frame
+///
+/// If a frame isn't done after that many instructions, the game engine yields
+/// and continues the frame on the next call.
+const INSTRUCTIONS_PER_CALL: u32 = 1_000_000;
+
+/// # The number of instructions after which a frame is considered stuck
+///
+/// If a frame isn't done by then, the game engine stops the process by
+/// triggering [`Effect::BudgetExhausted`].
+const INSTRUCTIONS_PER_FRAME: u32 = 100_000_000;
+
 #[derive(Debug)]
 pub struct GameEngine {
     pub runtime: Runtime,
 
     arguments: [Value; 2],
     last_frame_start_s: Option<f64>,
+    frame: u64,
+    frame_in_progress: bool,
+    instructions_this_frame: u32,
+    history: History,
     instructions: Option<Instructions>,
-    heap: Heap,
+    breakpoints: Breakpoints,
+    hits: BTreeMap<InstructionAddress, u32>,
+    trace: Vec<String>,
     memory: Memory,
     input: VecDeque<u8>,
-    random: VecDeque<i32>,
+    seed: u64,
+    random: ChaCha8Rng,
+    recording: Option<Vec<RecordedEvent>>,
+    finished_recording: Option<Vec<RecordedEvent>>,
+    saved_snapshot: Option<Snapshot>,
 }
 
 impl GameEngine {
     pub fn new() -> Self {
+        Self::with_stack_limits(StackLimits::default())
+    }
+
+    pub fn with_stack_limits(limits: StackLimits) -> Self {
         let arguments = [Value::from(TILES_PER_AXIS); 2];
 
-        let mut runtime = Runtime::default();
+        let mut runtime = Runtime::with_stack_limits(limits);
         runtime.reset(arguments);
 
         Self {
             runtime,
             arguments,
             last_frame_start_s: None,
+            frame: 0,
+            frame_in_progress: false,
+            instructions_this_frame: 0,
+            history: History::default(),
             instructions: None,
-            heap: Heap::default(),
+            breakpoints: Breakpoints::default(),
+            hits: BTreeMap::new(),
+            trace: Vec::new(),
             memory: Memory::default(),
             input: VecDeque::new(),
-            random: VecDeque::new(),
+            seed: 0,
+            random: ChaCha8Rng::seed_from_u64(0),
+            recording: None,
+            finished_recording: None,
+            saved_snapshot: None,
         }
     }
 
@@ -46,8 +94,148 @@ impl GameEngine {
         &self.memory
     }
 
+    /// # Describe which frames the game engine can travel back to
+    pub fn timeline(&self) -> Timeline {
+        self.history.timeline(self.frame)
+    }
+
+    /// # Take the snapshot that was saved by the last `SaveSnapshot` command
+    ///
+    /// Returns `None`, if no snapshot was saved since the last call.
+    pub fn take_saved_snapshot(&mut self) -> Option<Snapshot> {
+        self.saved_snapshot.take()
+    }
+
+    /// # Take the messages that tracepoints have logged since the last call
+    pub fn take_trace(&mut self) -> Vec<String> {
+        mem::take(&mut self.trace)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            runtime: self.runtime.clone(),
+            memory: self.memory.clone(),
+            input: self.input.clone(),
+            seed: self.seed,
+            random: self.random.clone(),
+            frame: self.frame,
+            frame_in_progress: self.frame_in_progress,
+            instructions_this_frame: self.instructions_this_frame,
+            hits: self.hits.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            runtime,
+            memory,
+            input,
+            seed,
+            random,
+            frame,
+            frame_in_progress,
+            instructions_this_frame,
+            hits,
+        } = snapshot;
+
+        self.runtime = runtime;
+        self.memory = memory;
+        self.input = input;
+        self.seed = seed;
+        self.random = random;
+        self.frame = frame;
+        self.frame_in_progress = frame_in_progress;
+        self.instructions_this_frame = instructions_this_frame;
+        self.hits = hits;
+    }
+
+    /// # Restore a snapshot, keeping the process stopped
+    ///
+    /// The developer went back to look at this state. It would be
+    /// rather confusing, if the process just ran off from there.
+    fn travel_to(&mut self, snapshot: Snapshot) {
+        self.restore(snapshot);
+
+        self.runtime
+            .effect_mut()
+            .trigger(Effect::Breakpoint)
+            // If the snapshot was taken while the process was stopped, there
+            // already is a triggered effect. That's fine too.
+            .ignore();
+    }
+
+    /// # Add the current state to the history, if the process just stopped
+    fn update_history(&mut self) {
+        if self.runtime.effect().inspect().is_none() {
+            return;
+        }
+
+        let snapshot = self.snapshot();
+
+        if let Some(latest) = self.history.latest() {
+            if let HistoryEntryKind::Stopped = latest.kind {
+                if latest.snapshot == snapshot {
+                    // We already know about this stop.
+                    return;
+                }
+            }
+        }
+
+        self.history.push(HistoryEntryKind::Stopped, snapshot);
+    }
+
+    /// # Access a hash of the current code
+    ///
+    /// Returns `None`, if no code has been provided yet.
+    pub fn code_version(&self) -> Option<Hash<Instructions>> {
+        self.instructions.as_ref().map(Hash::new)
+    }
+
+    /// # Start recording the session
+    ///
+    /// From now on, the game engine records everything that influences the
+    /// game, and everything that comes out of it: code updates, input, the
+    /// random seed, commands from the debugger, and the end of every frame.
+    /// The recorded events can be retrieved using
+    /// [`GameEngine::take_recorded_events`], and replayed using
+    /// [`Replay`](crate::recording::Replay).
+    ///
+    /// A recording is only going to replay correctly, if it starts with a
+    /// freshly reset game engine.
+    pub fn start_recording(&mut self) {
+        let mut events = Vec::new();
+
+        if let Some(version) = self.code_version() {
+            events.push(RecordedEvent::UpdateCode { version });
+        }
+        events.push(RecordedEvent::SetSeed { seed: self.seed });
+
+        self.recording = Some(events);
+    }
+
+    /// # Take the events that have been recorded since the last call
+    ///
+    /// Returns an empty `Vec`, if the session isn't being recorded.
+    pub fn take_recorded_events(&mut self) -> Vec<RecordedEvent> {
+        self.recording.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    /// # Take the recording that was finished by the last `StopRecording`
+    ///
+    /// Returns `None`, if no recording was finished since the last call.
+    pub fn take_finished_recording(&mut self) -> Option<Vec<RecordedEvent>> {
+        self.finished_recording.take()
+    }
+
+    fn record(&mut self, event: RecordedEvent) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(event);
+        }
+    }
+
     pub fn on_input(&mut self, value: u8) {
         self.input.push_back(value);
+        self.record(RecordedEvent::Input { value });
     }
 
     pub fn on_command(&mut self, command: Command) {
@@ -55,13 +243,33 @@ impl GameEngine {
             self.memory = Memory::default();
         }
 
+        match &command {
+            Command::Reset
+            | Command::SetSeed { .. }
+            | Command::StartRecording
+            | Command::StopRecording => {
+                // These commands are either recorded as their own events, or
+                // don't influence the game at all.
+            }
+            command => {
+                self.record(RecordedEvent::Command {
+                    command: command.clone(),
+                });
+            }
+        }
+
         match command {
             Command::ClearBreakpointAndContinue => {
-                if let Some(Effect::Breakpoint) =
+                if let Some(Effect::Breakpoint | Effect::BudgetExhausted) =
                     self.runtime.effect_mut().inspect()
                 {
                     self.runtime.effect_mut().handle();
                 }
+
+                // If the process was stopped because the current frame took
+                // too long, the developer has decided to let it keep going.
+                // Give it a fresh budget.
+                self.instructions_this_frame = 0;
             }
             Command::ClearBreakpointAndEvaluateNextInstruction => {
                 if let Some(Effect::Breakpoint) =
@@ -80,7 +288,6 @@ impl GameEngine {
                 if let Some(instructions) = &self.instructions {
                     self.runtime.evaluate_next_instruction(
                         instructions.to_runtime_instructions(),
-                        &mut self.heap,
                     );
                 } else {
                     // Same as above: This should only happen if the debugger is
@@ -89,6 +296,71 @@ impl GameEngine {
             }
             Command::Reset => {
                 self.runtime.reset(self.arguments);
+                self.frame = 0;
+                self.frame_in_progress = false;
+                self.instructions_this_frame = 0;
+                self.hits.clear();
+                self.history.clear();
+                self.random = ChaCha8Rng::seed_from_u64(self.seed);
+                self.record(RecordedEvent::Reset);
+            }
+            Command::RestoreSnapshot { snapshot } => {
+                // The history describes how we got to the current state. It
+                // has nothing to do with the state we're restoring.
+                self.history.clear();
+                self.travel_to(snapshot);
+            }
+            Command::ReverseContinue => {
+                let current = self.snapshot();
+                let breakpoints = self
+                    .breakpoints
+                    .durable
+                    .iter()
+                    .filter(|(_, breakpoint)| {
+                        !breakpoint.settings.is_tracepoint()
+                    })
+                    .map(|(address, _)| *address)
+                    .collect::<Vec<_>>();
+
+                let snapshot = self
+                    .history
+                    .rewind(|entry| {
+                        entry.is_stopped_at(&breakpoints)
+                            && entry.snapshot != current
+                    })
+                    .or_else(|| {
+                        // There's no earlier breakpoint we could stop at. Go
+                        // back as far as we can.
+                        self.history.rewind_to_start()
+                    });
+
+                if let Some(snapshot) = snapshot {
+                    self.travel_to(snapshot);
+                }
+            }
+            Command::SaveSnapshot => {
+                self.saved_snapshot = Some(self.snapshot());
+            }
+            Command::SetSeed { seed } => {
+                self.seed = seed;
+                self.random = ChaCha8Rng::seed_from_u64(seed);
+                self.record(RecordedEvent::SetSeed { seed });
+            }
+            Command::StartRecording => {
+                self.on_command(Command::Reset);
+                self.start_recording();
+            }
+            Command::StepBack => {
+                let current = self.snapshot();
+
+                let snapshot = self.history.rewind(|entry| {
+                    matches!(entry.kind, HistoryEntryKind::Stopped)
+                        && entry.snapshot != current
+                });
+
+                if let Some(snapshot) = snapshot {
+                    self.travel_to(snapshot);
+                }
             }
             Command::Stop => {
                 self.runtime
@@ -103,36 +375,40 @@ impl GameEngine {
                     // will learn about the specifics soon enough.
                     .ignore();
             }
+            Command::StopRecording => {
+                self.finished_recording = self.recording.take();
+            }
+            Command::TimeTravel { frame } => {
+                let snapshot = self.history.rewind(|entry| {
+                    matches!(entry.kind, HistoryEntryKind::StartOfFrame)
+                        && entry.snapshot.frame == frame
+                });
+
+                if let Some(snapshot) = snapshot {
+                    self.travel_to(snapshot);
+                }
+            }
+            Command::UpdateBreakpoints { breakpoints } => {
+                // Hits only count towards the settings they happened with.
+                self.hits.retain(|address, _| {
+                    let previous = self.breakpoints.durable.get(address);
+                    let current = breakpoints.durable.get(address);
+
+                    previous.map(|breakpoint| &breakpoint.settings)
+                        == current.map(|breakpoint| &breakpoint.settings)
+                });
+
+                self.breakpoints = breakpoints;
+            }
             Command::UpdateCode { instructions } => {
                 self.instructions = Some(instructions);
             }
         }
-    }
-
-    /// # Top off the game engine's random numbers
-    ///
-    /// Whatever code embeds `GameEngine` is expected to call this in a loop
-    /// every frame, until this function returns `false`.
-    ///
-    /// ## Implementation Note
-    ///
-    /// It would make a lot more sense to expect a random seed in the
-    /// constructor, and have a PRNG to get more randomness as needed.
-    ///
-    /// But this works too, and it's fine for now. Ideally, I would like to
-    /// write the PRNG in Crosscut, and it's a bit too early for that. I'm not
-    /// in hurry to replace this with a Rust-based solution right now.
-    pub fn push_random(&mut self, value: i32) -> bool {
-        // If games grow complex enough to need more than this many random
-        // numbers per frame, we can increase this limit. But hopefully, we'll
-        // have a PRNG by then.
-        if self.random.len() >= 1024 {
-            return false;
-        }
 
-        self.random.push_back(value);
-
-        true
+        // If the command stopped the process somewhere new, that needs to be
+        // reflected in the history right away. Otherwise, a replay of the
+        // recorded commands wouldn't necessarily see the same history.
+        self.update_history();
     }
 
     pub fn run_until_end_of_frame(
@@ -140,50 +416,74 @@ impl GameEngine {
         current_time_s: f64,
         pixels: &mut [u8],
     ) -> bool {
-        // For now, we're targeting an unambitious 30 fps.
-        let frame_time_s = 1. / 30.;
+        let was_stopped = self.runtime.effect().inspect().is_some();
 
-        if let Some(last_frame_start_s) = self.last_frame_start_s {
-            let time_since_last_frame_start_s =
-                current_time_s - last_frame_start_s;
+        let finished_frame = self.run_frame(current_time_s, pixels);
 
-            if time_since_last_frame_start_s >= frame_time_s * 2. {
-                // It's time for another frame, but it seems that has been true
-                // for a while. This could mean that the game was paused, or
-                // that we're running too slow, getting behind on frames.
-                //
-                // Either way, we don't want to burn the CPU, trying to catch
-                // up.
-                self.last_frame_start_s = Some(current_time_s);
-            } else if time_since_last_frame_start_s >= frame_time_s {
-                // It's time for another frame, and we don't seem to be getting
-                // behind.
-                //
-                // In this case, don't remember the current time as the start
-                // time of the frame, but instead just advance that by the
-                // nominal frame time. This way, any timing inaccuracies in
-                // calling this function should get smoothed out a bit, on
-                // average.
-                self.last_frame_start_s =
-                    Some(last_frame_start_s + frame_time_s);
-            } else {
-                // It's not time for another frame yet!
+        // While the process stays stopped, nothing runs, so there's nothing
+        // new to add to the history. Commands that change anything update the
+        // history themselves. Checking only when the process just stopped
+        // avoids taking and comparing a snapshot on every call.
+        if !was_stopped && self.runtime.effect().inspect().is_some() {
+            self.update_history();
+            self.record(RecordedEvent::Stop);
+        }
+
+        finished_frame
+    }
+
+    fn run_frame(&mut self, current_time_s: f64, pixels: &mut [u8]) -> bool {
+        if !self.frame_in_progress {
+            if !self.is_time_for_next_frame(current_time_s) {
                 return false;
             }
-        } else {
-            // This seems to be the first frame. Just run it immediately.
-            self.last_frame_start_s = Some(current_time_s);
+
+            self.frame_in_progress = true;
+            self.instructions_this_frame = 0;
+
+            if self.runtime.state().is_running() {
+                self.history
+                    .push(HistoryEntryKind::StartOfFrame, self.snapshot());
+            }
         }
 
+        let mut budget = INSTRUCTIONS_PER_CALL;
+
         while self.runtime.state().is_running() {
             let Some(instructions) = &self.instructions else {
                 return true;
             };
 
-            self.runtime.evaluate_next_instruction(
-                instructions.to_runtime_instructions(),
-                &mut self.heap,
-            );
+            if budget == 0 {
+                if self.instructions_this_frame >= INSTRUCTIONS_PER_FRAME {
+                    // The game has been working on this frame for much longer
+                    // than it should. Maybe it's stuck in an endless loop.
+                    // Stop it, so the developer can find out what's going on.
+                    self.runtime
+                        .effect_mut()
+                        .trigger(Effect::BudgetExhausted)
+                        // The runtime is running, so there can't be a triggered
+                        // effect.
+                        .assert_triggered();
+                    return true;
+                }
+
+                // The frame isn't done yet, but we've evaluated enough
+                // instructions for now. Yield, so whoever is embedding us gets
+                // a chance to stay responsive. We'll pick up where we left off
+                // on the next call.
+                self.record(RecordedEvent::Yield);
+                return false;
+            }
+
+            let budget_before = budget;
+            self.runtime
+                .run(instructions.to_runtime_instructions(), &mut budget);
+            self.instructions_this_frame += budget_before - budget;
+
+            if let Some(Effect::Breakpoint) = self.runtime.effect().inspect() {
+                self.pass_breakpoint();
+            }
 
             if let Some(effect) = self.runtime.effect_mut().handle() {
                 match self.handle_effect(&effect, pixels) {
@@ -192,6 +492,13 @@ impl GameEngine {
                     }
                     Ok(EffectOutcome::WasSubmit) => {
                         self.runtime.ignore_next_instruction();
+                        self.frame += 1;
+                        self.frame_in_progress = false;
+
+                        if self.recording.is_some() {
+                            let hashes = FrameHashes::new(&self.memory, pixels);
+                            self.record(RecordedEvent::EndOfFrame { hashes });
+                        }
 
                         // The game is done rendering. This is our sign to break
                         // out of this loop.
@@ -225,6 +532,87 @@ impl GameEngine {
         true
     }
 
+    /// # Move past a durable breakpoint, if it shouldn't stop the process
+    ///
+    /// Evaluates the instruction that the breakpoint replaced in its stead. If
+    /// that triggers an effect, it is left for the caller to handle, like any
+    /// other.
+    fn pass_breakpoint(&mut self) {
+        let address = self.runtime.evaluator().next_instruction;
+
+        if self.breakpoints.ephemeral.contains(&address) {
+            return;
+        }
+        let Some(breakpoint) = self.breakpoints.durable.get(&address) else {
+            return;
+        };
+        let Some(instructions) = &mut self.instructions else {
+            return;
+        };
+
+        let stops = breakpoint.stops(
+            self.hits.entry(address).or_default(),
+            self.runtime.stack().current_slots(),
+            &mut self.trace,
+        );
+        if stops {
+            return;
+        }
+
+        self.runtime.effect_mut().handle();
+
+        instructions.replace(&address, breakpoint.instruction.clone());
+        self.runtime
+            .evaluate_next_instruction(instructions.to_runtime_instructions());
+        instructions.replace(
+            &address,
+            Instruction::TriggerEffect {
+                effect: Effect::Breakpoint,
+            },
+        );
+
+        self.instructions_this_frame += 1;
+    }
+
+    fn is_time_for_next_frame(&mut self, current_time_s: f64) -> bool {
+        // For now, we're targeting an unambitious 30 fps.
+        let frame_time_s = 1. / 30.;
+
+        if let Some(last_frame_start_s) = self.last_frame_start_s {
+            let time_since_last_frame_start_s =
+                current_time_s - last_frame_start_s;
+
+            if time_since_last_frame_start_s >= frame_time_s * 2. {
+                // It's time for another frame, but it seems that has been true
+                // for a while. This could mean that the game was paused, or
+                // that we're running too slow, getting behind on frames.
+                //
+                // Either way, we don't want to burn the CPU, trying to catch
+                // up.
+                self.last_frame_start_s = Some(current_time_s);
+            } else if time_since_last_frame_start_s >= frame_time_s {
+                // It's time for another frame, and we don't seem to be getting
+                // behind.
+                //
+                // In this case, don't remember the current time as the start
+                // time of the frame, but instead just advance that by the
+                // nominal frame time. This way, any timing inaccuracies in
+                // calling this function should get smoothed out a bit, on
+                // average.
+                self.last_frame_start_s =
+                    Some(last_frame_start_s + frame_time_s);
+            } else {
+                // It's not time for another frame yet!
+                return false;
+            }
+        } else {
+            // This seems to be the first frame. Just run it immediately.
+            self.last_frame_start_s = Some(current_time_s);
+        }
+
+        true
+    }
+
     fn handle_effect(
         &mut self,
         effect: &Effect,
@@ -259,7 +647,7 @@ impl GameEngine {
                 let address: usize = address.into();
                 let value = self.memory.inner[address];
 
-                self.runtime.stack_mut().push_operand(value);
+                self.runtime.stack_mut().push_operand(value)?;
             }
             GameEngineFunction::Store => {
                 let address = self.runtime.stack_mut().pop_operand()?;
@@ -273,12 +661,11 @@ impl GameEngine {
             }
             GameEngineFunction::ReadInput => {
                 let input = self.input.pop_front().unwrap_or(0);
-                self.runtime.stack_mut().push_operand(input);
+                self.runtime.stack_mut().push_operand(input)?;
             }
             GameEngineFunction::ReadRandom => {
-                // See `GameEngine::push_random` for context.
-                let random = self.random.pop_front().unwrap();
-                self.runtime.stack_mut().push_operand(random);
+                let random = self.random.next_u32() as i32;
+                self.runtime.stack_mut().push_operand(random)?;
             }
             GameEngineFunction::SetPixel => {
                 let a = self.runtime.stack_mut().pop_operand()?;
@@ -0,0 +1,152 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeInclusive,
+};
+
+use crosscut_runtime::{InstructionAddress, Runtime};
+use rand_chacha::ChaCha8Rng;
+
+use crate::memory::Memory;
+
+/// # A bounded history of the game engine's past states
+///
+/// The game engine adds an entry at the start of every frame, and whenever the
+/// process stops. This allows the developer to go back in time, to look at an
+/// earlier state of the process, and to run it again from there.
+///
+/// ## Implementation Note
+///
+/// Every entry is a full copy of the game engine's state. That is simple and
+/// works well enough, as long as that state stays small. If it grows, storing
+/// the differences between entries might become necessary.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl History {
+    /// # The maximum number of entries that the history keeps
+    ///
+    /// At 30 frames per second, that's a bit over 15 seconds, if the process
+    /// isn't stopped in between.
+    const MAX_ENTRIES: usize = 512;
+
+    pub fn push(&mut self, kind: HistoryEntryKind, snapshot: Snapshot) {
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(HistoryEntry { kind, snapshot });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// # Access the most recent entry
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.entries.back()
+    }
+
+    /// # Go back to the most recent entry that matches the predicate
+    ///
+    /// Removes all entries that are more recent than the one that matches,
+    /// since they represent a future that the process is going to replace, once
+    /// it continues. Leaves the matching entry in place.
+    ///
+    /// Returns `None` and leaves the history unchanged, if no entry matches.
+    pub fn rewind(
+        &mut self,
+        predicate: impl Fn(&HistoryEntry) -> bool,
+    ) -> Option<Snapshot> {
+        let index = self.entries.iter().rposition(predicate)?;
+        self.entries.truncate(index + 1);
+
+        self.entries.back().map(|entry| entry.snapshot.clone())
+    }
+
+    /// # Go back to the oldest entry
+    ///
+    /// Like [`History::rewind`], this removes all entries that are more recent.
+    pub fn rewind_to_start(&mut self) -> Option<Snapshot> {
+        self.entries.truncate(1);
+        self.entries.front().map(|entry| entry.snapshot.clone())
+    }
+
+    /// # Describe which frames the history covers
+    pub fn timeline(&self, current_frame: u64) -> Timeline {
+        let mut frames = self.entries.iter().filter_map(|entry| {
+            if let HistoryEntryKind::StartOfFrame = entry.kind {
+                Some(entry.snapshot.frame)
+            } else {
+                None
+            }
+        });
+
+        let first = frames.next();
+        let last = frames.next_back().or(first);
+
+        Timeline {
+            frames: first.zip(last).map(|(first, last)| first..=last),
+            current_frame,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub kind: HistoryEntryKind,
+    pub snapshot: Snapshot,
+}
+
+impl HistoryEntry {
+    /// # Determine whether the process is stopped at the given instruction
+    pub fn is_stopped_at(&self, instructions: &[InstructionAddress]) -> bool {
+        let HistoryEntryKind::Stopped = self.kind else {
+            return false;
+        };
+
+        instructions
+            .contains(&self.snapshot.runtime.evaluator().next_instruction)
+    }
+}
+
+#[derive(Debug)]
+pub enum HistoryEntryKind {
+    /// # The entry was made at the start of a frame
+    StartOfFrame,
+
+    /// # The entry was made when the process stopped
+    Stopped,
+}
+
+/// # A copy of the game engine's state at a specific point in time
+///
+/// This contains everything that determines how the game continues, except for
+/// its code.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Snapshot {
+    pub runtime: Runtime,
+    pub memory: Memory,
+    pub input: VecDeque<u8>,
+    pub seed: u64,
+    pub random: ChaCha8Rng,
+    pub frame: u64,
+    pub frame_in_progress: bool,
+    pub instructions_this_frame: u32,
+
+    /// # How often each durable breakpoint has been hit
+    pub hits: BTreeMap<InstructionAddress, u32>,
+}
+
+/// # The frames that the game engine can go back to
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Timeline {
+    /// # The range of frames in the history
+    ///
+    /// `None`, if the history doesn't cover any frames yet.
+    pub frames: Option<RangeInclusive<u64>>,
+
+    /// # The frame that the game is currently working on
+    pub current_frame: u64,
+}
@@ -158,13 +158,11 @@ impl GameEngineFunction {
         let number = (*self).into();
         let signature = match self {
             Self::Halt => ([], []).into(),
-            Self::Load => ([Number], [Number]).into(),
-            Self::Store => ([Number, Number], []).into(),
-            Self::ReadInput => ([], [Number]).into(),
-            Self::ReadRandom => ([], [Number]).into(),
-            Self::SetPixel => {
-                ([Number, Number, Number, Number, Number, Number], []).into()
-            }
+            Self::Load => ([U8], [U8]).into(),
+            Self::Store => ([U8, U8], []).into(),
+            Self::ReadInput => ([], [U8]).into(),
+            Self::ReadRandom => ([], [S32]).into(),
+            Self::SetPixel => ([U8, U8, U8, U8, U8, U8], []).into(),
             Self::SubmitFrame => ([], []).into(),
         };
 
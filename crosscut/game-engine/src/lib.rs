@@ -1,5 +1,12 @@
+pub mod breakpoints;
 pub mod command;
 pub mod display;
+pub mod expression;
 pub mod game_engine;
+pub mod history;
 pub mod host;
 pub mod memory;
+pub mod recording;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,218 @@
+use crosscut_compiler::{code::Hash, Instructions};
+
+use crate::{
+    command::Command, display::NUM_PIXEL_BYTES, game_engine::GameEngine,
+    memory::Memory,
+};
+
+/// # An event that happened while a game session was being recorded
+///
+/// A recording is a sequence of these events, in the order in which they
+/// happened. See [`GameEngine::start_recording`].
+#[allow(clippy::large_enum_variant)] // haven't optimized this yet
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum RecordedEvent {
+    /// # The recording was started while this version of the code was running
+    ///
+    /// Code updates during the recording are recorded as [`Command`]s, as the
+    /// debugger might have changed the code to apply breakpoints.
+    ///
+    /// [`Command`]: RecordedEvent::Command
+    UpdateCode { version: Hash<Instructions> },
+
+    /// # The game has been reset
+    Reset,
+
+    /// # The host has provided a byte of input
+    Input { value: u8 },
+
+    /// # The random number generator has been seeded
+    SetSeed { seed: u64 },
+
+    /// # The game engine yielded, before the game finished its current frame
+    Yield,
+
+    /// # The game has finished a frame
+    EndOfFrame { hashes: FrameHashes },
+
+    /// # The process has stopped, before the game finished its current frame
+    ///
+    /// This can be the result of a breakpoint, or of the frame taking too long.
+    Stop,
+
+    /// # The debugger has sent a command to the game engine
+    ///
+    /// Commands that have their own event, or that only control the recording
+    /// itself, are not recorded as this event. Code updates are, and a replay
+    /// applies them, regardless of the code it started with.
+    Command { command: Command },
+}
+
+/// # Hashes of the state of the game at the end of a frame
+///
+/// These are used to verify that a replay matches the recording.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FrameHashes {
+    pub memory: [u8; 32],
+    pub pixels: [u8; 32],
+}
+
+impl FrameHashes {
+    pub fn new(memory: &Memory, pixels: &[u8]) -> Self {
+        Self {
+            memory: blake3::hash(&memory.inner).into(),
+            pixels: blake3::hash(pixels).into(),
+        }
+    }
+}
+
+/// # Replays a recording, verifying that the game behaves as recorded
+///
+/// Expects a [`GameEngine`] that has been reset and has been provided with the
+/// code of the game, but is otherwise untouched.
+///
+/// ## Implementation Note
+///
+/// The game engine decides when to start a new frame based on the current
+/// time. The replay doesn't care about that, and just makes sure enough time
+/// has passed for every frame. It doesn't need to be more accurate, since the
+/// game itself has no way to read the time.
+pub struct Replay {
+    current_time_s: f64,
+    num_frames: u64,
+    pixels: Vec<u8>,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            current_time_s: 0.,
+            num_frames: 0,
+            pixels: vec![0; NUM_PIXEL_BYTES],
+        }
+    }
+
+    /// # The number of frames that have been replayed so far
+    pub fn num_frames(&self) -> u64 {
+        self.num_frames
+    }
+
+    pub fn replay_event(
+        &mut self,
+        event: RecordedEvent,
+        game_engine: &mut GameEngine,
+    ) -> Result<(), ReplayError> {
+        match event {
+            RecordedEvent::UpdateCode { version } => {
+                if game_engine.code_version() != Some(version) {
+                    return Err(ReplayError::CodeVersion);
+                }
+            }
+            RecordedEvent::Reset => {
+                game_engine.on_command(Command::Reset);
+            }
+            RecordedEvent::Input { value } => {
+                game_engine.on_input(value);
+            }
+            RecordedEvent::SetSeed { seed } => {
+                game_engine.on_command(Command::SetSeed { seed });
+            }
+            RecordedEvent::Yield => {
+                if self.run(game_engine)? {
+                    return Err(ReplayError::FinishedInsteadOfYielded {
+                        frame: self.num_frames,
+                    });
+                }
+            }
+            RecordedEvent::Stop => {
+                self.current_time_s += 1.;
+                game_engine.run_until_end_of_frame(
+                    self.current_time_s,
+                    &mut self.pixels,
+                );
+
+                if game_engine.runtime.effect().inspect().is_none() {
+                    return Err(ReplayError::DidNotStop {
+                        frame: self.num_frames,
+                    });
+                }
+            }
+            RecordedEvent::Command { command } => {
+                game_engine.on_command(command);
+            }
+            RecordedEvent::EndOfFrame { hashes: expected } => {
+                if !self.run(game_engine)? {
+                    return Err(ReplayError::YieldedInsteadOfFinished {
+                        frame: self.num_frames,
+                    });
+                }
+
+                let actual =
+                    FrameHashes::new(game_engine.memory(), &self.pixels);
+
+                if actual.memory != expected.memory {
+                    return Err(ReplayError::Memory {
+                        frame: self.num_frames,
+                    });
+                }
+                if actual.pixels != expected.pixels {
+                    return Err(ReplayError::Pixels {
+                        frame: self.num_frames,
+                    });
+                }
+
+                self.num_frames += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run(
+        &mut self,
+        game_engine: &mut GameEngine,
+    ) -> Result<bool, ReplayError> {
+        self.current_time_s += 1.;
+
+        let finished = game_engine
+            .run_until_end_of_frame(self.current_time_s, &mut self.pixels);
+
+        if game_engine.runtime.effect().inspect().is_some() {
+            return Err(ReplayError::Stopped {
+                frame: self.num_frames,
+            });
+        }
+
+        Ok(finished)
+    }
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("Recording was made with a different version of the code")]
+    CodeVersion,
+
+    #[error("Memory differs from recording after frame {frame}")]
+    Memory { frame: u64 },
+
+    #[error("Pixels differ from recording after frame {frame}")]
+    Pixels { frame: u64 },
+
+    #[error("Game stopped unexpectedly during frame {frame}")]
+    Stopped { frame: u64 },
+
+    #[error("Expected game to stop during frame {frame}")]
+    DidNotStop { frame: u64 },
+
+    #[error("Expected game engine to yield during frame {frame}")]
+    FinishedInsteadOfYielded { frame: u64 },
+
+    #[error("Expected game to finish frame {frame}")]
+    YieldedInsteadOfFinished { frame: u64 },
+}
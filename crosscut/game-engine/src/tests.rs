@@ -0,0 +1,4 @@
+//! # End-to-end testing for `crosscut-game-engine`
+
+mod infra;
+mod suite;
@@ -0,0 +1,103 @@
+use crosscut_compiler::Compiler;
+
+use crate::{
+    command::Command, display::NUM_PIXEL_BYTES, game_engine::GameEngine,
+    host::GameEngineHost,
+};
+
+/// # A game that stores a random number and the latest input every frame
+///
+/// The random number ends up at address `0`, the input at address `1`. Both
+/// are also written to the single pixel that the game draws.
+pub const GAME: &str = r"
+    main: fn
+        br _, _ ->
+            frame
+        end
+    end
+
+    frame: fn
+        br ->
+            read_random abs 100 remainder_s32 s32_to_u8
+            0 store
+            read_input
+            1 store
+            0 0 0 load 1 load 0 255 set_pixel
+            submit_frame
+            frame
+        end
+    end
+";
+
+pub fn game_engine(source: &str) -> TestGameEngine {
+    let code = Compiler::default().compile(source, &GameEngineHost);
+    assert!(
+        !code.diagnostics.has_errors(),
+        "Test game should compile without errors: {:#?}",
+        code.diagnostics,
+    );
+
+    let mut game_engine = GameEngine::new();
+    game_engine.on_command(Command::UpdateCode {
+        instructions: code.instructions,
+    });
+
+    TestGameEngine {
+        game_engine,
+        pixels: vec![0; NUM_PIXEL_BYTES],
+        current_time_s: 0.,
+    }
+}
+
+pub struct TestGameEngine {
+    pub game_engine: GameEngine,
+    pub pixels: Vec<u8>,
+    current_time_s: f64,
+}
+
+impl TestGameEngine {
+    pub fn run_frame(&mut self) -> &mut Self {
+        loop {
+            // Pretend that plenty of time has passed, so the next frame is
+            // always due.
+            self.current_time_s += 1.;
+
+            let finished_frame = self
+                .game_engine
+                .run_until_end_of_frame(self.current_time_s, &mut self.pixels);
+
+            if let Some(effect) = self.game_engine.runtime.effect().inspect() {
+                panic!("Unexpected effect: {effect}");
+            }
+
+            if finished_frame {
+                break;
+            }
+        }
+
+        self
+    }
+
+    /// # Give the game engine one chance to run, like a host does every frame
+    ///
+    /// Unlike [`TestGameEngine::run_frame`], this doesn't expect the game to
+    /// finish a frame, or to keep running.
+    pub fn update(&mut self) -> &mut Self {
+        self.current_time_s += 1.;
+        self.game_engine
+            .run_until_end_of_frame(self.current_time_s, &mut self.pixels);
+        self
+    }
+
+    /// # Run the provided number of frames, returning the memory after each
+    pub fn run_frames(&mut self, num_frames: usize) -> Vec<[u8; 2]> {
+        (0..num_frames)
+            .map(|_| {
+                self.run_frame();
+
+                let memory = &self.game_engine.memory().inner;
+                [memory[0], memory[1]]
+            })
+            .collect()
+    }
+}
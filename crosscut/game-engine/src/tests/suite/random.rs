@@ -0,0 +1,63 @@
+use crate::{
+    command::Command,
+    tests::infra::{game_engine, GAME},
+};
+
+#[test]
+fn same_seed_results_in_same_random_numbers() {
+    // Games that are started with the same seed should see the same sequence
+    // of random numbers. A different seed should result in a different one.
+
+    let mut a = game_engine(GAME);
+    let mut b = game_engine(GAME);
+    let mut c = game_engine(GAME);
+
+    a.game_engine.on_command(Command::SetSeed { seed: 42 });
+    b.game_engine.on_command(Command::SetSeed { seed: 42 });
+    c.game_engine.on_command(Command::SetSeed { seed: 43 });
+
+    let a = a.run_frames(8);
+    let b = b.run_frames(8);
+    let c = c.run_frames(8);
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn reset_restarts_random_numbers() {
+    // After a reset, the game should see the same sequence of random numbers
+    // as it did when it was first started.
+
+    let mut game = game_engine(GAME);
+    game.game_engine.on_command(Command::SetSeed { seed: 42 });
+
+    let before = game.run_frames(8);
+    game.game_engine.on_command(Command::Reset);
+    let after = game.run_frames(8);
+
+    assert_eq!(before, after);
+}
+
+#[test]
+fn snapshot_includes_random_number_generator() {
+    // Restoring a snapshot should also restore the state of the random number
+    // generator, so the game sees the same random numbers as it did after the
+    // snapshot was taken.
+
+    let mut game = game_engine(GAME);
+    game.game_engine.on_command(Command::SetSeed { seed: 42 });
+    game.run_frames(2);
+
+    game.game_engine.on_command(Command::SaveSnapshot);
+    let snapshot = game.game_engine.take_saved_snapshot().unwrap();
+
+    let before = game.run_frames(8);
+    game.game_engine
+        .on_command(Command::RestoreSnapshot { snapshot });
+    game.game_engine
+        .on_command(Command::ClearBreakpointAndContinue);
+    let after = game.run_frames(8);
+
+    assert_eq!(before, after);
+}
@@ -0,0 +1,151 @@
+use crosscut_compiler::Compiler;
+
+use crate::{
+    command::Command,
+    host::GameEngineHost,
+    recording::{RecordedEvent, Replay, ReplayError},
+    tests::infra::{game_engine, GAME},
+};
+
+#[test]
+fn replay_matches_recording() {
+    // Replaying a recording should result in exactly the same memory and
+    // pixels, frame by frame, as the recorded session.
+
+    let mut recorded = game_engine(GAME);
+    recorded
+        .game_engine
+        .on_command(Command::SetSeed { seed: 42 });
+    recorded.game_engine.start_recording();
+
+    recorded.run_frames(3);
+    recorded.game_engine.on_input(7);
+    recorded.run_frames(3);
+
+    let events = recorded.game_engine.take_recorded_events();
+
+    let mut replayed = game_engine(GAME);
+    let mut replay = Replay::new();
+    for event in events {
+        replay
+            .replay_event(event, &mut replayed.game_engine)
+            .unwrap();
+    }
+
+    assert_eq!(replay.num_frames(), 6);
+    assert_eq!(replayed.game_engine.memory(), recorded.game_engine.memory(),);
+}
+
+#[test]
+fn replay_detects_diverging_memory() {
+    // If the game doesn't behave the same way during the replay, that should
+    // be detected at the end of the first frame where it differs.
+
+    let mut recorded = game_engine(GAME);
+    recorded
+        .game_engine
+        .on_command(Command::SetSeed { seed: 42 });
+    recorded.game_engine.start_recording();
+    recorded.run_frames(3);
+
+    // A different seed results in different random numbers, which the game
+    // stores in memory.
+    let events = recorded
+        .game_engine
+        .take_recorded_events()
+        .into_iter()
+        .map(|event| match event {
+            RecordedEvent::SetSeed { .. } => {
+                RecordedEvent::SetSeed { seed: 43 }
+            }
+            event => event,
+        })
+        .collect::<Vec<_>>();
+
+    let mut replayed = game_engine(GAME);
+    let mut replay = Replay::new();
+    let result = events.into_iter().try_for_each(|event| {
+        replay.replay_event(event, &mut replayed.game_engine)
+    });
+
+    assert!(matches!(result, Err(ReplayError::Memory { frame: 0 })));
+}
+
+#[test]
+fn replay_detects_different_code_version() {
+    // A recording can only be replayed with the code that it was made with.
+
+    let mut recorded = game_engine(GAME);
+    recorded.game_engine.start_recording();
+    recorded.run_frames(1);
+
+    let events = recorded.game_engine.take_recorded_events();
+
+    let mut replayed = game_engine(&GAME.replace("100", "99"));
+    let mut replay = Replay::new();
+    let result = events.into_iter().try_for_each(|event| {
+        replay.replay_event(event, &mut replayed.game_engine)
+    });
+
+    assert!(matches!(result, Err(ReplayError::CodeVersion)));
+}
+
+#[test]
+fn replay_includes_debugger_commands() {
+    // Stopping at breakpoints, updating the code to get past them, and
+    // traveling back in time influence the game just as much as input does. A
+    // replay needs to do the same.
+
+    let with_breakpoint = GAME.replace("0 store", "0 store brk");
+    let without_breakpoint = GAME.replace("0 store", "0 store nop");
+
+    let mut recorded = game_engine(&with_breakpoint);
+    recorded
+        .game_engine
+        .on_command(Command::SetSeed { seed: 42 });
+    recorded.game_engine.start_recording();
+
+    // Stop at the breakpoint in the first frame. Then get rid of it, the way
+    // the debugger would, and finish the frame.
+    recorded.update();
+    recorded.game_engine.on_command(Command::UpdateCode {
+        instructions: Compiler::default()
+            .compile(&without_breakpoint, &GameEngineHost)
+            .instructions,
+    });
+    recorded
+        .game_engine
+        .on_command(Command::ClearBreakpointAndContinue);
+    recorded.update();
+
+    // Go back to the first frame, and run from there again.
+    recorded.game_engine.on_input(7);
+    recorded
+        .game_engine
+        .on_command(Command::TimeTravel { frame: 0 });
+    recorded
+        .game_engine
+        .on_command(Command::ClearBreakpointAndContinue);
+    recorded.run_frames(2);
+
+    let events = recorded.game_engine.take_recorded_events();
+    assert!(events.contains(&RecordedEvent::Stop));
+    assert!(events.contains(&RecordedEvent::Command {
+        command: Command::TimeTravel { frame: 0 },
+    }));
+
+    let mut replayed = game_engine(&with_breakpoint);
+    let mut replay = Replay::new();
+    for event in events {
+        replay
+            .replay_event(event, &mut replayed.game_engine)
+            .unwrap();
+    }
+
+    assert_eq!(replay.num_frames(), 3);
+    assert_eq!(replayed.game_engine.memory(), recorded.game_engine.memory());
+    assert_eq!(
+        replayed.game_engine.timeline(),
+        recorded.game_engine.timeline(),
+    );
+}
@@ -1,7 +1,7 @@
 use std::sync::Mutex;
 
 use crosscut_ffi::{framed_buffer::FramedBuffer, shared::Shared};
-use crosscut_game_engine::display::NUM_PIXEL_BYTES;
+use crosscut_game_engine::{command::Command, display::NUM_PIXEL_BYTES};
 use crosscut_protocol::{COMMANDS_BUFFER_SIZE, UPDATES_BUFFER_SIZE};
 
 use crate::host::Host;
@@ -89,16 +89,14 @@ pub fn pixels_len() -> usize {
 }
 
 #[no_mangle]
-pub fn push_random(random: f64) -> bool {
+pub fn set_seed(random: f64) {
     let mut state = STATE.lock().unwrap();
     let state = state.get_or_insert_with(Default::default);
 
-    let min: f64 = i32::MIN.into();
-    let max: f64 = i32::MAX.into();
+    let max = u64::MAX as f64;
+    let seed = (random * max).floor() as u64;
 
-    let random = min + random * (max - min);
-
-    state.game_engine.push_random(random.floor() as _)
+    state.game_engine.on_command(Command::SetSeed { seed });
 }
 
 #[no_mangle]
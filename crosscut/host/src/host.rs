@@ -39,7 +39,16 @@ impl Host {
         self.updates.queue_updates(
             &self.game_engine.runtime,
             self.game_engine.memory(),
+            self.game_engine.timeline(),
         );
+
+        if let Some(snapshot) = self.game_engine.take_saved_snapshot() {
+            self.updates.queue_snapshot(snapshot);
+        }
+        if let Some(events) = self.game_engine.take_finished_recording() {
+            self.updates.queue_recording(events);
+        }
+        self.updates.queue_trace(self.game_engine.take_trace());
     }
 }
 
@@ -0,0 +1,367 @@
+use crosscut_compiler::code::{
+    syntax::{
+        Expression, FunctionLocation, Located, Member, MemberLocation, Module,
+        ParameterLocation,
+    },
+    Position, Span,
+};
+
+use crate::document::Document;
+
+/// # Something that an identifier can refer to
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Target {
+    /// # A user-defined function
+    Function(FunctionLocation),
+
+    /// # A binding, defined by the parameter at the provided location
+    Binding(ParameterLocation),
+}
+
+/// # A symbol within a document
+pub struct Symbol {
+    pub name: String,
+
+    /// # The span of the whole definition of the symbol
+    pub span: Span,
+
+    /// # The span of the symbol's name
+    pub name_span: Span,
+}
+
+/// # Find the target at the provided byte offset, if any
+///
+/// This could be either the definition of the target, or a reference to it.
+pub fn target_at(document: &Document, byte: usize) -> Option<Target> {
+    let code = &document.code;
+
+    if let Some(member) = member_at(document, byte) {
+        if let Member::Expression { expression, .. } = member.fragment {
+            if matches!(
+                expression,
+                Expression::Identifier { .. }
+                    | Expression::FunctionReference { .. }
+            ) {
+                return target_of_identifier(document, &member.location);
+            }
+        }
+    }
+
+    if let Some(location) = parameter_at(document, byte) {
+        let parameter = code
+            .syntax_tree
+            .binding_by_location(&location)
+            .map(|binding| binding.location)?;
+        return Some(Target::Binding(parameter));
+    }
+
+    for function in code.syntax_tree.named_functions() {
+        let Some(span) = name_span_of_function(document, &function.location())
+        else {
+            continue;
+        };
+
+        if contains(&span, byte) {
+            return Some(Target::Function(function.location()));
+        }
+    }
+
+    None
+}
+
+/// # Find the span of the definition of the provided target
+pub fn definition(document: &Document, target: &Target) -> Option<Span> {
+    match target {
+        Target::Function(location) => name_span_of_function(document, location),
+        Target::Binding(location) => document
+            .code
+            .syntax_tree
+            .spans
+            .of_parameter(location)
+            .copied(),
+    }
+}
+
+/// # Find the spans of all references to the provided target
+pub fn references(document: &Document, target: &Target) -> Vec<Span> {
+    let syntax_tree = &document.code.syntax_tree;
+    let mut references = Vec::new();
+
+    for function in syntax_tree.all_functions() {
+        for branch in function.branches() {
+            for expression in branch.expressions() {
+                let Some(expression_target) =
+                    target_of_identifier(document, &expression.location)
+                else {
+                    continue;
+                };
+
+                if expression_target != *target {
+                    continue;
+                }
+
+                if let Some(span) =
+                    syntax_tree.spans.of_member(&expression.location)
+                {
+                    references.push(*span);
+                }
+            }
+        }
+    }
+
+    references
+}
+
+/// # Describe whatever is at the provided byte offset, for display on hover
+pub fn hover(document: &Document, byte: usize) -> Option<String> {
+    let code = &document.code;
+
+    if let Some(member) = member_at(document, byte) {
+        if let Some(expression) = member.fragment.as_expression() {
+            let description = match expression {
+                Expression::Identifier { name } => name.clone(),
+                Expression::FunctionReference { name } => format!("&{name}"),
+                Expression::LiteralNumber { value } => value.to_string(),
+                Expression::LocalFunction { .. } => "fn".to_string(),
+            };
+
+            let signature = match expression {
+                Expression::LocalFunction { .. } => {
+                    code.types.signature_of_function(&FunctionLocation::from(
+                        member.location.clone(),
+                    ))
+                }
+                _ => code.types.signature_of_expression(&member.location),
+            };
+
+            return Some(match signature {
+                Some(signature) => format!("{description}: {signature}"),
+                None => description,
+            });
+        }
+    }
+
+    if let Some(location) = parameter_at(document, byte) {
+        let binding = code.syntax_tree.binding_by_location(&location)?;
+
+        return Some(match code.types.type_of_parameter(&location) {
+            Some(type_) => format!("{}: {type_}", binding.name),
+            None => binding.name.clone(),
+        });
+    }
+
+    for function in code.syntax_tree.named_functions() {
+        let location = function.location();
+        let Some(span) = name_span_of_function(document, &location) else {
+            continue;
+        };
+
+        if contains(&span, byte) {
+            return Some(match code.types.signature_of_function(&location) {
+                Some(signature) => format!("{}: {signature}", function.name),
+                None => function.name.clone(),
+            });
+        }
+    }
+
+    None
+}
+
+/// # List the symbols within the document
+///
+/// Symbols that are defined in imported modules are not included.
+pub fn symbols(document: &Document) -> Vec<Symbol> {
+    let syntax_tree = &document.code.syntax_tree;
+
+    syntax_tree
+        .named_functions()
+        .filter(|function| function.module == Module::main_index())
+        .filter_map(|function| {
+            let location = function.location();
+
+            let span = syntax_tree.spans.of_function(&location).copied()?;
+            let name_span = name_span_of_function(document, &location)?;
+
+            Some(Symbol {
+                name: function.name.clone(),
+                span,
+                name_span,
+            })
+        })
+        .collect()
+}
+
+fn target_of_identifier(
+    document: &Document,
+    location: &MemberLocation,
+) -> Option<Target> {
+    if let Some(parameter) = document.bindings.is_binding(location) {
+        return Some(Target::Binding(parameter.clone()));
+    }
+
+    document
+        .code
+        .function_calls
+        .is_call_to_user_defined_function(location)
+        .map(|function| Target::Function(function.clone()))
+}
+
+/// # Find the innermost member at the provided byte offset
+fn member_at(document: &Document, byte: usize) -> Option<Located<&Member>> {
+    let syntax_tree = &document.code.syntax_tree;
+
+    syntax_tree
+        .all_functions()
+        .flat_map(|function| {
+            function
+                .branches()
+                .flat_map(|branch| branch.body().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|member| {
+            let span = syntax_tree.spans.of_member(&member.location)?;
+            contains(span, byte).then_some((*span, member))
+        })
+        .min_by_key(|(span, _)| span.end.byte - span.start.byte)
+        .map(|(_, member)| member)
+}
+
+/// # Find the parameter at the provided byte offset
+fn parameter_at(document: &Document, byte: usize) -> Option<ParameterLocation> {
+    let syntax_tree = &document.code.syntax_tree;
+
+    syntax_tree.all_functions().find_map(|function| {
+        function.branches().find_map(|branch| {
+            branch.parameters().find_map(|parameter| {
+                let span =
+                    syntax_tree.spans.of_parameter(&parameter.location)?;
+                contains(span, byte).then_some(parameter.location)
+            })
+        })
+    })
+}
+
+/// # Compute the span of the name of the function at the provided location
+///
+/// Only named functions have a name, so this returns `None` for any other.
+fn name_span_of_function(
+    document: &Document,
+    location: &FunctionLocation,
+) -> Option<Span> {
+    let FunctionLocation::Named { index } = location else {
+        return None;
+    };
+
+    let syntax_tree = &document.code.syntax_tree;
+    let function = syntax_tree.named_functions.get(index)?;
+    let start = syntax_tree.spans.of_named_function(index)?.start;
+
+    // The span of a named function starts with its name, which can't contain
+    // a line break.
+    let end = Position {
+        byte: start.byte + function.name.len(),
+        line: start.line,
+        column: start.column + function.name.chars().count(),
+    };
+
+    Some(Span {
+        module: function.module,
+        start,
+        end,
+    })
+}
+
+/// # Determine, if the span contains the provided byte offset
+///
+/// The byte offset refers to the document itself, so spans within imported
+/// modules never contain it.
+///
+/// Also returns `true`, if the offset is right at the end of the span. Editors
+/// commonly request information about the position right after an identifier.
+fn contains(span: &Span, byte: usize) -> bool {
+    span.module == Module::main_index()
+        && span.start.byte <= byte
+        && byte <= span.end.byte
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::Document;
+
+    use super::{definition, hover, references, symbols, target_at};
+
+    const SOURCE: &str = "\
+main: fn
+    br value ->
+        value f
+        1 f
+    end
+end
+
+f: fn
+    br x ->
+        x 1 add_s32
+    end
+end
+";
+
+    #[test]
+    fn go_to_definition_of_function() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let call = SOURCE.find("f\n").unwrap();
+        let target = target_at(&document, call).unwrap();
+        let span = definition(&document, &target).unwrap();
+
+        assert_eq!(&SOURCE[span.start.byte..span.end.byte], "f");
+        assert_eq!(span.start.line, 8);
+    }
+
+    #[test]
+    fn go_to_definition_of_binding() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let use_of_binding = SOURCE.find("value f").unwrap();
+        let target = target_at(&document, use_of_binding).unwrap();
+        let span = definition(&document, &target).unwrap();
+
+        assert_eq!(&SOURCE[span.start.byte..span.end.byte], "value");
+        assert_eq!(span.start.line, 2);
+    }
+
+    #[test]
+    fn find_references_to_function() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let definition = SOURCE.find("f: fn").unwrap();
+        let target = target_at(&document, definition).unwrap();
+        let references = references(&document, &target);
+
+        let lines = references
+            .iter()
+            .map(|span| span.start.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [3, 4]);
+    }
+
+    #[test]
+    fn hover_over_function_shows_signature() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let definition = SOURCE.find("f: fn").unwrap();
+        let hover = hover(&document, definition).unwrap();
+
+        assert_eq!(hover, "f: S32 -> S32");
+    }
+
+    #[test]
+    fn list_named_functions_as_symbols() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let names = symbols(&document)
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["main", "f"]);
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    any::Any,
+    convert::Infallible,
+    fs, panic,
+    path::{Path, PathBuf},
+};
+
+use crosscut_compiler::{
+    code::{syntax::Module, Bindings, Index, Position, Span},
+    diagnostics::{Diagnostic, Diagnostics},
+    sources::{search_path, SourceFile, Sources},
+    Compiler, CompilerOutput,
+};
+use crosscut_game_engine::host::GameEngineHost;
+
+/// # A document that is open in the editor
+///
+/// Every time the document changes, it is compiled from scratch. Editors send
+/// the whole text on every change, and the compiler is fast enough that this
+/// isn't a problem.
+///
+/// The document is compiled as the main module. Any modules it imports are
+/// loaded from disk.
+pub struct Document {
+    pub sources: Sources,
+    pub code: CompilerOutput,
+    pub bindings: Bindings,
+}
+
+impl Document {
+    /// # Compile the provided source code into a document
+    ///
+    /// If a path is provided, imported modules are looked for relative to it,
+    /// the same way the Crosscut tool does when building a game.
+    ///
+    /// Problems with the source code are reported as diagnostics. If the
+    /// compiler panics anyway, that is a bug in the compiler. The language
+    /// server must keep running regardless, so the panic is reported as a
+    /// diagnostic too, in place of the ones that compiling would have
+    /// produced.
+    pub fn new(path: Option<PathBuf>, source: String) -> Self {
+        let sources = load_sources(path, source);
+
+        let (code, bindings) = panic::catch_unwind(|| compile(&sources))
+            .unwrap_or_else(|payload| {
+                // There's nothing to analyze, but an empty document still
+                // needs compiler output.
+                let empty = Sources::new(SourceFile::main("", ""));
+                let (mut code, bindings) = compile(&empty);
+
+                code.diagnostics = Diagnostics::default();
+                code.diagnostics.push(Diagnostic::error(
+                    format!(
+                        "Internal compiler error: {}",
+                        panic_message(payload.as_ref()),
+                    ),
+                    None,
+                ));
+
+                (code, bindings)
+            });
+
+        Self {
+            sources,
+            code,
+            bindings,
+        }
+    }
+
+    /// # Access the source code of the provided module
+    pub fn source(&self, module: &Index<Module>) -> &str {
+        self.sources
+            .get(module)
+            .map(|file| file.code.as_str())
+            .unwrap_or_default()
+    }
+
+    /// # Convert a position within a module to an LSP position
+    ///
+    /// LSP positions consist of a zero-based line and a character offset within
+    /// that line, in UTF-16 code units.
+    pub fn to_lsp_position(
+        &self,
+        module: &Index<Module>,
+        position: &Position,
+    ) -> LspPosition {
+        let source = self.source(module);
+
+        let byte = position.byte.min(source.len());
+        let line_start = source[..byte]
+            .rfind('\n')
+            .map(|index| index + 1)
+            .unwrap_or(0);
+
+        LspPosition {
+            line: position.line - 1,
+            character: source[line_start..byte].encode_utf16().count(),
+        }
+    }
+
+    /// # Convert a span to an LSP range
+    pub fn to_lsp_range(&self, span: &Span) -> [LspPosition; 2] {
+        [&span.start, &span.end]
+            .map(|position| self.to_lsp_position(&span.module, position))
+    }
+
+    /// # Convert an LSP position to a byte offset within the document
+    ///
+    /// Positions beyond the end of a line or the source code are clamped.
+    pub fn to_byte(&self, position: &LspPosition) -> usize {
+        let source = self.source(&Module::main_index());
+
+        let mut line_start = 0;
+
+        for _ in 0..position.line {
+            match source[line_start..].find('\n') {
+                Some(index) => line_start += index + 1,
+                None => return source.len(),
+            }
+        }
+
+        let mut character = 0;
+
+        for (offset, ch) in source[line_start..].char_indices() {
+            if ch == '\n' || character >= position.character {
+                return line_start + offset;
+            }
+
+            character += ch.len_utf16();
+        }
+
+        source.len()
+    }
+}
+
+/// # A position, as defined by the Language Server Protocol
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LspPosition {
+    /// # The zero-based line
+    pub line: usize,
+
+    /// # The offset within the line, in UTF-16 code units
+    pub character: usize,
+}
+
+fn compile(sources: &Sources) -> (CompilerOutput, Bindings) {
+    let code = Compiler::default().compile_sources(sources, &GameEngineHost);
+
+    // Bindings are not part of the compiler output, so we need to resolve them
+    // ourselves. The compiler has already reported any diagnostics that this
+    // produces.
+    let bindings =
+        Bindings::resolve(&code.syntax_tree, &mut Diagnostics::default());
+
+    (code, bindings)
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+fn load_sources(path: Option<PathBuf>, source: String) -> Sources {
+    let search_path = path
+        .as_deref()
+        .and_then(Path::parent)
+        .map(search_path)
+        .unwrap_or_default();
+
+    // The document might not be the main module of a game. Name it after its
+    // file, so it can't be mistaken for any module it imports.
+    let module = path
+        .as_deref()
+        .and_then(Path::file_stem)
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| Module::MAIN.to_string());
+    let main = SourceFile {
+        module,
+        path: path.unwrap_or_default(),
+        code: source,
+    };
+    let mut sources = Sources::new(main);
+
+    // If a module can't be read, the compiler is going to report it as missing.
+    let result = sources.load_imports(&search_path, |path| {
+        Ok::<_, Infallible>(fs::read_to_string(path).ok())
+    });
+    let Ok(_) = result;
+
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use crosscut_compiler::code::{syntax::Module, Position};
+
+    use super::{Document, LspPosition};
+
+    // `ä` takes up two bytes, but one UTF-16 code unit. `😀` takes up four
+    // bytes, and two UTF-16 code units.
+    const SOURCE: &str = "\
+main: fn
+    br ->
+        # ä😀
+        x
+    end
+end
+";
+
+    #[test]
+    fn convert_byte_to_lsp_position() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let byte = SOURCE.find("😀").unwrap() + "😀".len();
+        let position = document.to_lsp_position(
+            &Module::main_index(),
+            &Position {
+                byte,
+                line: 3,
+                column: 13,
+            },
+        );
+
+        assert_eq!(
+            position,
+            LspPosition {
+                line: 2,
+                character: 13,
+            },
+        );
+    }
+
+    #[test]
+    fn convert_lsp_position_to_byte() {
+        let document = Document::new(None, SOURCE.to_string());
+
+        let byte = document.to_byte(&LspPosition {
+            line: 2,
+            character: 11,
+        });
+        assert_eq!(&SOURCE[byte..], &SOURCE[SOURCE.find("😀").unwrap()..]);
+
+        // Positions beyond the end of the line are clamped to it.
+        let byte = document.to_byte(&LspPosition {
+            line: 2,
+            character: 100,
+        });
+        assert_eq!(byte, SOURCE.find("\n        x").unwrap());
+    }
+}
@@ -0,0 +1,21 @@
+//! # Language server for Crosscut
+//!
+//! Serves the Language Server Protocol over stdio, providing diagnostics,
+//! hover information, go-to-definition, find-references, and document symbols
+//! for `.capi` files.
+
+mod analysis;
+mod document;
+mod protocol;
+mod server;
+
+use std::io;
+
+fn main() -> anyhow::Result<()> {
+    let stdin = io::stdin().lock();
+    let stdout = io::stdout().lock();
+
+    server::run(stdin, stdout)?;
+
+    Ok(())
+}
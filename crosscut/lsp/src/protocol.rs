@@ -0,0 +1,200 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::{anyhow, Context};
+use serde_json::{json, Value};
+
+/// # A message sent by the client
+pub enum Message {
+    /// # A request, which expects a response
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+
+    /// # A notification, which doesn't expect a response
+    Notification { method: String, params: Value },
+
+    /// # A response to a request that the server sent
+    ///
+    /// The server doesn't send any requests, so these are ignored.
+    Response,
+}
+
+/// # Read the next message from the client
+///
+/// Returns `None`, if the client closed the connection.
+pub fn read_message(
+    input: &mut impl BufRead,
+) -> anyhow::Result<Option<Message>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            // An empty line marks the end of the header.
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            content_length = Some(
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .context("Parsing `Content-Length` header")?,
+            );
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| anyhow!("Message has no `Content-Length` header"))?;
+
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content)?;
+
+    let mut value: Value = serde_json::from_slice(&content)?;
+
+    let id = value.get_mut("id").map(Value::take);
+    let method = value
+        .get("method")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    let params = value.get_mut("params").map(Value::take).unwrap_or_default();
+
+    let message = match (id, method) {
+        (Some(id), Some(method)) => Message::Request { id, method, params },
+        (None, Some(method)) => Message::Notification { method, params },
+        (_, None) => Message::Response,
+    };
+
+    Ok(Some(message))
+}
+
+/// # Send a successful response to a request
+pub fn send_response(
+    output: &mut impl Write,
+    id: Value,
+    result: Value,
+) -> io::Result<()> {
+    send(
+        output,
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }),
+    )
+}
+
+/// # Send an error response to a request
+pub fn send_error(
+    output: &mut impl Write,
+    id: Value,
+    code: i64,
+    message: &str,
+) -> io::Result<()> {
+    send(
+        output,
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": code,
+                "message": message,
+            },
+        }),
+    )
+}
+
+/// # Send a notification to the client
+pub fn send_notification(
+    output: &mut impl Write,
+    method: &str,
+    params: Value,
+) -> io::Result<()> {
+    send(
+        output,
+        json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }),
+    )
+}
+
+fn send(output: &mut impl Write, message: Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    output.flush()
+}
+
+/// # The error code for a method that the server doesn't support
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// # The error code for invalid parameters
+pub const INVALID_PARAMS: i64 = -32602;
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use serde_json::json;
+
+    use super::{read_message, send_notification, Message};
+
+    #[test]
+    fn read_consecutive_messages() {
+        // The content length is given in bytes, not characters. If multi-byte
+        // characters were miscounted, the next message would be corrupted.
+        let first = r#"{"jsonrpc":"2.0","id":1,"method":"a","params":"ä😀"}"#;
+        let second = r#"{"jsonrpc":"2.0","method":"b"}"#;
+        let input = format!(
+            "Content-Length: {}\r\n\r\n{first}\
+            Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n\
+            Content-Length: {}\r\n\r\n{second}",
+            first.len(),
+            second.len(),
+        );
+        let mut input = BufReader::new(input.as_bytes());
+
+        let Some(Message::Request { id, method, params }) =
+            read_message(&mut input).unwrap()
+        else {
+            panic!("Expected request");
+        };
+        assert_eq!(id, json!(1));
+        assert_eq!(method, "a");
+        assert_eq!(params, json!("ä😀"));
+
+        let Some(Message::Notification { method, .. }) =
+            read_message(&mut input).unwrap()
+        else {
+            panic!("Expected notification");
+        };
+        assert_eq!(method, "b");
+
+        assert!(read_message(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn send_message_with_content_length_in_bytes() {
+        let mut output = Vec::new();
+        send_notification(&mut output, "a", json!("ä😀")).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let (header, content) = output.split_once("\r\n\r\n").unwrap();
+
+        assert_eq!(header, format!("Content-Length: {}", content.len()));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(content).unwrap()
+                ["params"],
+            json!("ä😀"),
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+
+use crosscut_compiler::{
+    code::{syntax::Module, Span},
+    diagnostics::Severity,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    analysis,
+    document::{Document, LspPosition},
+    protocol::{
+        read_message, send_error, send_notification, send_response, Message,
+        INVALID_PARAMS, METHOD_NOT_FOUND,
+    },
+};
+
+/// # Serve the Language Server Protocol over the provided input and output
+///
+/// Returns, once the client has sent the `exit` notification, or closed the
+/// connection.
+pub fn run(
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> anyhow::Result<()> {
+    let mut server = Server {
+        documents: BTreeMap::new(),
+    };
+
+    while let Some(message) = read_message(&mut input)? {
+        match message {
+            Message::Request { id, method, params } => {
+                match server.handle_request(&method, params) {
+                    Ok(result) => send_response(&mut output, id, result)?,
+                    Err(RequestError { code, message }) => {
+                        send_error(&mut output, id, code, &message)?
+                    }
+                }
+            }
+            Message::Notification { method, params } => {
+                if method == "exit" {
+                    break;
+                }
+
+                if let Some(uri) = server.handle_notification(&method, params) {
+                    server.publish_diagnostics(&uri, &mut output)?;
+                }
+            }
+            Message::Response => {}
+        }
+    }
+
+    Ok(())
+}
+
+struct Server {
+    documents: BTreeMap<String, Document>,
+}
+
+impl Server {
+    fn handle_request(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, RequestError> {
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    // Documents are synced by sending their full text.
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": {
+                    "name": "crosscut-lsp",
+                },
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/hover" => {
+                let (_, document, byte) = self.document_position(&params)?;
+
+                analysis::hover(document, byte)
+                    .map(|hover| {
+                        json!({
+                            "contents": {
+                                "kind": "markdown",
+                                "value": format!("```\n{hover}\n```"),
+                            },
+                        })
+                    })
+                    .unwrap_or(Value::Null)
+            }
+            "textDocument/definition" => {
+                let (uri, document, byte) = self.document_position(&params)?;
+
+                analysis::target_at(document, byte)
+                    .and_then(|target| analysis::definition(document, &target))
+                    .map(|span| location(uri, document, &span))
+                    .unwrap_or(Value::Null)
+            }
+            "textDocument/references" => {
+                let (uri, document, byte) = self.document_position(&params)?;
+
+                let include_declaration = params
+                    .pointer("/context/includeDeclaration")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+
+                let Some(target) = analysis::target_at(document, byte) else {
+                    return Ok(Value::Null);
+                };
+
+                let mut spans = Vec::new();
+                if include_declaration {
+                    spans.extend(analysis::definition(document, &target));
+                }
+                spans.extend(analysis::references(document, &target));
+
+                Value::Array(
+                    spans
+                        .iter()
+                        .map(|span| location(uri, document, span))
+                        .collect(),
+                )
+            }
+            "textDocument/documentSymbol" => {
+                let (_, document) = self.document(&params)?;
+
+                Value::Array(
+                    analysis::symbols(document)
+                        .into_iter()
+                        .map(|symbol| {
+                            json!({
+                                "name": symbol.name,
+                                // The LSP defines `12` as a function.
+                                "kind": 12,
+                                "range": range(document, &symbol.span),
+                                "selectionRange":
+                                    range(document, &symbol.name_span),
+                            })
+                        })
+                        .collect(),
+                )
+            }
+            method => {
+                return Err(RequestError {
+                    code: METHOD_NOT_FOUND,
+                    message: format!("Unsupported method: `{method}`"),
+                });
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// # Handle a notification
+    ///
+    /// Returns the URI of the document, if the notification changed it.
+    fn handle_notification(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Option<String> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)?
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params.pointer("/textDocument/text")?.as_str()?;
+                self.documents.insert(
+                    uri.clone(),
+                    Document::new(path_from_uri(&uri), text.to_string()),
+                );
+            }
+            "textDocument/didChange" => {
+                // We only support full document sync, so the last change
+                // contains the full text.
+                let text = params
+                    .get("contentChanges")?
+                    .as_array()?
+                    .last()?
+                    .get("text")?
+                    .as_str()?;
+                self.documents.insert(
+                    uri.clone(),
+                    Document::new(path_from_uri(&uri), text.to_string()),
+                );
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+            }
+            _ => {
+                return None;
+            }
+        }
+
+        Some(uri)
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: &str,
+        output: &mut impl Write,
+    ) -> anyhow::Result<()> {
+        // If the document was closed, this clears its diagnostics.
+        let diagnostics = self
+            .documents
+            .get(uri)
+            .map(|document| {
+                document
+                    .code
+                    .diagnostics
+                    .iter()
+                    .filter(|diagnostic| {
+                        // Diagnostics within imported modules belong to the
+                        // documents of those modules.
+                        diagnostic.span.is_none_or(|span| {
+                            span.module == Module::main_index()
+                        })
+                    })
+                    .map(|diagnostic| {
+                        let span = diagnostic.span.unwrap_or_default();
+
+                        let severity = match diagnostic.severity {
+                            Severity::Error => 1,
+                            Severity::Warning => 2,
+                        };
+
+                        let mut message = diagnostic.message.clone();
+                        for note in &diagnostic.notes {
+                            message.push_str("\n\nNote: ");
+                            message.push_str(note);
+                        }
+
+                        json!({
+                            "range": range(document, &span),
+                            "severity": severity,
+                            "source": "crosscut",
+                            "message": message,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        send_notification(
+            output,
+            "textDocument/publishDiagnostics",
+            json!({
+                "uri": uri,
+                "diagnostics": diagnostics,
+            }),
+        )?;
+
+        Ok(())
+    }
+
+    fn document<'r>(
+        &'r self,
+        params: &'r Value,
+    ) -> Result<(&'r str, &'r Document), RequestError> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RequestError::invalid_params("Expected URI"))?;
+        let document = self.documents.get(uri).ok_or_else(|| {
+            RequestError::invalid_params("Document is not open")
+        })?;
+
+        Ok((uri, document))
+    }
+
+    fn document_position<'r>(
+        &'r self,
+        params: &'r Value,
+    ) -> Result<(&'r str, &'r Document, usize), RequestError> {
+        let (uri, document) = self.document(params)?;
+
+        let [line, character] =
+            ["/position/line", "/position/character"].map(|pointer| {
+                params
+                    .pointer(pointer)
+                    .and_then(Value::as_u64)
+                    .and_then(|value| usize::try_from(value).ok())
+            });
+        let (Some(line), Some(character)) = (line, character) else {
+            return Err(RequestError::invalid_params("Expected position"));
+        };
+
+        let byte = document.to_byte(&LspPosition { line, character });
+
+        Ok((uri, document, byte))
+    }
+}
+
+struct RequestError {
+    code: i64,
+    message: String,
+}
+
+impl RequestError {
+    fn invalid_params(message: &str) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+        }
+    }
+}
+
+fn location(uri: &str, document: &Document, span: &Span) -> Value {
+    // Spans within imported modules refer to other files.
+    let uri = if span.module == Module::main_index() {
+        uri.to_string()
+    } else {
+        document
+            .sources
+            .get(&span.module)
+            .map(|file| format!("file://{}", file.path.display()))
+            .unwrap_or_else(|| uri.to_string())
+    };
+
+    json!({
+        "uri": uri,
+        "range": range(document, span),
+    })
+}
+
+/// # Convert a `file://` URI into a path
+///
+/// Returns `None`, if the URI refers to something other than a file.
+///
+/// ## Implementation Note
+///
+/// This doesn't decode percent-encoded characters, so it only works for paths
+/// that don't contain any characters that need encoding.
+fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    uri.strip_prefix("file://").map(PathBuf::from)
+}
+
+fn range(document: &Document, span: &Span) -> Value {
+    let [start, end] = document.to_lsp_range(span).map(|position| {
+        json!({
+            "line": position.line,
+            "character": position.character,
+        })
+    });
+
+    json!({
+        "start": start,
+        "end": end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use serde_json::{json, Value};
+
+    use super::run;
+
+    #[test]
+    fn answer_requests_and_publish_diagnostics() {
+        let messages = [
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {},
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": {
+                    "textDocument": {
+                        "uri": "untitled:a",
+                        "text": "main: fn\n    br ->\n        ä\n    end\nend\n",
+                    },
+                },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "exit",
+            }),
+        ];
+        let input = messages
+            .iter()
+            .map(|message| {
+                let content = message.to_string();
+                format!("Content-Length: {}\r\n\r\n{content}", content.len())
+            })
+            .collect::<String>();
+
+        let mut output = Vec::new();
+        run(BufReader::new(input.as_bytes()), &mut output).unwrap();
+
+        let [response, diagnostics] = &read_all(&output)[..] else {
+            panic!("Expected a response and a notification");
+        };
+        assert_eq!(response["id"], json!(1));
+        assert_eq!(
+            diagnostics["method"],
+            json!("textDocument/publishDiagnostics"),
+        );
+
+        let range = &diagnostics["params"]["diagnostics"][0]["range"];
+        assert_eq!(range["start"], json!({ "line": 2, "character": 8 }));
+        assert_eq!(range["end"], json!({ "line": 2, "character": 9 }));
+    }
+
+    fn read_all(mut output: &[u8]) -> Vec<Value> {
+        let mut messages = Vec::new();
+
+        while !output.is_empty() {
+            let text = std::str::from_utf8(output).unwrap();
+            let (header, rest) = text.split_once("\r\n\r\n").unwrap();
+            let content_length = header
+                .strip_prefix("Content-Length: ")
+                .unwrap()
+                .parse::<usize>()
+                .unwrap();
+
+            let content = &rest.as_bytes()[..content_length];
+            messages.push(serde_json::from_slice(content).unwrap());
+
+            output = &rest.as_bytes()[content_length..];
+        }
+
+        messages
+    }
+}
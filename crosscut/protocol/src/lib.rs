@@ -1,5 +1,6 @@
 pub mod command;
 pub mod host_state;
+pub mod snapshot;
 pub mod updates;
 
 /// The size of the updates buffer
@@ -0,0 +1,19 @@
+use crosscut_compiler::{code::Hash, Instructions};
+use crosscut_game_engine::history::Snapshot;
+
+/// # A snapshot of the game, as it is stored by the server
+///
+/// A snapshot only makes sense in combination with the code that was running
+/// when it was taken. That code isn't part of the file, but its hash is. This
+/// makes it possible to refuse restoring a snapshot, after the code changed.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct SnapshotFile {
+    /// # The hash of the code that was running when the snapshot was taken
+    ///
+    /// This is the code as the compiler produced it, without any breakpoints
+    /// applied.
+    pub code: Hash<Instructions>,
+
+    /// # The snapshot itself
+    pub snapshot: Snapshot,
+}
@@ -1,4 +1,8 @@
-use crosscut_game_engine::memory::Memory;
+use crosscut_game_engine::{
+    history::{Snapshot, Timeline},
+    memory::Memory,
+    recording::RecordedEvent,
+};
 use crosscut_runtime::{Runtime, RuntimeState};
 
 use crate::host_state::HostState;
@@ -11,7 +15,12 @@ pub struct Updates {
 }
 
 impl Updates {
-    pub fn queue_updates(&mut self, runtime: &Runtime, memory: &Memory) {
+    pub fn queue_updates(
+        &mut self,
+        runtime: &Runtime,
+        memory: &Memory,
+        timeline: Timeline,
+    ) {
         self.latest_memory = Some(memory.clone());
 
         if self.update_is_necessary(runtime) {
@@ -36,6 +45,7 @@ impl Updates {
             };
 
             self.queue.push(UpdateFromHost::State { state });
+            self.queue.push(UpdateFromHost::Timeline { timeline });
 
             if let Some(memory) = self.latest_memory.take() {
                 self.queue.push(UpdateFromHost::Memory { memory });
@@ -43,6 +53,23 @@ impl Updates {
         }
     }
 
+    pub fn queue_snapshot(&mut self, snapshot: Snapshot) {
+        self.queue.push(UpdateFromHost::Snapshot { snapshot });
+    }
+
+    pub fn queue_recording(&mut self, events: Vec<RecordedEvent>) {
+        self.queue.push(UpdateFromHost::Recording { events });
+    }
+
+    /// # Queue the messages that tracepoints have logged
+    ///
+    /// Does nothing, if there are no messages.
+    pub fn queue_trace(&mut self, messages: Vec<String>) {
+        if !messages.is_empty() {
+            self.queue.push(UpdateFromHost::Trace { messages });
+        }
+    }
+
     pub fn take_queued_updates(
         &mut self,
     ) -> impl Iterator<Item = UpdateFromHost> + '_ {
@@ -73,6 +100,10 @@ impl Updates {
 pub enum UpdateFromHost {
     State { state: HostState },
     Memory { memory: Memory },
+    Timeline { timeline: Timeline },
+    Snapshot { snapshot: Snapshot },
+    Recording { events: Vec<RecordedEvent> },
+    Trace { messages: Vec<String> },
 }
 
 impl UpdateFromHost {
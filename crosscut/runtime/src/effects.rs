@@ -1,7 +1,8 @@
 use core::num::TryFromIntError;
 
 use crate::{
-    operands::PopOperandError, stack::PushStackFrameError,
+    operands::PopOperandError,
+    stack::{PushOperandError, PushStackFrameError},
     value::IntegerOverflow,
 };
 
@@ -62,11 +63,18 @@ impl TriggeredEffect {
     serde::Deserialize,
     serde::Serialize,
     thiserror::Error,
+    udigest::Digestable,
 )]
 pub enum Effect {
     #[error("Breakpoint")]
     Breakpoint,
 
+    /// The host has stopped a process that evaluated too many instructions
+    ///
+    /// The runtime itself never triggers this effect. See `Runtime::run`.
+    #[error("Instruction budget exhausted")]
+    BudgetExhausted,
+
     #[error("Hit instruction that was generated from invalid Crosscut code")]
     BuildError,
 
@@ -82,9 +90,6 @@ pub enum Effect {
     #[error("Invalid argument")]
     InvalidArgument,
 
-    #[error("Invalid function")]
-    InvalidFunction,
-
     #[error("Invalid host effect")]
     InvalidHostEffect,
 
@@ -100,6 +105,12 @@ pub enum Effect {
         source: PopOperandError,
     },
 
+    #[error(transparent)]
+    PushOperand {
+        #[from]
+        source: PushOperandError,
+    },
+
     #[error(transparent)]
     PushStackFrame {
         #[from]
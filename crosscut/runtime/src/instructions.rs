@@ -1,9 +1,9 @@
-use alloc::{collections::BTreeSet, string::String, vec::Vec};
 use core::fmt;
 
-use crate::{Branch, Effect, Function, Value};
+use crate::{Effect, Value};
 
 /// # The instructions that the runtime executes
+#[derive(Clone, Copy)]
 pub struct Instructions<'r> {
     pub inner: &'r [(InstructionAddress, Instruction)],
 }
@@ -28,6 +28,7 @@ impl Instructions<'_> {
     PartialOrd,
     serde::Deserialize,
     serde::Serialize,
+    udigest::Digestable,
 )]
 pub struct InstructionAddress {
     pub index: u32,
@@ -59,7 +60,15 @@ impl fmt::Display for InstructionAddress {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    udigest::Digestable,
+)]
 pub enum Instruction {
     /// # Add two signed 8-bit integers, triggering an error on overflow
     AddS8,
@@ -73,86 +82,33 @@ pub enum Instruction {
     /// # Add two unsigned 8-bit integers, wrapping on overflow
     AddU8Wrap,
 
-    /// # Bind a value to a name
-    ///
-    /// ## Implementation Note
-    ///
-    /// This is one of those high-level instructions that I'd like to get rid
-    /// of, eventually. There's no need to know about the names of values at
-    /// runtime.
-    ///
-    /// The compiler should just keep track of all names and the respective
-    /// value's locations on the stack at compile-time. This requires changes to
-    /// the instruction set, either in the form of an instruction that can copy
-    /// any value to the top of the stack, or new means to specify which values
-    /// a given instruction targets.
-    Bind {
-        name: String,
-    },
+    /// # Convert a signed 32-bit number to a signed 8-bit number
+    ConvertS32ToS8,
 
-    /// # Push the value that is bound to the provided name to the stack
-    ///
-    /// ## Implementation Note
-    ///
-    /// This is one of those high-level instructions that I'd like to get rid
-    /// of, eventually. There's no need to know about the names of values at
-    /// runtime.
-    ///
-    /// The compiler should just keep track of all names and the respective
-    /// value's locations on the stack at compile-time. This requires changes to
-    /// the instruction set, either in the form of an instruction that can copy
-    /// any value to the top of the stack, or new means to specify which values
-    /// a given instruction targets.
-    BindingEvaluate {
-        name: String,
-    },
+    /// # Convert a signed 32-bit number to an unsigned 8-bit number
+    ConvertS32ToU8,
 
-    /// # Call a function, selecting the right branch via pattern matching
-    ///
-    /// ## Implementation Note
-    ///
-    /// This instruction is overly complex. I think ideally, there would be two
-    /// simple instructions instead:
-    ///
-    /// - `PushFrame`, to push a new stack frame, and do nothing else with it.
-    /// - `ReuseFrame`, to prepare the existing stack frame for reuse. (If
-    ///   bindings were just regular values whose addresses were managed by the
-    ///   compiler, instead of being a special thing handled by the stack at
-    ///   runtime, then we probably wouldn't need this at all, and could reuse
-    ///   any stack frame without preparation. That's a different story,
-    ///   however.)
-    ///
-    /// Those would just take a single address as an argument, and jump there
-    /// directly. (And maybe even pushing a frame and jumping to an instructions
-    /// could and should be decoupled eventually.)
-    ///
-    /// Pattern matching could move into the branches themselves. The compiler
-    /// could generate the necessary code as required, using the following
-    /// approach:
+    /// # Convert a signed 32-bit number to an unsigned 32-bit number
+    ConvertS32ToU32,
+
+    /// # Convert an unsigned 8-bit number to a signed 32-bit number
+    ConvertU8ToS32,
+
+    /// # Convert an unsigned 32-bit number to a signed 32-bit number
+    ConvertU32ToS32,
+
+    /// # Compare an operand to a value
     ///
-    /// - If there are no literal patterns among the arguments, just generate
-    ///   nothing.
-    /// - If there are literal patterns, generate code that compares them
-    ///   against the call stack.
-    ///   - If all patterns match, remove the respective operands from the
-    ///     stack and continue with the rest of the branch as normal. (Although
-    ///     probably the next step would be the compiler-generated code that
-    ///     handles the arguments.
-    ///   - If the patterns don't match, leave the operands as-is and jump
-    ///     directly to the next branch, which continues with its own pattern
-    ///     matching.
+    /// The operand is identified by an offset from the top of the stack. It
+    /// stays where it is, and the result of the comparison is pushed on top.
     ///
-    /// This would require some new instructions for doing the comparison and
-    /// jumping to another address, but that seems like the right direction to
-    /// go in anyway.
-    CallFunction {
-        callee: Function,
-        is_tail_call: bool,
+    /// This is used by the compiler to generate pattern matching code, which
+    /// is why it takes its arguments directly, instead of from the stack.
+    CompareOperand {
+        offset_from_top: u32,
+        value: Value,
     },
 
-    /// # Convert a signed 32-bit number to a signed 8-bit number
-    ConvertS32ToS8,
-
     /// # Copy a value on the stack to the top of the stack
     ///
     /// The value to copy is identified by an offset from the top of the stack,
@@ -171,23 +127,16 @@ pub enum Instruction {
     /// # Compare two values for equality
     Eq,
 
-    /// # Evaluate an anonymous function
-    ///
-    /// The top value on the stack is interpreted as the index of the anonymous
-    /// function. If it doesn't identify an anonymous function (because the
-    /// anonymous function was evaluated previously, or such an index never
-    /// existed), an error is triggered.
+    /// # Evaluate a function
     ///
-    /// ## Implementation Note
+    /// The top value on the stack is interpreted as the address of the
+    /// function. Like `PushFrame` or `ReuseFrame`, depending on whether this is
+    /// a tail call, this instruction prepares a stack frame, then jumps to that
+    /// address.
     ///
-    /// This instruction is too high-level, and it's partially redundant with
-    /// other high-level instructions. The duplicated code in their
-    /// implementations within `Evaluator` is supporting evidence of this.
-    ///
-    /// Like other instructions, it needs to be replaced by smaller, more
-    /// low-level ones. This requires the compiler to become smarter. Which is
-    /// the direction I'd like things to go into anyway, but it hasn't fully
-    /// happened yet.
+    /// Any values that the function captured from its environment are right
+    /// below the address on the stack. The function's own code moves them into
+    /// its slots.
     Eval {
         is_tail_call: bool,
     },
@@ -201,37 +150,28 @@ pub enum Instruction {
     /// # Determine if the first of two unsigned 8-bit numbers is greater
     GreaterU8,
 
+    /// # Jump to the provided address, if the top operand is zero
+    ///
+    /// Pops the top operand either way. If it is not zero, evaluation just
+    /// continues with the next instruction.
+    JumpIfZero {
+        address: InstructionAddress,
+    },
+
+    /// # Push the value from a slot in the current stack frame to the stack
+    ///
+    /// Slots hold the values of bindings. The compiler decides which binding
+    /// goes into which slot.
+    LoadSlot {
+        index: u32,
+    },
+
     /// # Logical and
     LogicalAnd,
 
     /// # Logical not
     LogicalNot,
 
-    /// # Create an anonymous function
-    ///
-    /// ## Implementation Note
-    ///
-    /// Anonymous functions and their environment are currently allocated in a
-    /// special map within the evaluator. This instruction is required to make
-    /// that work.
-    ///
-    /// But this situation is undesirable. Anonymous functions should not be
-    /// boxed. They should just be regular values, with everything needed to
-    /// make them work allocated on the stack.
-    ///
-    /// Right now, this is not feasible, because the language is untyped and
-    /// every value is a 32-bit word. Once we have static typing and the ability
-    /// to create composite values, anonymous functions should be represented
-    /// using those.
-    ///
-    /// When this is the case, special handling at runtime will no longer be
-    /// required. Then all logic specific to anonymous functions can live in the
-    /// compiler, and this instruction can be removed.
-    MakeAnonymousFunction {
-        branches: Vec<Branch>,
-        environment: BTreeSet<String>,
-    },
-
     /// # Multiply two signed 32-bit numbers, triggering an error on overflow
     MulS32,
 
@@ -248,11 +188,44 @@ pub enum Instruction {
         value: Value,
     },
 
+    /// # Push a new stack frame, then jump to the provided address
+    ///
+    /// The new stack frame returns to the instruction after this one.
+    ///
+    /// ## Implementation Note
+    ///
+    /// Pushing a frame and jumping to an instruction could be decoupled, but
+    /// there's no need for that yet.
+    PushFrame {
+        address: InstructionAddress,
+    },
+
     /// # Compute the remainder of the division of two signed 32-bit numbers
     RemainderS32,
 
     Return,
 
+    /// # Prepare the current stack frame for reuse, then jump to an address
+    ///
+    /// This is how tail calls are implemented.
+    ///
+    /// ## Implementation Note
+    ///
+    /// Preparing the stack frame just means clearing its slots. The compiler
+    /// never loads from a slot that it hasn't stored to before, so this isn't
+    /// strictly necessary. But it keeps stale values from piling up, and it's
+    /// cheap.
+    ReuseFrame {
+        address: InstructionAddress,
+    },
+
+    /// # Pop a value and store it in a slot in the current stack frame
+    ///
+    /// See `LoadSlot`.
+    StoreSlot {
+        index: u32,
+    },
+
     /// # Subtract two signed 32-bit numbers, triggering an error on overflow
     SubS32,
 
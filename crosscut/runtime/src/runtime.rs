@@ -1,5 +1,6 @@
 use crate::{
-    evaluator::Evaluator, Heap, Instructions, Stack, TriggeredEffect, Value,
+    evaluator::Evaluator, Instructions, Stack, StackLimits, TriggeredEffect,
+    Value,
 };
 
 #[derive(
@@ -11,6 +12,19 @@ pub struct Runtime {
 }
 
 impl Runtime {
+    /// # Create a runtime whose stack enforces the provided limits
+    ///
+    /// Use [`Runtime::default`] to create a runtime with the default limits.
+    pub fn with_stack_limits(limits: StackLimits) -> Self {
+        Self {
+            effect: TriggeredEffect::default(),
+            evaluator: Evaluator {
+                stack: Stack::with_limits(limits),
+                ..Evaluator::default()
+            },
+        }
+    }
+
     pub fn state(&self) -> RuntimeState {
         if self.effect.inspect().is_some() {
             RuntimeState::Stopped
@@ -29,6 +43,18 @@ impl Runtime {
         &mut self.effect
     }
 
+    /// # Access the values that the process returned
+    ///
+    /// These are the operands that remain on the stack, after the last stack
+    /// frame has returned. Returns `None`, if the process hasn't finished yet.
+    pub fn return_values(&self) -> Option<impl Iterator<Item = Value> + '_> {
+        if self.evaluator.stack.no_frames_left() {
+            Some(self.evaluator.stack.operands().copied())
+        } else {
+            None
+        }
+    }
+
     pub fn evaluator(&self) -> &Evaluator {
         &self.evaluator
     }
@@ -41,24 +67,26 @@ impl Runtime {
         &mut self.evaluator.stack
     }
 
+    /// # Reset the runtime, keeping its stack limits
+    ///
+    /// If the arguments don't fit on the stack, this triggers an effect.
     pub fn reset(&mut self, arguments: impl IntoIterator<Item = Value>) {
-        *self = Self::default();
+        *self = Self::with_stack_limits(self.stack().limits());
 
         for argument in arguments {
-            self.evaluator.stack.push_operand(argument);
+            if let Err(err) = self.evaluator.stack.push_operand(argument) {
+                self.effect.trigger(err).assert_triggered();
+                break;
+            }
         }
     }
 
-    pub fn evaluate_next_instruction(
-        &mut self,
-        instructions: Instructions,
-        heap: &mut Heap,
-    ) {
+    pub fn evaluate_next_instruction(&mut self, instructions: Instructions) {
         if !self.state().is_running() {
             return;
         }
 
-        if let Err(effect) = self.evaluator.step(instructions, heap) {
+        if let Err(effect) = self.evaluator.step(instructions) {
             self.effect
                 .trigger(effect)
                 // If there already was an effect, we would have left the
@@ -68,6 +96,26 @@ impl Runtime {
         }
     }
 
+    /// # Evaluate instructions, until the runtime stops or the budget runs out
+    ///
+    /// Evaluates instructions, as long as the runtime is running, but no more
+    /// than `budget` of them. The budget is reduced by the number of evaluated
+    /// instructions.
+    ///
+    /// If the budget is zero after this method returns, but the runtime is
+    /// still running, it has been preempted. This gives the host the
+    /// opportunity to do other work (like staying responsive to a debugger),
+    /// before it continues evaluation by calling this method again.
+    ///
+    /// Hosts that want to stop a process that runs for too long, can do so by
+    /// triggering [`Effect::BudgetExhausted`](crate::Effect::BudgetExhausted).
+    pub fn run(&mut self, instructions: Instructions, budget: &mut u32) {
+        while *budget > 0 && self.state().is_running() {
+            self.evaluate_next_instruction(instructions);
+            *budget -= 1;
+        }
+    }
+
     /// # Ignore the next instruction
     ///
     /// This advances the instruction that the evaluator is going to evaluate
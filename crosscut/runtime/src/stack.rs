@@ -1,55 +1,103 @@
-use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use alloc::{vec, vec::Vec};
 
 use crate::{operands::PopOperandError, InstructionAddress, Value};
 
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Stack {
     inner: Vec<StackElement>,
+    limits: StackLimits,
+    num_frames: usize,
+    num_operands: usize,
 }
 
 impl Stack {
     pub fn new() -> Self {
+        Self::with_limits(StackLimits::default())
+    }
+
+    pub fn with_limits(limits: StackLimits) -> Self {
         Self {
             inner: vec![
                 StackElement::StartMarker,
-                StackElement::Bindings(Bindings::new()),
+                StackElement::Slots(Vec::new()),
             ],
+            limits,
+            num_frames: 1,
+            num_operands: 0,
         }
     }
 
+    pub fn limits(&self) -> StackLimits {
+        self.limits
+    }
+
     /// # Determine wether any stack frames are left
     ///
     /// The stack starts out with an initial stack frame on initialization. If
     /// no more stack frames are left, this means that the process is finished.
     ///
-    /// ## Implementation Note
+    /// The stack doesn't need to be empty at that point. Any operands that the
+    /// initial stack frame left behind, are the return values of the process.
+    pub fn no_frames_left(&self) -> bool {
+        self.num_frames == 0
+    }
+
+    /// # Load the value from a slot in the current stack frame
+    ///
+    /// Returns `None`, if the slot hasn't been allocated by a store yet.
+    pub fn load_slot(&self, index: usize) -> Option<Value> {
+        self.slots()?.get(index).copied()
+    }
+
+    /// # Store a value in a slot of the current stack frame
     ///
-    /// In principle, no stack frames being left does not mean that the stack is
-    /// empty. The process could have left some (frame-less) return value, and a
-    /// host might expect such a thing.
+    /// Slots are allocated as needed. Any slots before the one that is stored
+    /// to, which haven't been stored to yet, are filled with zeros.
+    pub fn store_slot(&mut self, index: usize, value: impl Into<Value>) {
+        let slots = self.slots_mut();
+
+        if slots.len() <= index {
+            slots.resize(index + 1, Value::from(0));
+        }
+
+        slots[index] = value.into();
+    }
+
+    /// # Access the slots of the current stack frame
     ///
-    /// But right now, this method checks whether the stack is completely empty.
-    /// This is a bug, which is tracked here:
-    /// <https://github.com/hannobraun/crosscut/issues/44>
-    pub fn no_frames_left(&self) -> bool {
-        self.inner.is_empty()
+    /// Returns an empty slice, if no stack frame is left.
+    pub fn current_slots(&self) -> &[Value] {
+        self.slots().map(Vec::as_slice).unwrap_or_default()
     }
 
-    pub fn bindings(&self) -> Option<&Bindings> {
+    fn slots(&self) -> Option<&Vec<Value>> {
         self.inner.iter().rev().find_map(|element| match element {
-            StackElement::Bindings(bindings) => Some(bindings),
+            StackElement::Slots(slots) => Some(slots),
             _ => None,
         })
     }
 
-    pub fn bindings_mut(&mut self) -> Option<&mut Bindings> {
-        self.inner
-            .iter_mut()
-            .rev()
-            .find_map(|element| match element {
-                StackElement::Bindings(bindings) => Some(bindings),
-                _ => None,
-            })
+    fn slots_mut(&mut self) -> &mut Vec<Value> {
+        let slots =
+            self.inner
+                .iter_mut()
+                .rev()
+                .find_map(|element| match element {
+                    StackElement::Slots(slots) => Some(slots),
+                    _ => None,
+                });
+
+        let Some(slots) = slots else {
+            panic!(
+                "Trying to access slots, but none are available. This implies \
+                that no stack frame is available.\n\
+                \n\
+                But one _should_ be available, as that is always the case, \
+                unless the runtime has finished running."
+            );
+        };
+
+        slots
     }
 
     /// # Iterate over the operands on the stack, from the base
@@ -75,17 +123,17 @@ impl Stack {
     ) -> Result<(), PushStackFrameError> {
         // Not a tail call. This means we need to create a new stack frame.
         // Let's first check if we can even do that.
-        const STACK_LIMIT: usize = 32;
-        if self.inner.len() >= STACK_LIMIT {
+        if self.num_frames >= self.limits.frames {
             return Err(PushStackFrameError::Overflow);
         }
+        self.num_frames += 1;
 
         // All stack frames but the initial one (which this one can't be, as the
         // initial one is created with the stack), start with a return address.
         self.inner.push(StackElement::ReturnAddress(return_address));
 
-        // And all stack frames need a map of bindings.
-        self.inner.push(StackElement::Bindings(Bindings::new()));
+        // And all stack frames need slots to store the values of bindings in.
+        self.inner.push(StackElement::Slots(Vec::new()));
 
         Ok(())
     }
@@ -101,25 +149,9 @@ impl Stack {
         // returns, so they can remain and be returned when the stack frame is
         // eventually done.
         //
-        // But we need to handle bindings.
-
-        let Some(bindings) = self.bindings_mut() else {
-            panic!(
-                "Trying to access bindings, but none are available. This \
-                implies that no stack frame is available.\n\
-                \n\
-                But one _should_ be available, as that is always the case, \
-                unless the runtime has finished running. Right now, we're \
-                trying to reuse a stack frame.\n\
-                \n\
-                Current stack:\n\
-                {self:#?}",
-            );
-        };
-
-        // Any bindings that remain are no longer accessible, so let's remove
-        // them.
-        bindings.clear();
+        // But we need to handle the slots. Any values that remain in there are
+        // no longer accessible, so let's remove them.
+        self.slots_mut().clear();
     }
 
     pub fn pop_frame(&mut self) -> Option<InstructionAddress> {
@@ -133,15 +165,17 @@ impl Stack {
             index -= 1;
 
             match self.inner[index] {
-                StackElement::Bindings(_) => {
+                StackElement::Slots(_) => {
                     self.inner.remove(index);
                 }
                 StackElement::ReturnAddress(address) => {
                     self.inner.remove(index);
+                    self.num_frames -= 1;
                     break Some(address);
                 }
                 StackElement::StartMarker => {
                     self.inner.remove(index);
+                    self.num_frames -= 1;
                     break None;
                 }
                 _ => {}
@@ -149,14 +183,18 @@ impl Stack {
         }
     }
 
-    pub fn define_binding(&mut self, name: String, value: impl Into<Value>) {
-        self.bindings_mut()
-            .expect("Expected stack frame to exist")
-            .insert(name, value.into());
-    }
+    pub fn push_operand(
+        &mut self,
+        operand: impl Into<Value>,
+    ) -> Result<(), PushOperandError> {
+        if self.num_operands >= self.limits.operands {
+            return Err(PushOperandError::Overflow);
+        }
+        self.num_operands += 1;
 
-    pub fn push_operand(&mut self, operand: impl Into<Value>) {
         self.inner.push(StackElement::Operand(operand.into()));
+
+        Ok(())
     }
 
     pub fn pop_operand(&mut self) -> Result<Value, PopOperandError> {
@@ -166,6 +204,7 @@ impl Stack {
 
             if let StackElement::Operand(value) = self.inner[index] {
                 self.inner.remove(index);
+                self.num_operands -= 1;
                 return Ok(value);
             }
         }
@@ -184,6 +223,33 @@ impl Default for Stack {
     }
 }
 
+/// # The limits that a [`Stack`] enforces
+///
+/// Stack frames and operands are counted separately, so a deep chain of
+/// function calls doesn't run out of space because of the operands that the
+/// calling functions left on the stack, and vice versa. The slots that hold the
+/// values of bindings are part of their stack frame, and don't count towards
+/// either limit.
+#[derive(
+    Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize,
+)]
+pub struct StackLimits {
+    /// # The maximum number of stack frames, including the initial one
+    pub frames: usize,
+
+    /// # The maximum number of operands, across all stack frames
+    pub operands: usize,
+}
+
+impl Default for StackLimits {
+    fn default() -> Self {
+        Self {
+            frames: 256,
+            operands: 1024,
+        }
+    }
+}
+
 /// # The things that can be on the stack
 ///
 /// ## Implementation Note
@@ -197,21 +263,15 @@ impl Default for Stack {
 /// longer need to track this kind of type information at runtime.
 #[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum StackElement {
-    /// # The bindings in the current stack frame
+    /// # The slots of the current stack frame
     ///
     /// There should be one of these per stack frame, and it's expected that
     /// this is the first element after the return address.
     ///
-    /// ## Implementation Note
-    ///
-    /// Having a map with the names and values of bindings at runtime is
-    /// unnecessary. We need the value, sure, but the compiler can track the
-    /// names and locations of bindings at compile-time.
-    ///
-    /// At some point it will learn to do that, and generate the right
-    /// instructions to access them. Then we won't need to look up bindings by
-    /// name at runtime.
-    Bindings(Bindings),
+    /// Slots hold the values of bindings. The compiler decides which binding
+    /// goes into which slot, so nothing about the bindings themselves needs to
+    /// be known at runtime.
+    Slots(Vec<Value>),
 
     /// An operand
     Operand(Value),
@@ -236,8 +296,6 @@ pub enum StackElement {
     StartMarker,
 }
 
-pub type Bindings = BTreeMap<String, Value>;
-
 #[derive(
     Clone,
     Copy,
@@ -247,11 +305,28 @@ pub type Bindings = BTreeMap<String, Value>;
     serde::Deserialize,
     serde::Serialize,
     thiserror::Error,
+    udigest::Digestable,
 )]
 pub enum PushStackFrameError {
-    #[error("Reached recursion limit")]
+    #[error("Reached stack frame limit")]
     Overflow,
 
     #[error("Evaluator is already finished")]
     Finished,
 }
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    thiserror::Error,
+    udigest::Digestable,
+)]
+pub enum PushOperandError {
+    #[error("Reached operand limit")]
+    Overflow,
+}
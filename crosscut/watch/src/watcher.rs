@@ -1,4 +1,9 @@
-use std::{env, path::Path};
+use std::{
+    collections::BTreeSet,
+    env,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use anyhow::Context;
 use notify::{Event, EventKind, RecursiveMode, Watcher as _};
@@ -8,40 +13,18 @@ use tracing::error;
 use super::debounce::DebouncedChanges;
 
 pub struct Watcher {
-    // This field is not used, but we need to keep it around. If we drop the
-    // `notify` watcher, it stops watching.
-    _watcher: notify::RecommendedWatcher,
+    watcher: notify::RecommendedWatcher,
+    filter: Arc<Mutex<Filter>>,
+    directories: BTreeSet<PathBuf>,
     pub changes: DebouncedChanges,
 }
 
 impl Watcher {
+    /// # Create a watcher for everything within the provided directory
     pub fn new(path: &Path) -> anyhow::Result<Self> {
-        let (tx, rx) = watch::channel(());
-
-        let mut watcher = notify::recommended_watcher(move |event| {
-            match event {
-                Ok(Event {
-                    kind: EventKind::Access(_),
-                    ..
-                }) => {
-                    // We're not interested in read access to any files.
-                    return;
-                }
-                Err(err) => {
-                    error!("Error watching for changes: {err}");
-                    return;
-                }
-                _ => {
-                    // This is the kind of event we want to watch. Proceed.
-                }
-            }
-
-            if tx.send(()).is_err() {
-                // The other end has hung up. Not much we can do about that. The
-                // thread this is running on will probably also end soon.
-            }
-        })?;
+        let mut watcher = Self::with_filter(Filter::All)?;
         watcher
+            .watcher
             .watch(path, RecursiveMode::Recursive)
             .with_context(|| match path.canonicalize() {
                 Ok(path) => {
@@ -63,11 +46,138 @@ impl Watcher {
                 }
             })?;
 
+        Ok(watcher)
+    }
+
+    /// # Create a watcher for specific files
+    ///
+    /// The watcher doesn't watch any files initially. Use
+    /// [`Watcher::watch_files`] to specify them.
+    pub fn for_files() -> anyhow::Result<Self> {
+        Self::with_filter(Filter::Files(BTreeSet::new()))
+    }
+
+    fn with_filter(filter: Filter) -> anyhow::Result<Self> {
+        let (tx, rx) = watch::channel(());
+
+        let filter = Arc::new(Mutex::new(filter));
+
+        let watcher = notify::recommended_watcher({
+            let filter = filter.clone();
+
+            move |event| {
+                let paths = match event {
+                    Ok(Event {
+                        kind: EventKind::Access(_),
+                        ..
+                    }) => {
+                        // We're not interested in read access to any files.
+                        return;
+                    }
+                    Err(err) => {
+                        error!("Error watching for changes: {err}");
+                        return;
+                    }
+                    Ok(Event { paths, .. }) => {
+                        // This is the kind of event we want to watch. Proceed.
+                        paths
+                    }
+                };
+
+                let is_watched = match &*filter.lock().unwrap() {
+                    Filter::All => true,
+                    Filter::Files(files) => {
+                        paths.iter().any(|path| files.contains(path))
+                    }
+                };
+
+                if !is_watched {
+                    // Something else in one of the watched directories has
+                    // changed.
+                    return;
+                }
+
+                if tx.send(()).is_err() {
+                    // The other end has hung up. Not much we can do about
+                    // that. The thread this is running on will probably also
+                    // end soon.
+                }
+            }
+        })?;
+
         let changes = DebouncedChanges::new(rx);
 
         Ok(Self {
-            _watcher: watcher,
+            watcher,
+            filter,
+            directories: BTreeSet::new(),
             changes,
         })
     }
+
+    /// # Watch the provided files, instead of any previously watched ones
+    ///
+    /// The files don't need to exist. If they are created later, that counts as
+    /// a change.
+    ///
+    /// ## Implementation Note
+    ///
+    /// This watches the directories that contain the files, not the files
+    /// themselves. Many editors save a file by replacing it with a new one,
+    /// and a watch on the file itself wouldn't survive that.
+    pub fn watch_files(&mut self, files: &[PathBuf]) -> anyhow::Result<()> {
+        let mut watched_files = BTreeSet::new();
+        let mut directories = BTreeSet::new();
+
+        for file in files {
+            let (Some(directory), Some(file_name)) =
+                (file.parent(), file.file_name())
+            else {
+                continue;
+            };
+
+            // The watcher reports paths relative to the directories we
+            // watch. Canonicalize them, so the paths we compare against are
+            // consistent with that.
+            let Ok(directory) = canonicalize(directory) else {
+                // If the directory doesn't exist, neither can the file.
+                continue;
+            };
+
+            watched_files.insert(directory.join(file_name));
+            directories.insert(directory);
+        }
+
+        for directory in self.directories.difference(&directories) {
+            // If the directory has been removed, it's no longer being watched
+            // anyway. Nothing else could go wrong here that would matter.
+            let _ = self.watcher.unwatch(directory);
+        }
+        for directory in directories.difference(&self.directories) {
+            self.watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .with_context(|| {
+                    format!("Watching `{}`", directory.display())
+                })?;
+        }
+
+        self.directories = directories;
+        *self.filter.lock().unwrap() = Filter::Files(watched_files);
+
+        Ok(())
+    }
+}
+
+enum Filter {
+    All,
+    Files(BTreeSet<PathBuf>),
+}
+
+fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    if path.as_os_str().is_empty() {
+        // This is the parent of a relative path with just a single component.
+        return Path::new(".").canonicalize();
+    }
+
+    path.canonicalize()
 }